@@ -0,0 +1,106 @@
+//! Distance-2 rows built with `seen` markers against sorted-list merging.
+//!
+//! Run with `cargo bench -p tatva-coloring-core --bench distance2_rows`. Each
+//! stencil pattern is enumerated row by row on one thread, once through
+//! `for_each_neighbor` plus a sort and once through `merged_neighbors`, and
+//! the rows are checked to agree.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tatva_coloring_core::adjacency::Adjacency;
+use tatva_coloring_core::distance2::{Distance2Graph, MergeScratch};
+
+/// Pattern of a stencil of radius 1 on a grid of `dims`, the full box or only the axes.
+fn stencil(dims: &[usize], full: bool) -> Adjacency {
+    let n: usize = dims.iter().product();
+    let mut offsets: Vec<Vec<isize>> = vec![Vec::new()];
+    for _ in dims {
+        offsets = offsets
+            .into_iter()
+            .flat_map(|o| {
+                (-1..=1).map(move |d| {
+                    let mut o = o.clone();
+                    o.push(d);
+                    o
+                })
+            })
+            .collect();
+    }
+    offsets.retain(|o| full || o.iter().filter(|&&d| d != 0).count() <= 1);
+    let pairs = (0..n).flat_map(|v| {
+        let offsets = &offsets;
+        offsets.iter().filter_map(move |offset| {
+            let (mut rest, mut w, mut stride) = (v, 0, 1);
+            for (&dim, &d) in dims.iter().zip(offset) {
+                let x = (rest % dim).checked_add_signed(d).filter(|&x| x < dim)?;
+                rest /= dim;
+                w += x * stride;
+                stride *= dim;
+            }
+            Some((v, w))
+        })
+    });
+    let mut rows = Adjacency::from_pairs(n, pairs);
+    rows.sort_dedup_rows();
+    rows
+}
+
+/// Fastest of three runs of `f`.
+fn best_of(mut f: impl FnMut() -> usize) -> (Duration, usize) {
+    (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            let entries = black_box(f());
+            (start.elapsed(), entries)
+        })
+        .min()
+        .expect("three runs")
+}
+
+fn main() {
+    let patterns = [
+        ("2d 5-point", stencil(&[700, 700], false)),
+        ("2d 9-point", stencil(&[700, 700], true)),
+        ("3d 7-point", stencil(&[80, 80, 80], false)),
+        ("3d 27-point", stencil(&[50, 50, 50], true)),
+    ];
+    println!(
+        "{:<12} {:>10} {:>12} {:>12}",
+        "pattern", "entries", "markers", "merge"
+    );
+    for (name, rows) in patterns {
+        let graph = Distance2Graph::new(rows);
+        let n = graph.n_vertices();
+        let (markers, entries) = best_of(|| {
+            let mut seen = vec![usize::MAX; n];
+            let mut row = Vec::new();
+            (0..n)
+                .map(|i| {
+                    row.clear();
+                    graph.for_each_neighbor(i, &mut seen, |k| row.push(k));
+                    row.sort_unstable();
+                    row.len()
+                })
+                .sum()
+        });
+        let (merge, merged_entries) = best_of(|| {
+            let mut scratch = MergeScratch::default();
+            let mut row = Vec::new();
+            (0..n)
+                .map(|i| {
+                    graph.merged_neighbors(i, &mut scratch, &mut row);
+                    row.len()
+                })
+                .sum()
+        });
+        assert_eq!(entries, merged_entries, "{name}: methods disagree");
+        let mut scratch = MergeScratch::default();
+        let mut row = Vec::new();
+        for i in (0..n).step_by(97) {
+            graph.merged_neighbors(i, &mut scratch, &mut row);
+            assert_eq!(row, graph.adjacency()[i], "{name}: row {i} differs");
+        }
+        println!("{name:<12} {entries:>10} {markers:>12.2?} {merge:>12.2?}");
+    }
+}
@@ -0,0 +1,106 @@
+//! Acyclic coloring for symmetric sparsity patterns.
+
+use ahash::{AHashMap, AHashSet};
+
+use crate::adjacency::Adjacency;
+
+/// Disjoint sets over (vertex, color pair) nodes tracking two-colored trees.
+#[derive(Default)]
+struct BicoloredForest {
+    nodes: AHashMap<(usize, usize, usize), usize>,
+    parent: Vec<usize>,
+}
+
+impl BicoloredForest {
+    fn key(v: usize, a: usize, b: usize) -> (usize, usize, usize) {
+        (v, a.min(b), a.max(b))
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Root of the (a, b)-colored tree containing `v`, if `v` has such edges.
+    fn root(&mut self, v: usize, a: usize, b: usize) -> Option<usize> {
+        let &id = self.nodes.get(&Self::key(v, a, b))?;
+        Some(self.find(id))
+    }
+
+    fn node(&mut self, v: usize, a: usize, b: usize) -> usize {
+        let next = self.parent.len();
+        let id = *self.nodes.entry(Self::key(v, a, b)).or_insert(next);
+        if id == next {
+            self.parent.push(next);
+        }
+        id
+    }
+
+    fn union(&mut self, v: usize, w: usize, a: usize, b: usize) {
+        let rv = self.node(v, a, b);
+        let rv = self.find(rv);
+        let rw = self.node(w, a, b);
+        let rw = self.find(rw);
+        self.parent[rv] = rw;
+    }
+}
+
+/// Greedy acyclic coloring: a distance-1 coloring in which every cycle uses at least three colors.
+///
+/// Every two-colored subgraph is a forest, which is what substitution-based
+/// Hessian recovery relies on. Expects symmetric adjacency without self loops.
+pub fn acyclic_color(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut colors = vec![usize::MAX; n];
+    let mut forest = BicoloredForest::default();
+    let mut forbidden: Vec<usize> = Vec::new();
+    let mut counts: AHashMap<usize, u32> = AHashMap::new();
+    let mut roots: AHashSet<usize> = AHashSet::new();
+
+    for v in 0..n {
+        counts.clear();
+        for &w in &adjacency[v] {
+            let cw = colors[w];
+            if cw == usize::MAX {
+                continue;
+            }
+            if cw >= forbidden.len() {
+                forbidden.resize(cw + 1, usize::MAX);
+            }
+            forbidden[cw] = v;
+            *counts.entry(cw).or_insert(0) += 1;
+        }
+
+        // A candidate closes a two-colored cycle if two neighbors of the same
+        // color already sit in one tree of that color pair.
+        let mut c = 0;
+        loop {
+            if forbidden.get(c) != Some(&v) {
+                roots.clear();
+                let closes_cycle = adjacency[v].iter().any(|&w| {
+                    let cw = colors[w];
+                    if cw == usize::MAX || counts[&cw] < 2 {
+                        return false;
+                    }
+                    forest.root(w, cw, c).is_some_and(|r| !roots.insert(r))
+                });
+                if !closes_cycle {
+                    break;
+                }
+            }
+            c += 1;
+        }
+
+        colors[v] = c;
+        for &w in &adjacency[v] {
+            let cw = colors[w];
+            if cw != usize::MAX {
+                forest.union(v, w, cw, c);
+            }
+        }
+    }
+    colors
+}
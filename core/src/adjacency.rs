@@ -0,0 +1,225 @@
+//! Flat CSR storage for neighbor lists.
+
+use std::ops::Index;
+
+use rayon::prelude::*;
+
+/// Neighbor lists of `len()` vertices stored back to back in one array.
+///
+/// Row `v` is `neighbors[offsets[v]..offsets[v + 1]]`. Two allocations hold the
+/// whole graph, instead of one per vertex.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Adjacency {
+    offsets: Vec<usize>,
+    neighbors: Vec<usize>,
+}
+
+impl Adjacency {
+    /// Graph without vertices, grown with `push_row`.
+    pub fn new() -> Self {
+        Self {
+            offsets: vec![0],
+            neighbors: Vec::new(),
+        }
+    }
+
+    /// Rows of the given lengths, filled with zeros (see `rows_mut`).
+    pub fn with_degrees(degrees: &[usize]) -> Self {
+        let mut offsets = Vec::with_capacity(degrees.len() + 1);
+        offsets.push(0);
+        let mut total = 0;
+        for &d in degrees {
+            total += d;
+            offsets.push(total);
+        }
+        Self {
+            offsets,
+            neighbors: vec![0; total],
+        }
+    }
+
+    /// Graph on `n` vertices with `v` listed in row `u` for every `(u, v)` of `pairs`.
+    ///
+    /// Rows keep the order of `pairs`; `pairs` is iterated twice, once to count.
+    pub fn from_pairs<I>(n: usize, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        I::IntoIter: Clone,
+    {
+        let pairs = pairs.into_iter();
+        let mut degrees = vec![0; n];
+        for (u, _) in pairs.clone() {
+            degrees[u] += 1;
+        }
+        let mut adjacency = Self::with_degrees(&degrees);
+        // Next free slot of each row.
+        let mut fill = adjacency.offsets[..n].to_vec();
+        for (u, v) in pairs {
+            adjacency.neighbors[fill[u]] = v;
+            fill[u] += 1;
+        }
+        adjacency
+    }
+
+    /// Append a vertex with the given neighbors.
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = usize>) {
+        self.neighbors.extend(row);
+        self.offsets.push(self.neighbors.len());
+    }
+
+    /// `push_row` for fallible neighbor indices, stopping at the first error.
+    pub fn try_push_row<E>(
+        &mut self,
+        row: impl IntoIterator<Item = Result<usize, E>>,
+    ) -> Result<(), E> {
+        for w in row {
+            self.neighbors.push(w?);
+        }
+        self.offsets.push(self.neighbors.len());
+        Ok(())
+    }
+
+    /// Number of vertices.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Whether the graph has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Neighbors of `v`, or `None` if `v` is out of range.
+    pub fn get(&self, v: usize) -> Option<&[usize]> {
+        (v < self.len()).then(|| &self[v])
+    }
+
+    /// Row offsets: row `v` is `neighbors()[offsets()[v]..offsets()[v + 1]]`.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// All neighbor lists back to back, i.e. the CSR column indices.
+    pub fn neighbors(&self) -> &[usize] {
+        &self.neighbors
+    }
+
+    /// Neighbor lists in vertex order.
+    pub fn iter(&self) -> Rows<'_> {
+        Rows {
+            adjacency: self,
+            next: 0,
+        }
+    }
+
+    /// Mutable neighbor lists in vertex order, e.g. to fill rows in parallel.
+    pub fn rows_mut(&mut self) -> Vec<&mut [usize]> {
+        let mut rest = self.neighbors.as_mut_slice();
+        let mut rows = Vec::with_capacity(self.offsets.len() - 1);
+        for w in self.offsets.windows(2) {
+            let (row, tail) = std::mem::take(&mut rest).split_at_mut(w[1] - w[0]);
+            rows.push(row);
+            rest = tail;
+        }
+        rows
+    }
+
+    /// Whether every row is strictly increasing, i.e. sorted without duplicates.
+    pub fn is_canonical(&self) -> bool {
+        (0..self.len())
+            .into_par_iter()
+            .all(|v| self[v].windows(2).all(|w| w[0] < w[1]))
+    }
+
+    /// Sort every row and drop duplicate neighbors, compacting the storage.
+    ///
+    /// Rows are sorted and deduplicated in place in parallel; only moving
+    /// them together afterwards is sequential.
+    pub fn sort_dedup_rows(&mut self) {
+        let lengths: Vec<usize> = self
+            .rows_mut()
+            .into_par_iter()
+            .map(|row| {
+                row.sort_unstable();
+                let mut write = 0;
+                for read in 0..row.len() {
+                    if write == 0 || row[read] != row[write - 1] {
+                        row[write] = row[read];
+                        write += 1;
+                    }
+                }
+                write
+            })
+            .collect();
+        let mut write = 0;
+        for (v, length) in lengths.into_iter().enumerate() {
+            let start = self.offsets[v];
+            self.neighbors.copy_within(start..start + length, write);
+            self.offsets[v] = write;
+            write += length;
+        }
+        let n = self.len();
+        self.offsets[n] = write;
+        self.neighbors.truncate(write);
+    }
+}
+
+impl Default for Adjacency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for Adjacency {
+    type Output = [usize];
+
+    fn index(&self, v: usize) -> &[usize] {
+        &self.neighbors[self.offsets[v]..self.offsets[v + 1]]
+    }
+}
+
+impl<'a> IntoIterator for &'a Adjacency {
+    type Item = &'a [usize];
+    type IntoIter = Rows<'a>;
+
+    fn into_iter(self) -> Rows<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the neighbor lists of an `Adjacency`.
+#[derive(Clone)]
+pub struct Rows<'a> {
+    adjacency: &'a Adjacency,
+    next: usize,
+}
+
+impl<'a> Iterator for Rows<'a> {
+    type Item = &'a [usize];
+
+    fn next(&mut self) -> Option<&'a [usize]> {
+        if self.next == self.adjacency.len() {
+            return None;
+        }
+        self.next += 1;
+        Some(&self.adjacency[self.next - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.adjacency.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Rows<'_> {}
+
+/// Collect rows in vertex order.
+impl<L: IntoIterator<Item = usize>> FromIterator<L> for Adjacency {
+    fn from_iter<T: IntoIterator<Item = L>>(rows: T) -> Self {
+        let mut adjacency = Self::new();
+        for row in rows {
+            adjacency.push_row(row);
+        }
+        adjacency
+    }
+}
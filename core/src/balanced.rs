@@ -0,0 +1,45 @@
+//! Equitable coloring with near-equal class sizes.
+
+use crate::adjacency::Adjacency;
+use crate::greedy::{VertexOrdering, color_classes, color_count, greedy_color};
+
+/// Greedy "least used" recoloring capped at the average class size of a plain greedy pass.
+///
+/// Vertices are revisited class by class and take the admissible color with
+/// the fewest members among those below `ceil(n / k)`; a new color is opened
+/// only when none qualifies. No class exceeds the cap; the color count is the
+/// greedy count plus the colors the cap opens, which has no tighter bound.
+/// Expects symmetric adjacency.
+pub fn balanced_color(adjacency: &Adjacency, ordering: VertexOrdering) -> Vec<usize> {
+    let n = adjacency.len();
+    let initial = greedy_color(adjacency, ordering);
+    let n_initial = color_count(&initial);
+    if n_initial == 0 {
+        return initial;
+    }
+    let cap = n.div_ceil(n_initial);
+
+    let mut colors = vec![usize::MAX; n];
+    let mut sizes: Vec<usize> = Vec::new();
+    // forbidden[c] == v marks color c as unavailable for vertex v.
+    let mut forbidden: Vec<usize> = Vec::new();
+    for v in color_classes(&initial).into_iter().flatten() {
+        for &w in &adjacency[v] {
+            let cw = colors[w];
+            if cw != usize::MAX {
+                forbidden[cw] = v;
+            }
+        }
+        let c = (0..sizes.len())
+            .filter(|&c| forbidden[c] != v && sizes[c] < cap)
+            .min_by_key(|&c| sizes[c])
+            .unwrap_or_else(|| {
+                sizes.push(0);
+                forbidden.push(usize::MAX);
+                sizes.len() - 1
+            });
+        colors[v] = c;
+        sizes[c] += 1;
+    }
+    colors
+}
@@ -0,0 +1,46 @@
+//! Cyclic coloring of banded patterns.
+//!
+//! When no two adjacent vertices lie more than `w` apart, the colors
+//! `v mod (w + 1)` are valid, and for a full band, where every `w + 1`
+//! consecutive vertices are pairwise adjacent, no coloring needs fewer. A
+//! distance-2 coloring of a pattern with half-bandwidth `b` thus takes
+//! `2b + 1` colors straight from the vertex index.
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+
+/// Largest `|i - j|` over the entries `j` of every row `i`, 0 without any.
+pub fn bandwidth(rows: &Adjacency) -> usize {
+    (0..rows.len())
+        .into_par_iter()
+        .map(|i| rows[i].iter().map(|&j| i.abs_diff(j)).max().unwrap_or(0))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Largest `max - min` over the columns of every row, 0 without any.
+pub fn row_span(rows: &Adjacency) -> usize {
+    (0..rows.len())
+        .into_par_iter()
+        .map(|i| {
+            let row = &rows[i];
+            let (min, max) = (row.iter().min(), row.iter().max());
+            min.zip(max).map_or(0, |(min, max)| max - min)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Color `v mod (bandwidth + 1)` for each of `n` vertices.
+pub fn cyclic_colors(n: usize, bandwidth: usize) -> Vec<usize> {
+    (0..n)
+        .into_par_iter()
+        .map(|v| v % (bandwidth + 1))
+        .collect()
+}
+
+/// Cyclic coloring of a symmetric adjacency over its bandwidth.
+pub fn banded_color(adjacency: &Adjacency) -> Vec<usize> {
+    cyclic_colors(adjacency.len(), bandwidth(adjacency))
+}
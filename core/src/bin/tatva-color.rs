@@ -0,0 +1,268 @@
+//! `tatva-color`: color the pattern of a Matrix Market file from the command line.
+//!
+//! Built with the `cli` feature. Reads a coordinate `.mtx` file, colors its
+//! distance-1 or distance-2 graph (the columns of a rectangular matrix) and
+//! writes the colors, the color classes and statistics to the requested
+//! files, printing the statistics when no file is given for them.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use tatva_coloring_core::adjacency::Adjacency;
+use tatva_coloring_core::distance2::Distance2Graph;
+use tatva_coloring_core::greedy::VertexOrdering;
+use tatva_coloring_core::matrix_market;
+use tatva_coloring_core::options::{Algorithm, ColoringOptions};
+use tatva_coloring_core::progress::Silent;
+use tatva_coloring_core::refine::Refinement;
+use tatva_coloring_core::timings::Timings;
+
+const USAGE: &str = "\
+usage: tatva-color MATRIX.mtx [options]
+
+options:
+  --distance 1|2            conflict distance (default 2)
+  --algorithm NAME          greedy (default), exact, balanced, rlf, jones_plassmann,
+                            speculative, banded
+  --ordering NAME           natural (default), dsatur, dsatur_degree, smallest_last,
+                            largest_first, incidence_degree, random, rcm
+  --refine NAME             iterated_greedy, kempe, tabu
+  --seed N                  seed of the randomized strategies (default 0)
+  --time-limit SECONDS      budget of --algorithm exact (default 10)
+  --tabu-iterations N       move budget of --refine tabu (default 10000)
+  --deterministic           thread-count independent --algorithm speculative
+  --restarts N              keep the best of N greedy runs, all but the first in
+                            random orders (default 1)
+  --colors FILE             write one color per line, in vertex order
+  --groups FILE             write the vertices of one color per line
+  --stats FILE              write statistics and timings as JSON (default: stdout)
+  -h, --help                print this help";
+
+/// Parsed command line.
+struct Args {
+    matrix: PathBuf,
+    distance: usize,
+    options: ColoringOptions,
+    colors: Option<PathBuf>,
+    groups: Option<PathBuf>,
+    stats: Option<PathBuf>,
+}
+
+/// `None` when help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut matrix = None;
+    let mut distance = 2;
+    let (mut algorithm, mut ordering, mut refine) =
+        ("greedy".to_owned(), "natural".to_owned(), None);
+    let (mut seed, mut time_limit, mut tabu_iterations) = (0, 10.0, 10000);
+    let mut deterministic = false;
+    let mut restarts = 1;
+    let (mut colors, mut groups, mut stats) = (None, None, None);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        let number_error = |value: &str| format!("invalid value '{value}' for {arg}");
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--distance" => {
+                let v = value()?;
+                distance = v.parse().map_err(|_| number_error(&v))?;
+            }
+            "--algorithm" => algorithm = value()?,
+            "--ordering" => ordering = value()?,
+            "--refine" => refine = Some(value()?),
+            "--seed" => {
+                let v = value()?;
+                seed = v.parse().map_err(|_| number_error(&v))?;
+            }
+            "--time-limit" => {
+                let v = value()?;
+                time_limit = v.parse().map_err(|_| number_error(&v))?;
+            }
+            "--tabu-iterations" => {
+                let v = value()?;
+                tabu_iterations = v.parse().map_err(|_| number_error(&v))?;
+            }
+            "--deterministic" => deterministic = true,
+            "--restarts" => {
+                let v = value()?;
+                restarts = v.parse().map_err(|_| number_error(&v))?;
+            }
+            "--colors" => colors = Some(PathBuf::from(value()?)),
+            "--groups" => groups = Some(PathBuf::from(value()?)),
+            "--stats" => stats = Some(PathBuf::from(value()?)),
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{flag}'")),
+            _ if matrix.is_some() => return Err(format!("unexpected argument '{arg}'")),
+            _ => matrix = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let options = ColoringOptions {
+        algorithm: Algorithm::parse(&algorithm)?,
+        ordering: VertexOrdering::parse(&ordering, seed)?,
+        seed,
+        refine: refine
+            .map(|name| Refinement::parse(&name, tabu_iterations, seed))
+            .transpose()?,
+        time_limit: Duration::try_from_secs_f64(time_limit)
+            .map_err(|_| "--time-limit must be a non-negative number of seconds".to_owned())?,
+        deterministic,
+        priority: None,
+        restarts,
+        max_colors: None,
+        precolors: None,
+        allowed: None,
+    };
+    if restarts == 0 || (restarts > 1 && options.algorithm != Algorithm::Greedy) {
+        return Err("--restarts must be positive, and 1 unless --algorithm greedy".to_owned());
+    }
+    if !(1..=2).contains(&distance) {
+        return Err(format!("--distance must be 1 or 2, got {distance}"));
+    }
+    Ok(Some(Args {
+        matrix: matrix.ok_or("missing MATRIX.mtx")?,
+        distance,
+        options,
+        colors,
+        groups,
+        stats,
+    }))
+}
+
+/// Symmetrized square pattern without its diagonal, the distance-1 graph.
+fn distance1_adjacency(rows: &Adjacency) -> Adjacency {
+    let entries = rows.iter().enumerate().flat_map(|(i, row)| {
+        row.iter()
+            .filter(move |&&j| j != i)
+            .flat_map(move |&j| [(i, j), (j, i)])
+    });
+    let mut adjacency = Adjacency::from_pairs(rows.len(), entries);
+    adjacency.sort_dedup_rows();
+    adjacency
+}
+
+/// Vertices of every color class, in increasing order.
+fn color_classes(colors: &[usize]) -> Vec<Vec<usize>> {
+    let n_colors = colors.iter().max().map_or(0, |&c| c + 1);
+    let mut classes = vec![Vec::new(); n_colors];
+    for (v, &c) in colors.iter().enumerate() {
+        classes[c].push(v);
+    }
+    classes
+}
+
+/// Statistics and stage times as a JSON object.
+fn stats_json(args: &Args, colors: &[usize], timings: &Timings) -> String {
+    let classes = color_classes(colors);
+    let sizes = classes.iter().map(Vec::len);
+    let mean = if classes.is_empty() {
+        0.0
+    } else {
+        colors.len() as f64 / classes.len() as f64
+    };
+    let mut json = String::from("{\n");
+    let _ = writeln!(
+        json,
+        "  \"matrix\": {:?},",
+        args.matrix.display().to_string()
+    );
+    let _ = writeln!(json, "  \"distance\": {},", args.distance);
+    let _ = writeln!(json, "  \"n_vertices\": {},", colors.len());
+    let _ = writeln!(json, "  \"n_colors\": {},", classes.len());
+    let _ = writeln!(
+        json,
+        "  \"min_class_size\": {},",
+        sizes.clone().min().unwrap_or(0)
+    );
+    let _ = writeln!(json, "  \"max_class_size\": {},", sizes.max().unwrap_or(0));
+    let _ = writeln!(json, "  \"mean_class_size\": {mean},");
+    json.push_str("  \"timings\": {");
+    for (k, (stage, duration)) in timings.stages().iter().enumerate() {
+        let sep = if k == 0 { "" } else { "," };
+        let _ = write!(json, "{sep}\n    \"{stage}\": {}", duration.as_secs_f64());
+    }
+    json.push_str("\n  }\n}\n");
+    json
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let mut timings = Timings::default();
+    let pattern = timings
+        .time("read", || matrix_market::read_pattern(&args.matrix))
+        .map_err(|err| format!("{}: {err}", args.matrix.display()))?;
+    let square = pattern.n_rows == pattern.n_cols;
+    let colors = match args.distance {
+        1 if !square => return Err("distance 1 needs a square matrix".to_owned()),
+        1 => {
+            let adjacency = timings.time("adjacency", || distance1_adjacency(&pattern.rows));
+            timings.time("coloring", || args.options.color(&adjacency))
+        }
+        _ => {
+            let graph = match square {
+                true => Distance2Graph::new(pattern.rows),
+                false => Distance2Graph::columns(pattern.rows, pattern.n_cols),
+            };
+            args.options
+                .color_distance2(&graph, false, &mut timings, &Silent)
+                .map_err(|err| err.to_string())?
+        }
+    };
+
+    let write = |path: &PathBuf, text: String| {
+        fs::write(path, text).map_err(|err| format!("{}: {err}", path.display()))
+    };
+    timings.time("write", || -> Result<(), String> {
+        if let Some(path) = &args.colors {
+            let mut text = String::with_capacity(colors.len() * 4);
+            for c in &colors {
+                let _ = writeln!(text, "{c}");
+            }
+            write(path, text)?;
+        }
+        if let Some(path) = &args.groups {
+            let mut text = String::new();
+            for class in color_classes(&colors) {
+                let mut sep = "";
+                for v in class {
+                    let _ = write!(text, "{sep}{v}");
+                    sep = " ";
+                }
+                text.push('\n');
+            }
+            write(path, text)?;
+        }
+        Ok(())
+    })?;
+    let json = stats_json(args, &colors, &timings);
+    match &args.stats {
+        Some(path) => write(path, json),
+        None => {
+            print!("{json}");
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("tatva-color: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("tatva-color: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Column colorings of general (rectangular) sparsity patterns.
+
+use crate::adjacency::Adjacency;
+use crate::mesh::element_pattern;
+use crate::supervariables::distinct_rows;
+
+/// Transpose row-wise adjacency into per-column row lists.
+pub fn transpose(rows: &Adjacency, n_cols: usize) -> Adjacency {
+    let entries = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row.iter().map(move |&j| (j, i)));
+    Adjacency::from_pairs(n_cols, entries)
+}
+
+/// Greedy partial distance-2 coloring of the columns of the bipartite row–column graph.
+///
+/// Two columns conflict when they have a nonzero in a common row, so every color
+/// class is a set of structurally orthogonal columns. Repeated rows are
+/// dropped first, as they only repeat conflicts.
+pub fn partial_distance2_color(rows: &Adjacency, n_cols: usize) -> Vec<usize> {
+    let distinct = distinct_rows(rows);
+    let rows = distinct.as_ref().unwrap_or(rows);
+    let cols = transpose(rows, n_cols);
+    let mut colors = vec![usize::MAX; n_cols];
+    // forbidden[c] == j marks color c as unavailable for column j.
+    let mut forbidden: Vec<usize> = Vec::new();
+
+    for j in 0..n_cols {
+        for &i in &cols[j] {
+            for &k in &rows[i] {
+                let ck = colors[k];
+                if ck == usize::MAX {
+                    continue;
+                }
+                if ck >= forbidden.len() {
+                    forbidden.resize(ck + 1, usize::MAX);
+                }
+                forbidden[ck] = j;
+            }
+        }
+        let mut c = 0;
+        while forbidden.get(c) == Some(&j) {
+            c += 1;
+        }
+        colors[j] = c;
+    }
+    colors
+}
+
+/// Column intersection graph: columns are adjacent when they share a nonzero row.
+///
+/// Returns sorted, symmetric adjacency without self loops. Repeated rows
+/// are dropped first, as they only repeat conflicts.
+pub fn column_intersection_adjacency(rows: &Adjacency, n_cols: usize) -> Adjacency {
+    let distinct = distinct_rows(rows);
+    let rows = distinct.as_ref().unwrap_or(rows);
+    let cols = transpose(rows, n_cols);
+    // seen[k] == j marks column k as already adjacent to column j.
+    let mut seen = vec![usize::MAX; n_cols];
+    let mut neighs: Vec<usize> = Vec::new();
+    let mut adjacency = Adjacency::new();
+    for j in 0..n_cols {
+        seen[j] = j;
+        neighs.clear();
+        for &i in &cols[j] {
+            for &k in &rows[i] {
+                if seen[k] != j {
+                    seen[k] = j;
+                    neighs.push(k);
+                }
+            }
+        }
+        neighs.sort_unstable();
+        adjacency.push_row(neighs.iter().copied());
+    }
+    adjacency
+}
+
+/// Pattern of `AᵀA` for the pattern `rows` of `A`, diagonal included, without any values.
+///
+/// Columns are coupled when they share a row, so every row acts like an
+/// element of the columns it holds; its rows come back sorted. The pattern
+/// of `AAᵀ` is that of the `transpose`.
+pub fn normal_pattern(rows: &Adjacency, n_cols: usize) -> Adjacency {
+    element_pattern(rows, n_cols)
+}
+
+/// Bidirectional partition of a pattern into row-compressed dense rows and column-compressed rest.
+///
+/// Rows with at least `threshold` nonzeros are colored among themselves
+/// (rows sharing a column differ) and recovered from `W^T J`; all remaining
+/// entries are recovered from `J V` with a column coloring over the other rows.
+/// Without a threshold, candidate thresholds among the largest row lengths are
+/// tried and the one with the fewest total products wins. Returns row and
+/// column colors, `usize::MAX` marking rows/columns that need no color.
+pub fn bicolor(
+    rows: &Adjacency,
+    n_cols: usize,
+    threshold: Option<usize>,
+) -> (Vec<usize>, Vec<usize>) {
+    let thresholds: Vec<usize> = match threshold {
+        Some(t) => vec![t],
+        None => {
+            let mut lengths: Vec<usize> =
+                rows.iter().map(<[usize]>::len).filter(|&l| l > 0).collect();
+            lengths.sort_unstable_by(|a, b| b.cmp(a));
+            lengths.dedup();
+            // usize::MAX keeps every row on the column side.
+            std::iter::once(usize::MAX)
+                .chain(lengths.into_iter().take(32))
+                .collect()
+        }
+    };
+
+    thresholds
+        .into_iter()
+        .map(|t| split_bicolor(rows, n_cols, t))
+        .min_by_key(|(row_colors, col_colors)| used_colors(row_colors) + used_colors(col_colors))
+        .unwrap_or_default()
+}
+
+/// Bicoloring for a fixed dense-row threshold.
+fn split_bicolor(rows: &Adjacency, n_cols: usize, threshold: usize) -> (Vec<usize>, Vec<usize>) {
+    let is_dense = |row: &[usize]| row.len() >= threshold;
+    let sparse: Adjacency = rows
+        .iter()
+        .map(|row| if is_dense(row) { &[][..] } else { row }.iter().copied())
+        .collect();
+    let dense: Adjacency = rows
+        .iter()
+        .map(|row| if is_dense(row) { row } else { &[][..] }.iter().copied())
+        .collect();
+
+    let mut col_colors = partial_distance2_color(&sparse, n_cols);
+    for (j, rows_of_j) in transpose(&sparse, n_cols).iter().enumerate() {
+        if rows_of_j.is_empty() {
+            col_colors[j] = usize::MAX;
+        }
+    }
+    // Rows of J are the columns of J^T.
+    let mut row_colors = partial_distance2_color(&transpose(&dense, n_cols), rows.len());
+    for (i, row) in rows.iter().enumerate() {
+        if !is_dense(row) {
+            row_colors[i] = usize::MAX;
+        }
+    }
+    (row_colors, col_colors)
+}
+
+/// Number of colors in use, ignoring `usize::MAX` entries.
+fn used_colors(colors: &[usize]) -> usize {
+    colors
+        .iter()
+        .filter(|&&c| c != usize::MAX)
+        .max()
+        .map_or(0, |&c| c + 1)
+}
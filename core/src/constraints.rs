@@ -0,0 +1,63 @@
+//! DOFs tied together by constraints, colored as a single vertex.
+//!
+//! A multi-point constraint or a periodic boundary pair makes its DOFs move
+//! together, so a probe through the constraint operator perturbs all of
+//! them at once. They must share one color, and their columns are merged
+//! into one vertex of the conflict graph before coloring.
+
+use crate::adjacency::Adjacency;
+
+/// Group of each of `n` DOFs, merging all `ties` that share a DOF.
+///
+/// Each row of `ties` lists DOFs constrained together; untied DOFs form a
+/// group of their own. Groups are numbered by their smallest DOF, so the
+/// natural order of the groups follows the DOFs.
+pub fn tie_groups(n: usize, ties: &Adjacency) -> Vec<usize> {
+    // Union-find forest whose roots are the smallest DOF of their set.
+    let mut parent: Vec<usize> = (0..n).collect();
+    fn root(parent: &mut [usize], mut v: usize) -> usize {
+        while parent[v] != v {
+            parent[v] = parent[parent[v]];
+            v = parent[v];
+        }
+        v
+    }
+    for tie in ties.iter() {
+        let Some((&first, rest)) = tie.split_first() else {
+            continue;
+        };
+        for &v in rest {
+            let (a, b) = (root(&mut parent, first), root(&mut parent, v));
+            parent[a.max(b)] = a.min(b);
+        }
+    }
+    let mut groups = vec![0; n];
+    let mut n_groups = 0;
+    for v in 0..n {
+        let r = root(&mut parent, v);
+        if r == v {
+            groups[v] = n_groups;
+            n_groups += 1;
+        } else {
+            groups[v] = groups[r];
+        }
+    }
+    groups
+}
+
+/// Pattern rows with the columns of each group merged, and the rows too if `merge_rows`.
+///
+/// `groups` maps every column (and row, if merged) to its group, as from
+/// `tie_groups`. Rows come back sorted without duplicates.
+pub fn merge_groups(rows: &Adjacency, groups: &[usize], merge_rows: bool) -> Adjacency {
+    let n_groups = groups.iter().max().map_or(0, |&g| g + 1);
+    let row_group = |i: usize| if merge_rows { groups[i] } else { i };
+    let entries = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row.iter().map(move |&j| (row_group(i), groups[j])));
+    let n_rows = if merge_rows { n_groups } else { rows.len() };
+    let mut merged = Adjacency::from_pairs(n_rows, entries);
+    merged.sort_dedup_rows();
+    merged
+}
@@ -0,0 +1,487 @@
+//! Distance-2 neighborhoods traversed straight from a sparsity pattern.
+
+use std::cmp::Reverse;
+use std::sync::OnceLock;
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+use crate::banded;
+use crate::bipartite::transpose;
+use crate::constraints::merge_groups;
+use crate::ordering;
+use crate::progress::{Cancelled, Phase, Progress, Silent};
+use crate::supervariables;
+
+/// Implicit distance-2 graph of a pattern.
+///
+/// Only the pattern and its transpose are stored; 2-hop neighbors are
+/// enumerated on demand, so the distance-2 graph itself, often many times
+/// larger than the pattern, never has to exist in memory. Degrees and the
+/// explicit adjacency are built on first use and kept for later queries.
+pub struct Distance2Graph {
+    rows: Adjacency,
+    transposed: Adjacency,
+    columns: bool,
+    degrees: OnceLock<Vec<usize>>,
+    explicit: OnceLock<Adjacency>,
+}
+
+impl Distance2Graph {
+    /// Graph of a square pattern over its DOFs.
+    ///
+    /// Vertex i conflicts with its neighbors and their neighbors in both the
+    /// pattern and its transpose.
+    pub fn new(rows: Adjacency) -> Self {
+        Self {
+            transposed: transpose(&rows, rows.len()),
+            rows,
+            columns: false,
+            degrees: OnceLock::new(),
+            explicit: OnceLock::new(),
+        }
+    }
+
+    /// Column intersection graph of an `rows.len() x n_cols` pattern.
+    ///
+    /// Columns conflict when they share a nonzero row, i.e. the partial
+    /// distance-2 graph of the bipartite row/column graph. Identical and
+    /// empty rows add no conflicts and are dropped first, per
+    /// `supervariables::distinct_rows`, so later traversals skip them.
+    pub fn columns(rows: Adjacency, n_cols: usize) -> Self {
+        let rows = supervariables::distinct_rows(&rows).unwrap_or(rows);
+        Self {
+            transposed: transpose(&rows, n_cols),
+            rows,
+            columns: true,
+            degrees: OnceLock::new(),
+            explicit: OnceLock::new(),
+        }
+    }
+
+    pub fn n_vertices(&self) -> usize {
+        if self.columns {
+            self.transposed.len()
+        } else {
+            self.rows.len()
+        }
+    }
+
+    /// Call `f` once per distance-2 neighbor of `i`, in no particular order.
+    ///
+    /// `seen` holds one entry per vertex; no entry may equal `i` on entry.
+    /// Afterwards `seen[k] == i` marks `i` and every neighbor `k`.
+    pub fn for_each_neighbor(&self, i: usize, seen: &mut [usize], mut f: impl FnMut(usize)) {
+        seen[i] = i;
+        if self.columns {
+            for &row in &self.transposed[i] {
+                for &k in &self.rows[row] {
+                    if seen[k] != i {
+                        seen[k] = i;
+                        f(k);
+                    }
+                }
+            }
+            return;
+        }
+        for lists in [&self.rows, &self.transposed] {
+            for &j in &lists[i] {
+                for &k in std::iter::once(&j).chain(&lists[j]) {
+                    if seen[k] != i {
+                        seen[k] = i;
+                        f(k);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sorted distance-2 neighbors of `i` into `row`, merged from sorted pattern lists.
+    ///
+    /// The same row as `adjacency()[i]`, built without `seen` markers: the
+    /// lists `i` reaches are merged pairwise, level by level, with a
+    /// branchless two-way merge. Pattern rows must be sorted.
+    pub fn merged_neighbors(&self, i: usize, scratch: &mut MergeScratch, row: &mut Vec<usize>) {
+        scratch.runs.clear();
+        scratch.bounds.clear();
+        scratch.bounds.push(0);
+        let mut push = |list: &[usize]| {
+            scratch.runs.extend_from_slice(list);
+            scratch.bounds.push(scratch.runs.len());
+        };
+        if self.columns {
+            for &r in &self.transposed[i] {
+                push(&self.rows[r]);
+            }
+        } else {
+            for lists in [&self.rows, &self.transposed] {
+                push(&lists[i]);
+                for &j in &lists[i] {
+                    push(&lists[j]);
+                }
+            }
+        }
+        row.clear();
+        row.extend(scratch.union().iter().copied().filter(|&k| k != i));
+    }
+
+    /// Materialized adjacency: sorted lists without self loops, built in parallel.
+    ///
+    /// A counting pass sizes every row first, so each row is written in place.
+    /// Rows are deduplicated through the `seen` markers, one array access per
+    /// 2-hop entry with no hashing, and each row is sorted once filled.
+    /// `merged_neighbors` builds the same rows by merging sorted lists; the
+    /// markers are faster on every stencil of `benches/distance2_rows.rs`,
+    /// so they are kept here.
+    pub fn adjacency(&self) -> &Adjacency {
+        self.adjacency_with_progress(&Silent)
+            .expect("silent progress never cancels")
+    }
+
+    /// `adjacency()`, reporting the "degrees" and "distance2" phases if not built yet.
+    pub fn adjacency_with_progress(
+        &self,
+        progress: &dyn Progress,
+    ) -> Result<&Adjacency, Cancelled> {
+        if let Some(adjacency) = self.explicit.get() {
+            return Ok(adjacency);
+        }
+        let adjacency = self.build_adjacency(progress)?;
+        Ok(self.explicit.get_or_init(|| adjacency))
+    }
+
+    fn build_adjacency(&self, progress: &dyn Progress) -> Result<Adjacency, Cancelled> {
+        let n = self.n_vertices();
+        let mut adjacency = Adjacency::with_degrees(self.degrees_with_progress(progress)?);
+        let phase = Phase::start(progress, "distance2", n)?;
+        adjacency
+            .rows_mut()
+            .into_par_iter()
+            .enumerate()
+            .try_for_each_init(
+                || vec![usize::MAX; n],
+                |seen, (i, row)| {
+                    let mut next = 0;
+                    self.for_each_neighbor(i, seen, |k| {
+                        row[next] = k;
+                        next += 1;
+                    });
+                    row.sort_unstable();
+                    phase.step()
+                },
+            )?;
+        phase.finish()?;
+        Ok(adjacency)
+    }
+
+    /// Bytes the materialized `adjacency()` occupies.
+    pub fn adjacency_bytes(&self) -> usize {
+        let entries = self.n_vertices() + 1 + self.degrees().iter().sum::<usize>();
+        entries * std::mem::size_of::<usize>()
+    }
+
+    /// Number of distance-2 neighbors of every vertex.
+    pub fn degrees(&self) -> &[usize] {
+        self.degrees_with_progress(&Silent)
+            .expect("silent progress never cancels")
+    }
+
+    /// `degrees()`, reporting the "degrees" phase if not counted yet.
+    pub fn degrees_with_progress(&self, progress: &dyn Progress) -> Result<&[usize], Cancelled> {
+        if let Some(degrees) = self.degrees.get() {
+            return Ok(degrees);
+        }
+        let degrees = self.count_degrees(progress)?;
+        Ok(self.degrees.get_or_init(|| degrees))
+    }
+
+    /// `degrees()` if some query has counted them already.
+    pub fn counted_degrees(&self) -> Option<&[usize]> {
+        self.degrees.get().map(Vec::as_slice)
+    }
+
+    fn count_degrees(&self, progress: &dyn Progress) -> Result<Vec<usize>, Cancelled> {
+        let n = self.n_vertices();
+        let phase = Phase::start(progress, "degrees", n)?;
+        let degrees = (0..n)
+            .into_par_iter()
+            .map_init(
+                || vec![usize::MAX; n],
+                |seen, i| {
+                    let mut degree = 0;
+                    self.for_each_neighbor(i, seen, |_| degree += 1);
+                    phase.step().map(|()| degree)
+                },
+            )
+            .collect::<Result<_, _>>()?;
+        phase.finish()?;
+        Ok(degrees)
+    }
+
+    /// Sum of `weights` over the distance-2 neighbors of every vertex.
+    ///
+    /// Unit weights give `degrees()`. Weighting every vertex of a condensed
+    /// graph by the DOFs it stands for gives the conflicts of each of its DOFs
+    /// outside its own vertex.
+    pub fn weighted_degrees(&self, weights: &[usize]) -> Vec<usize> {
+        let n = self.n_vertices();
+        (0..n)
+            .into_par_iter()
+            .map_init(
+                || vec![usize::MAX; n],
+                |seen, i| {
+                    let mut degree = 0;
+                    self.for_each_neighbor(i, seen, |k| degree += weights[k]);
+                    degree
+                },
+            )
+            .collect()
+    }
+
+    /// Largest index distance between two distance-2 neighbors, bounded from the pattern.
+    ///
+    /// Twice the bandwidth of a square pattern, the widest row span of
+    /// columns; `banded::cyclic_colors` over it colors the graph validly.
+    pub fn bandwidth(&self) -> usize {
+        if self.columns {
+            banded::row_span(&self.rows)
+        } else {
+            2 * banded::bandwidth(&self.rows)
+        }
+    }
+
+    /// Supervariable of every vertex, as from `supervariables::identical_groups`.
+    ///
+    /// Vertices of a square pattern are identical if their rows and columns
+    /// are, so that they share every distance-2 neighbor; columns of a
+    /// column graph if they have the same rows.
+    pub fn supervariables(&self) -> Vec<usize> {
+        let n = self.n_vertices();
+        if self.columns {
+            supervariables::identical_groups(n, |j| (&self.transposed[j], &[]))
+        } else {
+            supervariables::identical_groups(n, |i| (&self.rows[i], &self.transposed[i]))
+        }
+    }
+
+    /// Graph over `groups` of the vertices, merging each group into one vertex.
+    ///
+    /// Two groups conflict when any of their members do, so colors of the
+    /// condensed graph carry over to the members of different groups.
+    pub fn condensed(&self, groups: &[usize]) -> Self {
+        let merged = merge_groups(&self.rows, groups, !self.columns);
+        if self.columns {
+            let n_groups = groups.iter().max().map_or(0, |&g| g + 1);
+            Self::columns(merged, n_groups)
+        } else {
+            Self::new(merged)
+        }
+    }
+
+    /// Reverse Cuthill–McKee order of the vertices, taken from the pattern.
+    ///
+    /// A square pattern is ordered by RCM of its symmetrized graph, whose
+    /// bandwidth bounds half that of the distance-2 graph in the same order;
+    /// columns are taken in the RCM order of the bipartite row/column graph.
+    /// Neither needs the distance-2 graph itself.
+    pub fn reverse_cuthill_mckee(&self) -> Vec<usize> {
+        let n_rows = self.rows.len();
+        let entries = self
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().map(move |&j| (i, j)));
+        if self.columns {
+            let pairs = entries.flat_map(|(i, j)| [(i, n_rows + j), (n_rows + j, i)]);
+            let mut graph = Adjacency::from_pairs(n_rows + self.n_vertices(), pairs);
+            graph.sort_dedup_rows();
+            ordering::reverse_cuthill_mckee(&graph)
+                .into_iter()
+                .filter_map(|v| v.checked_sub(n_rows))
+                .collect()
+        } else {
+            let pairs = entries
+                .filter(|&(i, j)| i != j)
+                .flat_map(|(i, j)| [(i, j), (j, i)]);
+            let mut graph = Adjacency::from_pairs(n_rows, pairs);
+            graph.sort_dedup_rows();
+            ordering::reverse_cuthill_mckee(&graph)
+        }
+    }
+
+    /// Clique found greedily around the `n_seeds` vertices of highest degree.
+    ///
+    /// From each seed, its neighbors are tried in decreasing degree and kept if
+    /// adjacent to everything kept so far; the largest clique is returned. Its
+    /// size is a lower bound on the colors any distance-2 coloring needs.
+    pub fn greedy_clique(&self, n_seeds: usize) -> Vec<usize> {
+        let n = self.n_vertices();
+        let degrees = self.degrees();
+        let seeds = ordering::by_degree(degrees);
+        seeds[..n_seeds.min(n)]
+            .par_iter()
+            .map(|&seed| {
+                let mut seen = vec![usize::MAX; n];
+                let mut candidates = Vec::new();
+                self.for_each_neighbor(seed, &mut seen, |k| candidates.push(k));
+                candidates.sort_unstable_by_key(|&k| (Reverse(degrees[k]), k));
+                let mut clique = vec![seed];
+                for c in candidates {
+                    self.for_each_neighbor(c, &mut seen, |_| {});
+                    if clique.iter().all(|&member| seen[member] == c) {
+                        clique.push(c);
+                    }
+                }
+                clique
+            })
+            .max_by_key(|clique| (clique.len(), Reverse(clique[0])))
+            .unwrap_or_default()
+    }
+
+    /// DOF pairs `(u, v)`, `u < v`, that share a color within distance 2.
+    ///
+    /// Every vertex stands for `block_size` consecutive DOFs, all of which
+    /// conflict with each other and with the DOFs of every neighbor. Pairs are
+    /// sorted; an empty result means `colors` is a valid coloring.
+    pub fn conflicts(&self, colors: &[usize], block_size: usize) -> Vec<(usize, usize)> {
+        let n = self.n_vertices();
+        let dofs = |v: usize| v * block_size..(v + 1) * block_size;
+        (0..n)
+            .into_par_iter()
+            .map_init(
+                || vec![usize::MAX; n],
+                |seen, i| {
+                    let mut pairs = Vec::new();
+                    for u in dofs(i) {
+                        for v in u + 1..dofs(i).end {
+                            if colors[u] == colors[v] {
+                                pairs.push((u, v));
+                            }
+                        }
+                    }
+                    self.for_each_neighbor(i, seen, |k| {
+                        if k > i {
+                            for u in dofs(i) {
+                                pairs.extend(
+                                    dofs(k).filter(|&v| colors[u] == colors[v]).map(|v| (u, v)),
+                                );
+                            }
+                        }
+                    });
+                    pairs.sort_unstable();
+                    pairs
+                },
+            )
+            .flatten()
+            .collect()
+    }
+
+    /// Greedy coloring in the given order: smallest color unused within distance 2.
+    ///
+    /// Gives the same colors as `greedy_color_in_order` on `adjacency()`,
+    /// reporting the "coloring" phase.
+    pub fn greedy_color_in_order(
+        &self,
+        order: impl IntoIterator<Item = usize>,
+        progress: &dyn Progress,
+    ) -> Result<Vec<usize>, Cancelled> {
+        let n = self.n_vertices();
+        let mut colors = vec![usize::MAX; n];
+        let phase = Phase::start(progress, "coloring", n)?;
+        self.color_vertices(&mut colors, order, None, || phase.step())?;
+        phase.finish()?;
+        Ok(colors)
+    }
+
+    /// Give each vertex of `order` the smallest color unused within distance 2.
+    ///
+    /// All other colors are kept, `usize::MAX` marking uncolored vertices.
+    /// Each vertex may appear in `order` at most once.
+    pub fn color_in_order(&self, colors: &mut [usize], order: impl IntoIterator<Item = usize>) {
+        self.color_vertices(colors, order, None, || Ok(()))
+            .expect("coloring without progress is never cancelled");
+    }
+
+    /// `color_in_order`, calling `step` after each colored vertex.
+    ///
+    /// With `allowed`, vertex `i` only takes colors `c` with bit `c` of
+    /// `allowed[i]` set, staying uncolored if all of them are taken.
+    pub(crate) fn color_vertices(
+        &self,
+        colors: &mut [usize],
+        order: impl IntoIterator<Item = usize>,
+        allowed: Option<&[u64]>,
+        mut step: impl FnMut() -> Result<(), Cancelled>,
+    ) -> Result<(), Cancelled> {
+        let mut seen = vec![usize::MAX; self.n_vertices()];
+        // forbidden[c] == i marks color c as unavailable for vertex i.
+        let mut forbidden: Vec<usize> = Vec::new();
+
+        for i in order {
+            self.for_each_neighbor(i, &mut seen, |k| {
+                let c = colors[k];
+                if c != usize::MAX {
+                    if c >= forbidden.len() {
+                        forbidden.resize(c + 1, usize::MAX);
+                    }
+                    forbidden[c] = i;
+                }
+            });
+            let free = |c: usize| forbidden.get(c) != Some(&i);
+            colors[i] = match allowed {
+                Some(allowed) => (0..u64::BITS as usize)
+                    .find(|&c| allowed[i] >> c & 1 == 1 && free(c))
+                    .unwrap_or(usize::MAX),
+                None => (0..).find(|&c| free(c)).expect("some color is free"),
+            };
+            step()?;
+        }
+        Ok(())
+    }
+}
+
+/// Reusable buffers of `Distance2Graph::merged_neighbors`.
+#[derive(Default)]
+pub struct MergeScratch {
+    runs: Vec<usize>,
+    spare: Vec<usize>,
+    bounds: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl MergeScratch {
+    /// Union of the sorted runs `runs[bounds[k]..bounds[k + 1]]`, merging neighboring pairs.
+    fn union(&mut self) -> &[usize] {
+        while self.bounds.len() > 2 {
+            let n_runs = self.bounds.len() - 1;
+            self.spare.clear();
+            self.next.clear();
+            self.next.push(0);
+            for k in (0..n_runs).step_by(2) {
+                let (lo, mid) = (self.bounds[k], self.bounds[k + 1]);
+                let hi = self.bounds.get(k + 2).copied().unwrap_or(mid);
+                merge_sorted(&self.runs[lo..mid], &self.runs[mid..hi], &mut self.spare);
+                self.next.push(self.spare.len());
+            }
+            std::mem::swap(&mut self.runs, &mut self.spare);
+            std::mem::swap(&mut self.bounds, &mut self.next);
+        }
+        &self.runs
+    }
+}
+
+/// Append the union of the sorted, duplicate-free `a` and `b` to `out`.
+///
+/// Both cursors advance by comparison results rather than branches, so the
+/// loop does not stall on mispredictions when the lists interleave.
+fn merge_sorted(a: &[usize], b: &[usize], out: &mut Vec<usize>) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let (x, y) = (a[i], b[j]);
+        out.push(x.min(y));
+        i += usize::from(x <= y);
+        j += usize::from(y <= x);
+    }
+    out.extend_from_slice(&a[i..]);
+    out.extend_from_slice(&b[j..]);
+}
@@ -0,0 +1,242 @@
+//! Distance-2 coloring of a pattern whose rows are spread over several ranks.
+//!
+//! Every rank holds a [`HaloGraph`] of its rows and colors its boundary in
+//! rounds: `color` the pending boundary vertices, exchange the colors of the
+//! halo with their owners, then `yield_conflicts` uncolors the vertices that
+//! clash with a halo vertex taking precedence over them. A conflict between
+//! two ranks is seen by both with the same verdict, and the pending vertex of
+//! highest precedence always keeps its color, so the rounds terminate. The
+//! interior, whose neighborhoods lie within the rank, is colored last
+//! without any communication. The exchange itself is left to the caller.
+
+use std::cmp::Ordering;
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+use crate::distance2::Distance2Graph;
+
+/// Distance-2 graph of the rows one rank owns, extended by their halo.
+///
+/// The rank owns the global vertices `start..start + n_owned()` and also
+/// holds the rows of its ghosts, the columns of its rows owned elsewhere.
+/// For a structurally symmetric pattern these rows contain every distance-2
+/// neighborhood of an owned vertex. Vertices are numbered locally: the
+/// owned ones first in global order, then the halo, i.e. every other vertex
+/// these rows mention, in increasing global index.
+pub struct HaloGraph {
+    graph: Distance2Graph,
+    start: usize,
+    n_owned: usize,
+    halo: Vec<usize>,
+}
+
+impl HaloGraph {
+    /// Graph of the `owned` rows of vertices `start..` and the `ghost_rows` of `ghosts`.
+    ///
+    /// Rows hold global column indices. Every column of `owned` outside the
+    /// owned range must be listed in `ghosts`, which must not hold owned
+    /// vertices.
+    pub fn new(
+        start: usize,
+        owned: &Adjacency,
+        ghosts: &[usize],
+        ghost_rows: &Adjacency,
+    ) -> Result<Self, String> {
+        let n_owned = owned.len();
+        let is_owned = |v: usize| (start..start + n_owned).contains(&v);
+        if ghost_rows.len() != ghosts.len() {
+            return Err(format!(
+                "{} ghosts but {} ghost rows",
+                ghosts.len(),
+                ghost_rows.len()
+            ));
+        }
+        if let Some(&g) = ghosts.iter().find(|&&g| is_owned(g)) {
+            return Err(format!("ghost {g} is one of the owned rows"));
+        }
+        let mut sorted_ghosts = ghosts.to_vec();
+        sorted_ghosts.sort_unstable();
+        for (i, row) in owned.iter().enumerate() {
+            if let Some(&j) = row
+                .iter()
+                .find(|&&j| !is_owned(j) && sorted_ghosts.binary_search(&j).is_err())
+            {
+                return Err(format!(
+                    "row {} has column {j}, which is neither owned nor a ghost",
+                    start + i
+                ));
+            }
+        }
+
+        let mut halo: Vec<usize> = ghosts
+            .iter()
+            .chain(ghost_rows.neighbors())
+            .copied()
+            .filter(|&v| !is_owned(v))
+            .collect();
+        halo.sort_unstable();
+        halo.dedup();
+        let local = |v: usize| {
+            if is_owned(v) {
+                v - start
+            } else {
+                n_owned + halo.binary_search(&v).expect("halo lists every vertex")
+            }
+        };
+        let owned_pairs = owned
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().map(move |&j| (i, j)));
+        let ghost_pairs = ghosts
+            .iter()
+            .zip(ghost_rows.iter())
+            .flat_map(|(&g, row)| row.iter().map(move |&j| (g, j)));
+        let pairs = owned_pairs
+            .map(|(i, j)| (i, local(j)))
+            .chain(ghost_pairs.map(|(g, j)| (local(g), local(j))));
+        let mut rows = Adjacency::from_pairs(n_owned + halo.len(), pairs);
+        rows.sort_dedup_rows();
+        Ok(Self {
+            graph: Distance2Graph::new(rows),
+            start,
+            n_owned,
+            halo,
+        })
+    }
+
+    /// First entry `(i, j)` among `owned` and `ghost_rows` whose mirror `(j, i)` is missing.
+    ///
+    /// Rows are those of `new` and must be sorted. Only mirrors held on this
+    /// rank are checked, i.e. entries with an owned end and the other end
+    /// owned or a ghost; entries between two ghosts are checked by their
+    /// owners.
+    pub fn asymmetric_entry(
+        start: usize,
+        owned: &Adjacency,
+        ghosts: &[usize],
+        ghost_rows: &Adjacency,
+    ) -> Option<(usize, usize)> {
+        let n_owned = owned.len();
+        let mut ghost_index: Vec<(usize, usize)> = ghosts.iter().copied().zip(0..).collect();
+        ghost_index.sort_unstable();
+        let row = |v: usize| -> Option<&[usize]> {
+            if (start..start + n_owned).contains(&v) {
+                return Some(&owned[v - start]);
+            }
+            let k = ghost_index.binary_search_by_key(&v, |&(g, _)| g).ok()?;
+            Some(&ghost_rows[ghost_index[k].1])
+        };
+        let owned_entries = owned
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().map(move |&j| (start + i, j)));
+        let ghost_entries = ghosts
+            .iter()
+            .zip(ghost_rows.iter())
+            .flat_map(|(&g, row)| row.iter().map(move |&j| (g, j)))
+            .filter(|&(_, j)| (start..start + n_owned).contains(&j));
+        owned_entries
+            .chain(ghost_entries)
+            .find(|&(i, j)| row(j).is_some_and(|mirror| mirror.binary_search(&i).is_err()))
+    }
+
+    /// Number of owned vertices, locally `0..n_owned()`.
+    pub fn n_owned(&self) -> usize {
+        self.n_owned
+    }
+
+    /// Number of local vertices, owned and halo.
+    pub fn n_vertices(&self) -> usize {
+        self.n_owned + self.halo.len()
+    }
+
+    /// Global index of every halo vertex, local vertex `n_owned() + k` being `halo()[k]`.
+    pub fn halo(&self) -> &[usize] {
+        &self.halo
+    }
+
+    /// Global index of local vertex `v`.
+    pub fn global(&self, v: usize) -> usize {
+        if v < self.n_owned {
+            self.start + v
+        } else {
+            self.halo[v - self.n_owned]
+        }
+    }
+
+    /// Owned vertices with a halo vertex within distance 2, in increasing order.
+    ///
+    /// All other owned vertices are interior: no other rank colors anything
+    /// within their distance 2.
+    pub fn boundary(&self) -> Vec<usize> {
+        let n = self.n_vertices();
+        (0..self.n_owned)
+            .into_par_iter()
+            .map_init(
+                || vec![usize::MAX; n],
+                |seen, v| {
+                    let mut halo = false;
+                    self.graph
+                        .for_each_neighbor(v, seen, |k| halo |= k >= self.n_owned);
+                    halo
+                },
+            )
+            .collect::<Vec<bool>>()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(v, halo)| halo.then_some(v))
+            .collect()
+    }
+
+    /// Give `vertices`, in decreasing precedence, the smallest color unused within distance 2.
+    ///
+    /// `colors` holds one entry per local vertex, `usize::MAX` marking
+    /// uncolored ones; halo colors are the latest received from their owners.
+    pub fn color(&self, colors: &mut [usize], vertices: &[usize], priority: Option<&[f64]>) {
+        let mut order = vertices.to_vec();
+        order.sort_unstable_by(|&a, &b| self.precedence(priority, b, a));
+        self.graph.color_in_order(colors, order);
+    }
+
+    /// Uncolor and return `vertices` that share a color with a halo vertex of higher precedence.
+    ///
+    /// Precedence is decreasing `priority`, ties going to the smaller global
+    /// index, so the owner of the other vertex reaches the opposite verdict.
+    pub fn yield_conflicts(
+        &self,
+        colors: &mut [usize],
+        vertices: &[usize],
+        priority: Option<&[f64]>,
+    ) -> Vec<usize> {
+        let n = self.n_vertices();
+        let current: &[usize] = colors;
+        let losers: Vec<usize> = vertices
+            .par_iter()
+            .map_init(
+                || vec![usize::MAX; n],
+                |seen, &v| {
+                    let mut yields = false;
+                    self.graph.for_each_neighbor(v, seen, |k| {
+                        yields |= k >= self.n_owned
+                            && current[v] != usize::MAX
+                            && current[k] == current[v]
+                            && self.precedence(priority, k, v) == Ordering::Greater;
+                    });
+                    yields.then_some(v)
+                },
+            )
+            .flatten()
+            .collect();
+        for &v in &losers {
+            colors[v] = usize::MAX;
+        }
+        losers
+    }
+
+    /// Order of `a` and `b` by precedence, greatest first to keep its color.
+    fn precedence(&self, priority: Option<&[f64]>, a: usize, b: usize) -> Ordering {
+        let by_priority = priority.map_or(Ordering::Equal, |p| p[a].total_cmp(&p[b]));
+        by_priority.then_with(|| self.global(b).cmp(&self.global(a)))
+    }
+}
@@ -0,0 +1,176 @@
+//! Exact minimum coloring by branch and bound.
+
+use std::time::{Duration, Instant};
+
+use crate::adjacency::Adjacency;
+use crate::greedy::{VertexOrdering, color_count, greedy_color};
+
+/// Largest graph `exact_color` searches; its counters take `4 * n * colors` bytes.
+pub const EXACT_MAX_VERTICES: usize = 10_000;
+
+/// DSATUR branch and bound (Brélaz), seeded with a DSATUR upper bound and a greedy clique.
+///
+/// Returns an optimal coloring if the search completes within `time_limit`,
+/// otherwise the best coloring found so far. Graphs with more than
+/// `EXACT_MAX_VERTICES` vertices get the DSATUR coloring without a search.
+/// Expects sorted, symmetric adjacency.
+pub fn exact_color(adjacency: &Adjacency, time_limit: Duration) -> Vec<usize> {
+    let n = adjacency.len();
+    let best = greedy_color(adjacency, VertexOrdering::Dsatur);
+    if n > EXACT_MAX_VERTICES {
+        return best;
+    }
+    let best_count = color_count(&best);
+    let clique = greedy_clique(adjacency);
+    if clique.len() >= best_count {
+        return best;
+    }
+
+    let mut search = Search {
+        adjacency,
+        deadline: Instant::now() + time_limit,
+        timed_out: false,
+        nodes: 0,
+        stride: best_count,
+        colors: vec![usize::MAX; n],
+        counts: vec![0; n * best_count],
+        saturation: vec![0; n],
+        best,
+        best_count,
+        lower_bound: clique.len(),
+    };
+    // Fixing the clique colors up front removes symmetric branches.
+    for (c, &v) in clique.iter().enumerate() {
+        search.assign(v, c);
+    }
+    search.branch(clique.len(), clique.len());
+    search.best
+}
+
+/// Greedy clique: grow from the highest-degree vertex by adding the best-connected candidate.
+fn greedy_clique(adjacency: &Adjacency) -> Vec<usize> {
+    let Some(start) = (0..adjacency.len()).max_by_key(|&v| adjacency[v].len()) else {
+        return Vec::new();
+    };
+    let mut clique = vec![start];
+    let mut candidates = adjacency[start].to_vec();
+    while let Some(&v) = candidates.iter().max_by_key(|&&v| adjacency[v].len()) {
+        clique.push(v);
+        candidates.retain(|&w| w != v && adjacency[v].binary_search(&w).is_ok());
+    }
+    clique
+}
+
+/// Branch-and-bound state.
+struct Search<'a> {
+    adjacency: &'a Adjacency,
+    deadline: Instant,
+    timed_out: bool,
+    nodes: usize,
+    /// Row length of `counts`; no partial coloring uses more colors.
+    stride: usize,
+    colors: Vec<usize>,
+    /// counts[v * stride + c]: number of neighbors of v colored c.
+    counts: Vec<u32>,
+    /// Number of distinct colors among the colored neighbors of each vertex.
+    saturation: Vec<usize>,
+    best: Vec<usize>,
+    best_count: usize,
+    lower_bound: usize,
+}
+
+impl Search<'_> {
+    fn assign(&mut self, v: usize, c: usize) {
+        self.colors[v] = c;
+        for &w in &self.adjacency[v] {
+            let count = &mut self.counts[w * self.stride + c];
+            if *count == 0 {
+                self.saturation[w] += 1;
+            }
+            *count += 1;
+        }
+    }
+
+    fn unassign(&mut self, v: usize, c: usize) {
+        self.colors[v] = usize::MAX;
+        for &w in &self.adjacency[v] {
+            let count = &mut self.counts[w * self.stride + c];
+            *count -= 1;
+            if *count == 0 {
+                self.saturation[w] -= 1;
+            }
+        }
+    }
+
+    /// Uncolored vertex with the highest saturation, ties by degree.
+    fn select(&self) -> usize {
+        (0..self.colors.len())
+            .filter(|&v| self.colors[v] == usize::MAX)
+            .max_by_key(|&v| (self.saturation[v], self.adjacency[v].len()))
+            .expect("an uncolored vertex remains")
+    }
+
+    /// Search every completion of the partial coloring, one stack frame per colored vertex.
+    ///
+    /// The search depth reaches the number of vertices, so frames live on the
+    /// heap rather than the call stack of the (possibly small) worker thread.
+    fn branch(&mut self, n_colored: usize, n_used: usize) {
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut n_used = n_used;
+        loop {
+            if n_colored + stack.len() == self.colors.len() {
+                if n_used < self.best_count {
+                    self.best.clone_from(&self.colors);
+                    self.best_count = n_used;
+                }
+            } else {
+                self.nodes += 1;
+                if self.nodes.is_multiple_of(1024) && Instant::now() >= self.deadline {
+                    self.timed_out = true;
+                }
+                if self.timed_out {
+                    return;
+                }
+                let v = self.select();
+                stack.push(Frame { v, c: 0, n_used });
+            }
+            // Move the deepest vertex to its next feasible color, backtracking when
+            // it has none left.
+            loop {
+                let Some(frame) = stack.last_mut() else {
+                    return;
+                };
+                let v = frame.v;
+                let assigned = self.colors[v];
+                if assigned != usize::MAX {
+                    self.unassign(v, assigned);
+                    if self.timed_out || self.best_count <= self.lower_bound {
+                        return;
+                    }
+                }
+                // Only colors that keep the partial coloring strictly below the incumbent.
+                let limit = (frame.n_used + 1).min(self.best_count - 1);
+                while frame.c < limit && self.counts[v * self.stride + frame.c] != 0 {
+                    frame.c += 1;
+                }
+                if frame.c < limit {
+                    let c = frame.c;
+                    frame.c += 1;
+                    n_used = frame.n_used.max(c + 1);
+                    self.assign(v, c);
+                    break;
+                }
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// Vertex branched on at one depth of the search.
+struct Frame {
+    v: usize,
+    /// Next color to try for `v`.
+    c: usize,
+    /// Colors used by the partial coloring before `v`.
+    n_used: usize,
+}
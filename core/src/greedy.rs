@@ -0,0 +1,176 @@
+//! Greedy coloring with selectable vertex orderings.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use ahash::AHashSet;
+
+use crate::adjacency::Adjacency;
+use crate::ordering;
+
+/// Strategy deciding in which order the greedy colorer visits vertices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexOrdering {
+    /// Visit vertices by index.
+    Natural,
+    /// Next color the vertex with the most distinct neighbor colors (DSATUR).
+    Dsatur,
+    /// DSATUR breaking saturation ties by higher degree before the index.
+    DsaturDegree,
+    /// Color in reverse order of repeated minimum-degree removal.
+    SmallestLast,
+    /// Color by decreasing degree (Welsh–Powell).
+    LargestFirst,
+    /// Next color the vertex with the most already-colored neighbors.
+    IncidenceDegree,
+    /// Random permutation drawn from the given seed.
+    Random(u64),
+    /// Reverse Cuthill–McKee order, keeping the sweep within a narrow band.
+    ReverseCuthillMcKee,
+}
+
+impl VertexOrdering {
+    /// Look up an ordering by name; `seed` drives the randomized orderings.
+    pub fn parse(name: &str, seed: u64) -> Result<Self, String> {
+        match name {
+            "natural" => Ok(Self::Natural),
+            "dsatur" => Ok(Self::Dsatur),
+            "dsatur_degree" => Ok(Self::DsaturDegree),
+            "smallest_last" => Ok(Self::SmallestLast),
+            "largest_first" => Ok(Self::LargestFirst),
+            "incidence_degree" => Ok(Self::IncidenceDegree),
+            "random" => Ok(Self::Random(seed)),
+            "rcm" => Ok(Self::ReverseCuthillMcKee),
+            _ => Err(format!(
+                "unknown ordering '{name}', expected one of: natural, dsatur, \
+                 dsatur_degree, smallest_last, largest_first, incidence_degree, random, rcm"
+            )),
+        }
+    }
+}
+
+/// Greedy coloring: smallest available color per vertex, visited per `ordering`.
+///
+/// Expects symmetric adjacency.
+pub fn greedy_color(adjacency: &Adjacency, ordering: VertexOrdering) -> Vec<usize> {
+    match ordering {
+        VertexOrdering::Natural => greedy_color_in_order(adjacency, 0..adjacency.len()),
+        VertexOrdering::Dsatur => dsatur_color(adjacency, false),
+        VertexOrdering::DsaturDegree => dsatur_color(adjacency, true),
+        VertexOrdering::SmallestLast => {
+            greedy_color_in_order(adjacency, ordering::smallest_last(adjacency))
+        }
+        VertexOrdering::LargestFirst => {
+            greedy_color_in_order(adjacency, ordering::largest_first(adjacency))
+        }
+        VertexOrdering::IncidenceDegree => {
+            greedy_color_in_order(adjacency, ordering::incidence_degree(adjacency))
+        }
+        VertexOrdering::Random(seed) => {
+            greedy_color_in_order(adjacency, ordering::random(adjacency.len(), seed))
+        }
+        VertexOrdering::ReverseCuthillMcKee => {
+            greedy_color_in_order(adjacency, ordering::reverse_cuthill_mckee(adjacency))
+        }
+    }
+}
+
+/// Simple greedy coloring: smallest available color per vertex, in the given order.
+pub fn greedy_color_in_order(
+    adjacency: &Adjacency,
+    order: impl IntoIterator<Item = usize>,
+) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut colors = vec![usize::MAX; n];
+    // forbidden[c] == i marks color c as unavailable for vertex i; reused
+    // across vertices, so no per-vertex clearing is needed.
+    let mut forbidden: Vec<usize> = Vec::new();
+
+    for i in order {
+        for &nb in &adjacency[i] {
+            let Some(&c) = colors.get(nb) else { continue };
+            if c != usize::MAX {
+                if c >= forbidden.len() {
+                    forbidden.resize(c + 1, usize::MAX);
+                }
+                forbidden[c] = i;
+            }
+        }
+        let mut c = 0;
+        while forbidden.get(c) == Some(&i) {
+            c += 1;
+        }
+        colors[i] = c;
+    }
+    colors
+}
+
+/// DSATUR: repeatedly color the uncolored vertex with the highest saturation degree.
+///
+/// Saturation is the number of distinct colors among colored neighbors; ties
+/// are broken by the higher degree if `by_degree`, then by the smallest
+/// vertex index.
+fn dsatur_color(adjacency: &Adjacency, by_degree: bool) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut colors = vec![usize::MAX; n];
+    let mut neighbor_colors: Vec<AHashSet<usize>> = vec![AHashSet::new(); n];
+    let tie = |v: usize| if by_degree { adjacency[v].len() } else { 0 };
+    // Lazy max-heap of (saturation, tie-break, vertex); outdated entries are skipped on pop.
+    let mut heap: BinaryHeap<(usize, usize, Reverse<usize>)> =
+        (0..n).map(|v| (0, tie(v), Reverse(v))).collect();
+
+    while let Some((saturation, _, Reverse(v))) = heap.pop() {
+        if colors[v] != usize::MAX || saturation != neighbor_colors[v].len() {
+            continue;
+        }
+        let mut c = 0;
+        while neighbor_colors[v].contains(&c) {
+            c += 1;
+        }
+        colors[v] = c;
+        for &w in &adjacency[v] {
+            if colors[w] == usize::MAX && neighbor_colors[w].insert(c) {
+                heap.push((neighbor_colors[w].len(), tie(w), Reverse(w)));
+            }
+        }
+    }
+    colors
+}
+
+/// Number of colors used, assuming colors `0..n` are all present.
+pub fn color_count(colors: &[usize]) -> usize {
+    colors.iter().max().map_or(0, |&c| c + 1)
+}
+
+/// Vertices of each color class, in increasing vertex order.
+pub fn color_classes(colors: &[usize]) -> Vec<Vec<usize>> {
+    let mut classes: Vec<Vec<usize>> = vec![Vec::new(); color_count(colors)];
+    for (v, &c) in colors.iter().enumerate() {
+        classes[c].push(v);
+    }
+    classes
+}
+
+/// Renumber colors so that classes holding higher-priority vertices come first.
+///
+/// Classes are ranked by the largest priority among their vertices.
+pub fn relabel_by_priority(colors: &[usize], priority: &[f64]) -> Vec<usize> {
+    let classes = color_classes(colors);
+    let top = |class: &Vec<usize>| {
+        class
+            .iter()
+            .map(|&v| priority[v])
+            .max_by(f64::total_cmp)
+            .unwrap_or(f64::NEG_INFINITY)
+    };
+    let mut rank: Vec<usize> = (0..classes.len()).collect();
+    rank.sort_by(|&a, &b| top(&classes[b]).total_cmp(&top(&classes[a])));
+
+    let mut relabeled = vec![0; colors.len()];
+    for (new, &old) in rank.iter().enumerate() {
+        for &v in &classes[old] {
+            relabeled[v] = new;
+        }
+    }
+    relabeled
+}
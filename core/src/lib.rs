@@ -0,0 +1,34 @@
+//! Graph construction, coloring algorithms and derivative recovery of
+//! `tatva-coloring`, usable from Rust without linking CPython.
+//!
+//! Patterns come in as [`adjacency::Adjacency`] neighbor lists and colorings
+//! go out as `Vec<usize>` with one color id per vertex. The Python module is
+//! a thin wrapper around this crate.
+
+pub mod acyclic;
+pub mod adjacency;
+pub mod balanced;
+pub mod banded;
+pub mod bipartite;
+pub mod constraints;
+pub mod distance2;
+pub mod distributed;
+pub mod exact;
+pub mod greedy;
+pub mod matrix_market;
+pub mod mesh;
+pub mod metis;
+pub mod options;
+pub mod ordering;
+pub mod parallel;
+pub mod partition;
+pub mod progress;
+pub mod recovery;
+pub mod refine;
+pub mod repair;
+pub mod rlf;
+pub mod rng;
+pub mod star;
+pub mod supervariables;
+pub mod timings;
+pub mod verify;
@@ -0,0 +1,115 @@
+//! Reading sparsity patterns from Matrix Market coordinate files.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::adjacency::Adjacency;
+
+/// Sparsity pattern of a Matrix Market file: rows, columns and sorted row-wise adjacency.
+pub struct MatrixMarketPattern {
+    pub n_rows: usize,
+    pub n_cols: usize,
+    pub rows: Adjacency,
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Read the pattern of a `coordinate` Matrix Market file, ignoring values.
+///
+/// Symmetric, skew-symmetric and Hermitian files store one triangle, which is
+/// mirrored; they must be square. Duplicate entries are merged.
+pub fn read_pattern(path: &Path) -> io::Result<MatrixMarketPattern> {
+    let text = fs::read_to_string(path)?;
+    let mut lines = text.lines();
+
+    let header = lines
+        .next()
+        .ok_or_else(|| invalid("empty Matrix Market file"))?
+        .to_ascii_lowercase();
+    let fields: Vec<&str> = header.split_ascii_whitespace().collect();
+    let [banner, "matrix", layout, _field, symmetry] = fields[..] else {
+        return Err(invalid(format!(
+            "malformed Matrix Market header '{header}'"
+        )));
+    };
+    if banner != "%%matrixmarket" {
+        return Err(invalid(format!(
+            "malformed Matrix Market header '{header}'"
+        )));
+    }
+    if layout != "coordinate" {
+        return Err(invalid("only coordinate Matrix Market files are supported"));
+    }
+    let mirror = match symmetry {
+        "general" => false,
+        "symmetric" | "skew-symmetric" | "hermitian" => true,
+        _ => {
+            return Err(invalid(format!(
+                "unknown Matrix Market symmetry '{symmetry}'"
+            )));
+        }
+    };
+
+    let mut entries = lines.filter(|line| {
+        let line = line.trim_start();
+        !line.is_empty() && !line.starts_with('%')
+    });
+    let size = entries
+        .next()
+        .ok_or_else(|| invalid("missing Matrix Market size line"))?;
+    let size: Vec<usize> = size
+        .split_ascii_whitespace()
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| invalid(format!("invalid size line '{size}'")))
+        })
+        .collect::<io::Result<_>>()?;
+    let [n_rows, n_cols, nnz] = size[..] else {
+        return Err(invalid(
+            "Matrix Market size line must hold rows, columns and entries",
+        ));
+    };
+    if mirror && n_rows != n_cols {
+        return Err(invalid(format!(
+            "{symmetry} Matrix Market file must be square, got {n_rows} x {n_cols}"
+        )));
+    }
+
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    let mut count = 0;
+    for line in entries {
+        let mut tokens = line.split_ascii_whitespace();
+        let mut position = |bound: usize| -> io::Result<usize> {
+            tokens
+                .next()
+                .and_then(|token| token.parse::<usize>().ok())
+                .filter(|&k| (1..=bound).contains(&k))
+                .map(|k| k - 1)
+                .ok_or_else(|| invalid(format!("invalid Matrix Market entry '{line}'")))
+        };
+        let i = position(n_rows)?;
+        let j = position(n_cols)?;
+        pairs.push((i, j));
+        if mirror && i != j {
+            pairs.push((j, i));
+        }
+        count += 1;
+    }
+    if count != nnz {
+        return Err(invalid(format!(
+            "Matrix Market file declares {nnz} entries but holds {count}"
+        )));
+    }
+
+    let mut rows = Adjacency::from_pairs(n_rows, pairs);
+    rows.sort_dedup_rows();
+    Ok(MatrixMarketPattern {
+        n_rows,
+        n_cols,
+        rows,
+    })
+}
@@ -0,0 +1,126 @@
+//! Colorings driven by element-to-node connectivity, without an assembled matrix.
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+use crate::bipartite::transpose;
+
+/// Sparsity pattern of the matrix assembled from element connectivity.
+///
+/// Row `v` lists every node sharing an element with `v`, itself included,
+/// in increasing order; nodes in no element get an empty row. Rows are
+/// counted and then filled in parallel, so no per-element entry list is
+/// ever materialized.
+pub fn element_pattern(elements: &Adjacency, n_nodes: usize) -> Adjacency {
+    let node_elements = transpose(elements, n_nodes);
+    // seen[w] == v marks w as already coupled to v.
+    let coupled = |v: usize, seen: &mut Vec<usize>, visit: &mut dyn FnMut(usize)| {
+        for &e in &node_elements[v] {
+            for &w in &elements[e] {
+                if seen[w] != v {
+                    seen[w] = v;
+                    visit(w);
+                }
+            }
+        }
+    };
+    let degrees: Vec<usize> = (0..n_nodes)
+        .into_par_iter()
+        .map_init(
+            || vec![usize::MAX; n_nodes],
+            |seen, v| {
+                let mut degree = 0;
+                coupled(v, seen, &mut |_| degree += 1);
+                degree
+            },
+        )
+        .collect();
+    let mut pattern = Adjacency::with_degrees(&degrees);
+    pattern
+        .rows_mut()
+        .into_par_iter()
+        .enumerate()
+        .for_each_init(
+            || vec![usize::MAX; n_nodes],
+            |seen, (v, row)| {
+                let mut next = 0;
+                coupled(v, seen, &mut |w| {
+                    row[next] = w;
+                    next += 1;
+                });
+                row.sort_unstable();
+            },
+        );
+    pattern
+}
+
+/// Distance-2 node graph implied by element connectivity.
+///
+/// Nodes are adjacent when they share an element, as in the assembled matrix;
+/// the result joins nodes at most two such hops apart. Returns sorted lists
+/// without self loops.
+pub fn element_distance2_adjacency(elements: &Adjacency, n_nodes: usize) -> Adjacency {
+    let node_elements = transpose(elements, n_nodes);
+    // seen_node[w] == v / seen_element[e] == v mark w or e as visited from v.
+    let mut seen_node = vec![usize::MAX; n_nodes];
+    let mut seen_element = vec![usize::MAX; elements.len()];
+    let mut first_hop: Vec<usize> = Vec::new();
+    let mut neighs: Vec<usize> = Vec::new();
+    let mut adjacency = Adjacency::new();
+
+    for v in 0..n_nodes {
+        seen_node[v] = v;
+        first_hop.clear();
+        let mut visit = |e: usize, out: &mut Vec<usize>| {
+            if seen_element[e] == v {
+                return;
+            }
+            seen_element[e] = v;
+            for &w in &elements[e] {
+                if seen_node[w] != v {
+                    seen_node[w] = v;
+                    out.push(w);
+                }
+            }
+        };
+        for &e in &node_elements[v] {
+            visit(e, &mut first_hop);
+        }
+        neighs.clone_from(&first_hop);
+        for &w in &first_hop {
+            for &e in &node_elements[w] {
+                visit(e, &mut neighs);
+            }
+        }
+        neighs.sort_unstable();
+        adjacency.push_row(neighs.iter().copied());
+    }
+    adjacency
+}
+
+/// Cell graph implied by cell-to-vertex connectivity: cells sharing a vertex are adjacent.
+///
+/// Returns sorted lists without self loops.
+pub fn cell_adjacency(cells: &Adjacency, n_vertices: usize) -> Adjacency {
+    let vertex_cells = transpose(cells, n_vertices);
+    // seen[d] == c marks cell d as already adjacent to cell c.
+    let mut seen = vec![usize::MAX; cells.len()];
+    let mut neighs: Vec<usize> = Vec::new();
+    let mut adjacency = Adjacency::new();
+
+    for c in 0..cells.len() {
+        seen[c] = c;
+        neighs.clear();
+        for &v in &cells[c] {
+            for &d in &vertex_cells[v] {
+                if seen[d] != c {
+                    seen[d] = c;
+                    neighs.push(d);
+                }
+            }
+        }
+        neighs.sort_unstable();
+        adjacency.push_row(neighs.iter().copied());
+    }
+    adjacency
+}
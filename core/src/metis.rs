@@ -0,0 +1,25 @@
+//! Writing graphs in the METIS format read by METIS, ParMETIS and KaHIP.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::adjacency::Adjacency;
+
+/// Write an unweighted symmetric `graph` without self loops as a METIS graph file.
+///
+/// The header holds the vertex and edge counts; line `v + 1` then lists the
+/// 1-based neighbors of vertex `v`, so every edge appears twice.
+pub fn write_graph(path: &Path, graph: &Adjacency) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "{} {}", graph.len(), graph.neighbors().len() / 2)?;
+    for row in graph.iter() {
+        let mut sep = "";
+        for &j in row {
+            write!(out, "{sep}{}", j + 1)?;
+            sep = " ";
+        }
+        writeln!(out)?;
+    }
+    out.flush()
+}
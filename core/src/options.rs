@@ -0,0 +1,413 @@
+//! Coloring strategy shared by the greedy-based entry points.
+
+use std::fmt;
+use std::time::Duration;
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+use crate::balanced::balanced_color;
+use crate::banded::{banded_color, cyclic_colors};
+use crate::distance2::Distance2Graph;
+use crate::exact::{EXACT_MAX_VERTICES, exact_color};
+use crate::greedy::{
+    VertexOrdering, color_count, greedy_color, greedy_color_in_order, relabel_by_priority,
+};
+use crate::ordering::{self, by_priority};
+use crate::parallel::{deterministic_speculative_color, jones_plassmann_color, speculative_color};
+use crate::progress::{Cancelled, Phase, Progress};
+use crate::refine::{Refinement, refine};
+use crate::rlf::rlf_color;
+use crate::timings::Timings;
+
+/// Algorithm producing the initial coloring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Sequential greedy coloring in the selected vertex ordering.
+    Greedy,
+    /// Branch-and-bound search for a minimum coloring, within a time budget.
+    Exact,
+    /// Greedy coloring that keeps color classes near-equal in size.
+    Balanced,
+    /// Recursive Largest First: one maximal independent set per color.
+    Rlf,
+    /// Parallel Jones–Plassmann rounds with random vertex weights.
+    JonesPlassmann,
+    /// Parallel speculative coloring with conflict repair (Gebremedhin–Manne).
+    Speculative,
+    /// Cyclic colors over the bandwidth, optimal for full bands.
+    Banded,
+}
+
+impl Algorithm {
+    /// Look up an algorithm by name.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "greedy" => Ok(Self::Greedy),
+            "exact" => Ok(Self::Exact),
+            "balanced" => Ok(Self::Balanced),
+            "rlf" => Ok(Self::Rlf),
+            "jones_plassmann" => Ok(Self::JonesPlassmann),
+            "speculative" => Ok(Self::Speculative),
+            "banded" => Ok(Self::Banded),
+            _ => Err(format!(
+                "unknown algorithm '{name}', expected one of: \
+                 greedy, exact, balanced, rlf, jones_plassmann, speculative, banded"
+            )),
+        }
+    }
+}
+
+/// Strategy selected through the Python keyword arguments.
+pub struct ColoringOptions {
+    pub algorithm: Algorithm,
+    pub ordering: VertexOrdering,
+    /// Seed of the randomized algorithms.
+    pub seed: u64,
+    pub refine: Option<Refinement>,
+    /// Budget for `Algorithm::Exact`.
+    pub time_limit: Duration,
+    /// Make `Algorithm::Speculative` independent of the thread count.
+    pub deterministic: bool,
+    /// Per-vertex priority: higher values are colored first and end up in lower colors.
+    pub priority: Option<Vec<f64>>,
+    /// Runs of `Algorithm::Greedy`, the first as configured and the others in
+    /// random orders drawn from `seed`; the one with the fewest colors wins.
+    pub restarts: usize,
+    /// Colors `color_distance2` should not exceed; above them it escalates
+    /// through stronger strategies and returns the best coloring found.
+    pub max_colors: Option<usize>,
+    /// Colors `color_distance2` keeps, `usize::MAX` marking the free vertices
+    /// it colors around them; priorities then only order the free vertices.
+    pub precolors: Option<Vec<usize>>,
+    /// Bitmask of the colors each vertex may take in `color_distance2`.
+    pub allowed: Option<Vec<u64>>,
+}
+
+/// Random restarts of the greedy step of the escalation to `max_colors`.
+const ESCALATION_RESTARTS: usize = 16;
+/// Tabu iterations of the escalation unless `refine` configures tabu search.
+const ESCALATION_TABU_ITERATIONS: usize = 10_000;
+
+impl ColoringOptions {
+    /// Color a symmetric adjacency with the selected strategy.
+    pub fn color(&self, adjacency: &Adjacency) -> Vec<usize> {
+        let colors = match self.algorithm {
+            Algorithm::Greedy => {
+                fewest_colors((0..self.restarts.max(1)).into_par_iter().map(|run| {
+                    match (&self.priority, run) {
+                        (Some(priority), 0) => {
+                            greedy_color_in_order(adjacency, by_priority(priority))
+                        }
+                        (None, 0) => greedy_color(adjacency, self.ordering),
+                        _ => greedy_color_in_order(
+                            adjacency,
+                            self.restart_order(adjacency.len(), run),
+                        ),
+                    }
+                }))
+            }
+            Algorithm::Exact => exact_color(adjacency, self.time_limit),
+            Algorithm::Balanced => balanced_color(adjacency, self.ordering),
+            Algorithm::Rlf => rlf_color(adjacency),
+            Algorithm::JonesPlassmann => jones_plassmann_color(adjacency, self.seed),
+            Algorithm::Speculative if self.deterministic => {
+                deterministic_speculative_color(adjacency, self.seed)
+            }
+            Algorithm::Speculative => speculative_color(adjacency),
+            Algorithm::Banded => banded_color(adjacency),
+        };
+        let colors = match self.refine {
+            Some(refinement) => refine(adjacency, colors, refinement),
+            None => colors,
+        };
+        match &self.priority {
+            Some(priority) => relabel_by_priority(&colors, priority),
+            None => colors,
+        }
+    }
+
+    /// Color a distance-2 graph, materializing it only if the strategy needs it.
+    ///
+    /// Greedy coloring in a fixed order (natural, random, largest first, RCM
+    /// or by priority) without refinement walks the 2-hop neighborhoods on the fly
+    /// and banded coloring only reads the bandwidth off the pattern;
+    /// everything else colors the explicit adjacency, which `low_memory`
+    /// forbids.
+    ///
+    /// Records the "distance2" (degrees or explicit graph), "ordering" (fixed
+    /// orders only; dynamic ones are part of coloring) and "coloring" stages.
+    /// `progress` sees the "degrees", "distance2" and "coloring" phases; the
+    /// strategies coloring the explicit graph only report the start and end
+    /// of coloring. `precolors` and `allowed` colors are only supported by
+    /// the fixed orders; with `allowed`, runs leaving a vertex without an
+    /// allowed color are dropped.
+    ///
+    /// With `max_colors`, a coloring above it is followed by greedy runs in
+    /// the largest first, smallest last, DSATUR and DSATUR-by-degree orders
+    /// and by random restarts, then by iterated greedy, Kempe and tabu
+    /// refinement of the best coloring and, on graphs of at most
+    /// `EXACT_MAX_VERTICES` vertices, an exact search within `time_limit`,
+    /// stopping as soon as a coloring fits. Steps needing the
+    /// explicit graph are skipped under `low_memory`, with `precolors` or
+    /// with `allowed` colors.
+    pub fn color_distance2(
+        &self,
+        graph: &Distance2Graph,
+        low_memory: bool,
+        timings: &mut Timings,
+        progress: &dyn Progress,
+    ) -> Result<Vec<usize>, Distance2Error> {
+        let colors = self.color_distance2_once(graph, low_memory, timings, progress)?;
+        match self.max_colors {
+            Some(max_colors) if color_count(&colors) > max_colors => {
+                self.escalate(graph, colors, max_colors, low_memory, timings, progress)
+            }
+            _ => Ok(colors),
+        }
+    }
+
+    /// `color_distance2` with the configured strategy alone.
+    fn color_distance2_once(
+        &self,
+        graph: &Distance2Graph,
+        low_memory: bool,
+        timings: &mut Timings,
+        progress: &dyn Progress,
+    ) -> Result<Vec<usize>, Distance2Error> {
+        let n = graph.n_vertices();
+        if self.algorithm == Algorithm::Banded && self.refine.is_none() {
+            if self.precolors.is_some() || self.allowed.is_some() {
+                return Err(Distance2Error::Constrained);
+            }
+            return Ok(timings.time("coloring", || {
+                let colors = cyclic_colors(n, graph.bandwidth());
+                match &self.priority {
+                    Some(priority) => relabel_by_priority(&colors, priority),
+                    None => colors,
+                }
+            }));
+        }
+        let order = match (self.algorithm, self.refine, &self.priority, self.ordering) {
+            (Algorithm::Greedy, None, Some(priority), _) => {
+                timings.time("ordering", || by_priority(priority))
+            }
+            (Algorithm::Greedy, None, None, VertexOrdering::Natural) => (0..n).collect(),
+            (Algorithm::Greedy, None, None, VertexOrdering::Random(seed)) => {
+                timings.time("ordering", || ordering::random(n, seed))
+            }
+            (Algorithm::Greedy, None, None, VertexOrdering::LargestFirst) => {
+                let degrees =
+                    timings.time("distance2", || graph.degrees_with_progress(progress))?;
+                timings.time("ordering", || ordering::by_degree(degrees))
+            }
+            (Algorithm::Greedy, None, None, VertexOrdering::ReverseCuthillMcKee) => {
+                timings.time("ordering", || graph.reverse_cuthill_mckee())
+            }
+            _ if self.precolors.is_some() || self.allowed.is_some() => {
+                return Err(Distance2Error::Constrained);
+            }
+            _ if low_memory => return Err(Distance2Error::LowMemory),
+            _ => {
+                let adjacency =
+                    timings.time("distance2", || graph.adjacency_with_progress(progress))?;
+                return timings.time("coloring", || {
+                    let phase = Phase::start(progress, "coloring", n)?;
+                    let colors = self.color(adjacency);
+                    phase.finish()?;
+                    Ok(colors)
+                });
+            }
+        };
+        timings.time("coloring", || {
+            // Precolored vertices keep their colors and drop out of every order.
+            let precolors = self.precolors.as_deref();
+            let free = |order: Vec<usize>| match precolors {
+                Some(precolors) => order
+                    .into_iter()
+                    .filter(|&v| precolors[v] == usize::MAX)
+                    .collect(),
+                None => order,
+            };
+            let order = free(order);
+            let runs = self.restarts.max(1);
+            let phase = Phase::start(progress, "coloring", order.len() * runs)?;
+            let runs = (0..runs)
+                .into_par_iter()
+                .map(|run| {
+                    let mut colors = precolors.map_or_else(|| vec![usize::MAX; n], <[_]>::to_vec);
+                    let order = match run {
+                        0 => order.clone(),
+                        _ => free(self.restart_order(n, run)),
+                    };
+                    let allowed = self.allowed.as_deref();
+                    graph.color_vertices(&mut colors, order, allowed, || phase.step())?;
+                    Ok(colors)
+                })
+                .collect::<Result<Vec<_>, Cancelled>>()?;
+            phase.finish()?;
+            let uncolored = |colors: &Vec<usize>| colors.iter().position(|&c| c == usize::MAX);
+            if let Some(v) = runs.iter().map(uncolored).collect::<Option<Vec<_>>>() {
+                return Err(Distance2Error::NoAllowedColor(v[0]));
+            }
+            let complete: Vec<_> = runs
+                .into_iter()
+                .filter(|colors| uncolored(colors).is_none())
+                .collect();
+            let colors = fewest_colors(complete.into_par_iter());
+            Ok(match (&self.priority, precolors) {
+                (Some(priority), None) => relabel_by_priority(&colors, priority),
+                _ => colors,
+            })
+        })
+    }
+
+    /// Improve on `best` until it has at most `max_colors` colors or every step is spent.
+    fn escalate(
+        &self,
+        graph: &Distance2Graph,
+        mut best: Vec<usize>,
+        max_colors: usize,
+        low_memory: bool,
+        timings: &mut Timings,
+        progress: &dyn Progress,
+    ) -> Result<Vec<usize>, Distance2Error> {
+        // A priority would override every ordering, so the steps run without
+        // one and their colors are relabeled by it afterwards instead.
+        let greedy = |ordering| ColoringOptions {
+            algorithm: Algorithm::Greedy,
+            ordering,
+            refine: None,
+            priority: None,
+            restarts: 1,
+            max_colors: None,
+            precolors: self.precolors.clone(),
+            allowed: self.allowed.clone(),
+            ..*self
+        };
+        let steps = [
+            greedy(VertexOrdering::LargestFirst),
+            greedy(VertexOrdering::SmallestLast),
+            greedy(VertexOrdering::Dsatur),
+            greedy(VertexOrdering::DsaturDegree),
+            ColoringOptions {
+                restarts: ESCALATION_RESTARTS,
+                ..greedy(VertexOrdering::Random(self.seed))
+            },
+        ];
+        let relabel = |colors: Vec<usize>| match (&self.priority, &self.precolors) {
+            (Some(priority), None) => relabel_by_priority(&colors, priority),
+            _ => colors,
+        };
+        for options in steps {
+            let colors = match options.color_distance2_once(graph, low_memory, timings, progress) {
+                Err(
+                    Distance2Error::LowMemory
+                    | Distance2Error::Constrained
+                    | Distance2Error::NoAllowedColor(_),
+                ) => continue,
+                colors => relabel(colors?),
+            };
+            if color_count(&colors) < color_count(&best) {
+                best = colors;
+            }
+            if color_count(&best) <= max_colors {
+                return Ok(best);
+            }
+        }
+        if low_memory || self.precolors.is_some() || self.allowed.is_some() {
+            return Ok(best);
+        }
+
+        let adjacency = timings.time("distance2", || graph.adjacency_with_progress(progress))?;
+        let iterations = match self.refine {
+            Some(Refinement::Tabu { iterations, .. }) => iterations,
+            _ => ESCALATION_TABU_ITERATIONS,
+        };
+        let refinements = [
+            Refinement::IteratedGreedy,
+            Refinement::Kempe,
+            Refinement::Tabu {
+                iterations,
+                seed: self.seed,
+            },
+        ];
+        for refinement in refinements {
+            best = timings.time("coloring", || {
+                let phase = Phase::start(progress, "coloring", adjacency.len())?;
+                let colors = relabel(refine(adjacency, best, refinement));
+                phase.finish()?;
+                Ok::<_, Cancelled>(colors)
+            })?;
+            if color_count(&best) <= max_colors {
+                return Ok(best);
+            }
+        }
+        if adjacency.len() > EXACT_MAX_VERTICES {
+            return Ok(best);
+        }
+        let colors = timings.time("coloring", || {
+            let phase = Phase::start(progress, "coloring", adjacency.len())?;
+            let colors = relabel(exact_color(adjacency, self.time_limit));
+            phase.finish()?;
+            Ok::<_, Cancelled>(colors)
+        })?;
+        Ok(if color_count(&colors) < color_count(&best) {
+            colors
+        } else {
+            best
+        })
+    }
+
+    /// Random vertex order of restart `run` over `n` vertices.
+    fn restart_order(&self, n: usize, run: usize) -> Vec<usize> {
+        ordering::random(n, self.seed.wrapping_add(run as u64))
+    }
+}
+
+/// Coloring with the fewest colors, the earliest run on ties.
+fn fewest_colors(runs: impl IndexedParallelIterator<Item = Vec<usize>>) -> Vec<usize> {
+    runs.min_by_key(|colors| color_count(colors))
+        .expect("at least one run")
+}
+
+/// Why `ColoringOptions::color_distance2` returned no coloring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distance2Error {
+    /// The strategy needs the explicit distance-2 graph, which `low_memory` forbids.
+    LowMemory,
+    /// The strategy cannot keep `precolors` or `allowed` colors.
+    Constrained,
+    /// Every run left this vertex without an allowed color.
+    NoAllowedColor(usize),
+    /// The progress observer stopped the computation.
+    Cancelled,
+}
+
+impl From<Cancelled> for Distance2Error {
+    fn from(_: Cancelled) -> Self {
+        Self::Cancelled
+    }
+}
+
+impl fmt::Display for Distance2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LowMemory => f.write_str(
+                "low_memory requires algorithm 'greedy' with ordering 'natural', \
+                 'random', 'largest_first' or 'rcm' (or a priority) and no refine",
+            ),
+            Self::Constrained => f.write_str(
+                "precolors and allowed colors require algorithm 'greedy' with ordering \
+                 'natural', 'random', 'largest_first' or 'rcm' (or a priority) and no refine",
+            ),
+            Self::NoAllowedColor(v) => write!(
+                f,
+                "vertex {v} has no allowed color left by its distance-2 neighbors"
+            ),
+            Self::Cancelled => f.write_str("coloring cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Distance2Error {}
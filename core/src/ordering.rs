@@ -0,0 +1,197 @@
+//! Static vertex orderings for the greedy colorer.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::adjacency::Adjacency;
+use crate::rng::SplitMix64;
+
+/// Largest-first ordering (Welsh–Powell): decreasing degree, ties by index.
+pub fn largest_first(adjacency: &Adjacency) -> Vec<usize> {
+    let degrees: Vec<usize> = adjacency.iter().map(<[usize]>::len).collect();
+    by_degree(&degrees)
+}
+
+/// `largest_first` from precomputed vertex degrees.
+pub fn by_degree(degrees: &[usize]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..degrees.len()).collect();
+    order.sort_by_key(|&v| Reverse(degrees[v]));
+    order
+}
+
+/// Priority ordering: decreasing priority, ties by index.
+pub fn by_priority(priority: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..priority.len()).collect();
+    order.sort_by(|&a, &b| priority[b].total_cmp(&priority[a]));
+    order
+}
+
+/// Smallest-last ordering (Matula–Beck).
+///
+/// Repeatedly removes a vertex of minimum degree in the remaining graph; the
+/// removal sequence is then colored back to front.
+pub fn smallest_last(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut degree: Vec<usize> = adjacency.iter().map(<[usize]>::len).collect();
+    let max_degree = degree.iter().copied().max().unwrap_or(0);
+
+    // Bucket queue keyed by current degree; stale entries are skipped on pop.
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); max_degree + 1];
+    for v in (0..n).rev() {
+        buckets[degree[v]].push(v);
+    }
+    let mut removed = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut d = 0;
+
+    while order.len() < n {
+        let Some(v) = buckets[d].pop() else {
+            d += 1;
+            continue;
+        };
+        if removed[v] || degree[v] != d {
+            continue;
+        }
+        removed[v] = true;
+        order.push(v);
+        for &w in &adjacency[v] {
+            if !removed[w] {
+                degree[w] -= 1;
+                buckets[degree[w]].push(w);
+                d = d.min(degree[w]);
+            }
+        }
+    }
+    order.reverse();
+    order
+}
+
+/// Incidence-degree ordering.
+///
+/// Repeatedly picks the vertex with the most already-ordered neighbors; ties
+/// are broken by the smallest vertex index.
+pub fn incidence_degree(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut incidence = vec![0usize; n];
+    let mut ordered = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    // Lazy max-heap of (incidence, vertex); outdated entries are skipped on pop.
+    let mut heap: BinaryHeap<(usize, Reverse<usize>)> = (0..n).map(|v| (0, Reverse(v))).collect();
+
+    while let Some((inc, Reverse(v))) = heap.pop() {
+        if ordered[v] || inc != incidence[v] {
+            continue;
+        }
+        ordered[v] = true;
+        order.push(v);
+        for &w in &adjacency[v] {
+            if !ordered[w] {
+                incidence[w] += 1;
+                heap.push((incidence[w], Reverse(w)));
+            }
+        }
+    }
+    order
+}
+
+/// Uniformly random permutation, reproducible for a given `seed`.
+pub fn random(n: usize, seed: u64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..n).collect();
+    SplitMix64::new(seed).shuffle(&mut order);
+    order
+}
+
+/// Reverse Cuthill–McKee ordering of a symmetric graph.
+///
+/// Each connected component is traversed breadth-first from a
+/// pseudo-peripheral vertex (George–Liu), appending the unvisited neighbors of
+/// every vertex by increasing degree; reversing the sequence reduces the
+/// profile further. `order[k]` is the vertex placed at position `k`, as for
+/// `scipy.sparse.csgraph.reverse_cuthill_mckee`.
+pub fn reverse_cuthill_mckee(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let degree: Vec<usize> = adjacency.iter().map(<[usize]>::len).collect();
+    let mut roots: Vec<usize> = (0..n).collect();
+    roots.sort_by_key(|&v| degree[v]);
+    let mut level = vec![usize::MAX; n];
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+
+    for root in roots {
+        if visited[root] {
+            continue;
+        }
+        let start = pseudo_peripheral(adjacency, root, &degree, &mut level);
+        visited[start] = true;
+        let mut head = order.len();
+        order.push(start);
+        while head < order.len() {
+            let v = order[head];
+            head += 1;
+            let first = order.len();
+            for &w in &adjacency[v] {
+                if !visited[w] {
+                    visited[w] = true;
+                    order.push(w);
+                }
+            }
+            order[first..].sort_unstable_by_key(|&w| (degree[w], w));
+        }
+    }
+    order.reverse();
+    order
+}
+
+/// Vertex of the component of `root` whose breadth-first level structure is deepest.
+///
+/// Starts at `root` and moves to a vertex of smallest degree on the last
+/// level while that deepens the structure. `level` is all `usize::MAX` on
+/// entry and exit.
+fn pseudo_peripheral(
+    adjacency: &Adjacency,
+    root: usize,
+    degree: &[usize],
+    level: &mut [usize],
+) -> usize {
+    let mut start = root;
+    let (mut depth, mut last) = last_level(adjacency, start, level);
+    loop {
+        let candidate = last
+            .iter()
+            .copied()
+            .min_by_key(|&v| (degree[v], v))
+            .expect("the last level is never empty");
+        let (candidate_depth, candidate_last) = last_level(adjacency, candidate, level);
+        if candidate_depth <= depth {
+            return start;
+        }
+        (start, depth, last) = (candidate, candidate_depth, candidate_last);
+    }
+}
+
+/// Depth of the breadth-first level structure from `start` and its deepest level.
+fn last_level(adjacency: &Adjacency, start: usize, level: &mut [usize]) -> (usize, Vec<usize>) {
+    let mut queue = vec![start];
+    level[start] = 0;
+    let mut head = 0;
+    while head < queue.len() {
+        let v = queue[head];
+        head += 1;
+        for &w in &adjacency[v] {
+            if level[w] == usize::MAX {
+                level[w] = level[v] + 1;
+                queue.push(w);
+            }
+        }
+    }
+    let depth = level[queue[queue.len() - 1]];
+    let last = queue
+        .iter()
+        .copied()
+        .filter(|&v| level[v] == depth)
+        .collect();
+    for &v in &queue {
+        level[v] = usize::MAX;
+    }
+    (depth, last)
+}
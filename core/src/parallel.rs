@@ -0,0 +1,132 @@
+//! Shared-memory parallel coloring.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+use crate::rng::hash64;
+
+/// Jones–Plassmann coloring with random vertex weights drawn from `seed`.
+///
+/// In every round, each uncolored vertex whose (weight, index) beats all its
+/// uncolored neighbors takes the smallest color free among its neighbors.
+/// Those vertices form an independent set, so a round runs fully in parallel,
+/// and the result depends only on `seed`, not on the thread count.
+/// Expects symmetric adjacency.
+pub fn jones_plassmann_color(adjacency: &Adjacency, seed: u64) -> Vec<usize> {
+    let n = adjacency.len();
+    let weights: Vec<(u64, usize)> = (0..n)
+        .into_par_iter()
+        .map(|v| (hash64(seed, v as u64), v))
+        .collect();
+    let mut colors = vec![usize::MAX; n];
+    let mut uncolored: Vec<usize> = (0..n).collect();
+
+    while !uncolored.is_empty() {
+        let colors_ref = &colors;
+        let winners: Vec<(usize, usize)> = uncolored
+            .par_iter()
+            .filter(|&&v| {
+                adjacency[v]
+                    .iter()
+                    .all(|&w| colors_ref[w] != usize::MAX || weights[w] < weights[v])
+            })
+            .map(|&v| (v, smallest_free_color(&adjacency[v], |w| colors_ref[w])))
+            .collect();
+        for (v, c) in winners {
+            colors[v] = c;
+        }
+        uncolored.retain(|&v| colors[v] == usize::MAX);
+    }
+    colors
+}
+
+/// Gebremedhin–Manne speculative coloring.
+///
+/// All pending vertices are colored concurrently from whatever neighbor colors
+/// they observe; the conflicts this races into are then detected in parallel
+/// and the larger-index endpoint is queued for the next round. The smallest
+/// pending vertex never conflicts, so the queue shrinks every round. The
+/// result may differ between runs and thread counts. Expects symmetric adjacency.
+pub fn speculative_color(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let colors: Vec<AtomicUsize> = (0..n).map(|_| AtomicUsize::new(usize::MAX)).collect();
+    let color_of = |w: usize| colors[w].load(Ordering::Relaxed);
+    let mut pending: Vec<usize> = (0..n).collect();
+
+    while !pending.is_empty() {
+        pending.par_iter().for_each(|&v| {
+            let c = smallest_free_color(&adjacency[v], color_of);
+            colors[v].store(c, Ordering::Relaxed);
+        });
+        pending = pending
+            .into_par_iter()
+            .filter(|&v| {
+                let c = color_of(v);
+                adjacency[v].iter().any(|&w| w < v && color_of(w) == c)
+            })
+            .collect();
+        pending
+            .par_iter()
+            .for_each(|&v| colors[v].store(usize::MAX, Ordering::Relaxed));
+    }
+    colors.into_iter().map(AtomicUsize::into_inner).collect()
+}
+
+/// Speculative coloring whose result depends only on `seed`, not on the thread count.
+///
+/// Each round colors all pending vertices from a snapshot of the previous
+/// round's colors instead of racing on live ones, so every vertex sees the same
+/// neighbor colors however the work is scheduled. Of two pending neighbors
+/// that picked the same color, the one with the smaller random (weight, index)
+/// drawn from `seed` is queued again, rather than whichever thread came second.
+/// The heaviest pending vertex never loses, so the queue shrinks every round.
+/// Expects symmetric adjacency.
+pub fn deterministic_speculative_color(adjacency: &Adjacency, seed: u64) -> Vec<usize> {
+    let n = adjacency.len();
+    let weight = |v: usize| (hash64(seed, v as u64), v);
+    let mut colors = vec![usize::MAX; n];
+    let mut pending: Vec<usize> = (0..n).collect();
+
+    while !pending.is_empty() {
+        let colors_ref = &colors;
+        let tentative: Vec<usize> = pending
+            .par_iter()
+            .map(|&v| smallest_free_color(&adjacency[v], |w| colors_ref[w]))
+            .collect();
+        for (&v, &c) in pending.iter().zip(&tentative) {
+            colors[v] = c;
+        }
+        // Colors fixed before this round are free of conflicts by construction,
+        // so only pairs of vertices colored in this round can clash.
+        let colors_ref = &colors;
+        let losers: Vec<usize> = pending
+            .par_iter()
+            .copied()
+            .filter(|&v| {
+                let c = colors_ref[v];
+                adjacency[v]
+                    .iter()
+                    .any(|&w| colors_ref[w] == c && weight(w) > weight(v))
+            })
+            .collect();
+        for &v in &losers {
+            colors[v] = usize::MAX;
+        }
+        pending = losers;
+    }
+    colors
+}
+
+/// Smallest color not used by any colored vertex in `neighbors`.
+pub fn smallest_free_color(neighbors: &[usize], color_of: impl Fn(usize) -> usize) -> usize {
+    // A vertex of degree d always finds a free color among 0..=d.
+    let mut used = vec![false; neighbors.len() + 1];
+    for &w in neighbors {
+        if let Some(slot) = used.get_mut(color_of(w)) {
+            *slot = true;
+        }
+    }
+    used.iter().position(|&u| !u).unwrap_or(neighbors.len())
+}
@@ -0,0 +1,99 @@
+//! Colorings whose color classes each stay inside one subdomain.
+//!
+//! Every subdomain is colored on its own with a range of colors no other
+//! subdomain uses, so a batch of probes per color never straddles two
+//! subdomains. Conflicts between subdomains are settled by the disjoint
+//! ranges, at the price of more colors overall.
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+use crate::options::ColoringOptions;
+
+/// Check that every subdomain id of `partition` is below its length.
+///
+/// Ids index the color ranges, so a sparse labeling such as global
+/// subdomain numbers would size them by its largest id; it must be
+/// renumbered densely first.
+pub fn check_partition(partition: &[usize]) -> Result<(), String> {
+    let n = partition.len();
+    match partition.iter().position(|&p| p >= n) {
+        Some(v) => Err(format!(
+            "partition[{v}] = {} must be below the number of vertices {n}; \
+             renumber the subdomains from 0",
+            partition[v]
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Color the symmetric `adjacency` one subdomain of `partition` at a time.
+///
+/// Returns the colors and the offsets of the color ranges: subdomain `p`
+/// uses colors `offsets[p]..offsets[p + 1]`. The ranges follow each other
+/// without gaps, or, with a `budget`, subdomain `p` owns the fixed range
+/// `p * budget..(p + 1) * budget` and must fit into it. Each subdomain is
+/// colored with `options`, its priorities restricted to its vertices.
+/// Fails unless `check_partition` passes.
+pub fn partitioned_colors(
+    adjacency: &Adjacency,
+    partition: &[usize],
+    budget: Option<usize>,
+    options: &ColoringOptions,
+) -> Result<(Vec<usize>, Vec<usize>), String> {
+    check_partition(partition)?;
+    let n_parts = partition.iter().max().map_or(0, |&p| p + 1);
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); n_parts];
+    let mut local = vec![0; partition.len()];
+    for (v, &p) in partition.iter().enumerate() {
+        local[v] = members[p].len();
+        members[p].push(v);
+    }
+
+    let local: &[usize] = &local;
+    let part_colors: Vec<Vec<usize>> = members
+        .par_iter()
+        .enumerate()
+        .map(|(p, vertices)| {
+            let pairs = vertices.iter().enumerate().flat_map(|(i, &v)| {
+                adjacency[v]
+                    .iter()
+                    .filter(|&&w| partition[w] == p)
+                    .map(move |&w| (i, local[w]))
+            });
+            let subgraph = Adjacency::from_pairs(vertices.len(), pairs);
+            let options = ColoringOptions {
+                priority: options
+                    .priority
+                    .as_ref()
+                    .map(|priority| vertices.iter().map(|&v| priority[v]).collect()),
+                precolors: None,
+                allowed: None,
+                ..*options
+            };
+            options.color(&subgraph)
+        })
+        .collect();
+
+    let mut offsets = Vec::with_capacity(n_parts + 1);
+    offsets.push(0);
+    for (p, colors) in part_colors.iter().enumerate() {
+        let n_colors = colors.iter().max().map_or(0, |&c| c + 1);
+        let width = match budget {
+            Some(budget) if n_colors > budget => {
+                return Err(format!(
+                    "subdomain {p} needs {n_colors} colors, more than the budget of {budget}"
+                ));
+            }
+            Some(budget) => budget,
+            None => n_colors,
+        };
+        offsets.push(offsets[p] + width);
+    }
+    let colors = partition
+        .iter()
+        .zip(local)
+        .map(|(&p, &i)| offsets[p] + part_colors[p][i])
+        .collect();
+    Ok((colors, offsets))
+}
@@ -0,0 +1,87 @@
+//! Progress reports from long-running loops, which may also stop them.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// The observer asked the computation to stop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// Observer of the vertices processed in each phase of a computation.
+///
+/// Reports may come from any worker thread, so `done` is not necessarily
+/// increasing between consecutive calls of a parallel phase.
+pub trait Progress: Sync {
+    /// Processed vertices between two reports within a phase; at least 1.
+    fn interval(&self) -> usize;
+
+    /// `done` of `total` vertices of `phase` are processed; `Err` stops the computation.
+    fn report(&self, phase: &'static str, done: usize, total: usize) -> Result<(), Cancelled>;
+
+    /// Whether to stop at the next processed vertex without waiting for a report.
+    fn cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Progress that is never reported and never stops anything.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Silent;
+
+impl Progress for Silent {
+    fn interval(&self) -> usize {
+        usize::MAX
+    }
+
+    fn report(&self, _phase: &'static str, _done: usize, _total: usize) -> Result<(), Cancelled> {
+        Ok(())
+    }
+}
+
+/// Vertex counter of one phase, shared by every thread working on it.
+pub struct Phase<'a> {
+    progress: &'a dyn Progress,
+    name: &'static str,
+    total: usize,
+    done: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl<'a> Phase<'a> {
+    /// Start `name` over `total` vertices, reporting none done.
+    pub fn start(
+        progress: &'a dyn Progress,
+        name: &'static str,
+        total: usize,
+    ) -> Result<Self, Cancelled> {
+        progress.report(name, 0, total)?;
+        Ok(Self {
+            progress,
+            name,
+            total,
+            done: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+        })
+    }
+
+    /// Count one processed vertex, reporting after every `interval()` of them.
+    ///
+    /// Once a report was answered with `Err` or the observer is `cancelled()`,
+    /// every thread gets `Err` without reporting again.
+    pub fn step(&self) -> Result<(), Cancelled> {
+        if self.cancelled.load(Ordering::Relaxed) || self.progress.cancelled() {
+            return Err(Cancelled);
+        }
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if !done.is_multiple_of(self.progress.interval()) {
+            return Ok(());
+        }
+        self.progress
+            .report(self.name, done, self.total)
+            .inspect_err(|_| self.cancelled.store(true, Ordering::Relaxed))
+    }
+
+    /// Report the phase as complete.
+    pub fn finish(self) -> Result<(), Cancelled> {
+        self.progress.report(self.name, self.total, self.total)
+    }
+}
@@ -0,0 +1,262 @@
+//! Recovery of sparse derivative entries from compressed products.
+
+use ahash::AHashMap;
+
+use crate::adjacency::Adjacency;
+use crate::greedy;
+
+/// Flat position of every entry `(i, j)` in the row-major `(n_rows, n_colors)` product `J S`.
+///
+/// With a column coloring, entry `J[i, j]` is read directly from row `i` and
+/// column `colors[j]` of the compressed product.
+pub fn direct_positions(
+    entries: &[(usize, usize)],
+    colors: &[usize],
+) -> Result<Vec<usize>, String> {
+    let n_colors = greedy::color_count(colors);
+    entries
+        .iter()
+        .map(|&(i, j)| {
+            let &c = colors.get(j).ok_or_else(|| {
+                format!("column index {j} out of range for {} colors", colors.len())
+            })?;
+            Ok(i * n_colors + c)
+        })
+        .collect()
+}
+
+/// Gather `compressed[p]` for every flat position `p`.
+pub fn gather<T: Copy>(compressed: &[T], positions: &[usize]) -> Result<Vec<T>, String> {
+    positions
+        .iter()
+        .map(|&p| {
+            compressed
+                .get(p)
+                .copied()
+                .ok_or_else(|| "pattern has more rows than the compressed product".to_string())
+        })
+        .collect()
+}
+
+/// How symmetric Hessian entries are read from the compressed product `H S`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HessianRecovery {
+    /// Every entry read from one product entry; needs a star coloring.
+    Direct,
+    /// Entries of each two-colored tree solved leaf by leaf; needs an acyclic coloring.
+    Substitution,
+}
+
+impl HessianRecovery {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "direct" => Ok(Self::Direct),
+            "substitution" => Ok(Self::Substitution),
+            _ => Err(format!(
+                "unknown method '{name}', expected one of: direct, substitution"
+            )),
+        }
+    }
+}
+
+/// Recovered symmetric Hessian: the diagonal and off-diagonal values aligned with the adjacency.
+pub struct HessianValues<T> {
+    pub diagonal: Vec<T>,
+    /// `off_diagonal[i][k]` is `H[i, adjacency[i][k]]`.
+    pub off_diagonal: Vec<Vec<T>>,
+}
+
+impl<T: Copy> HessianValues<T> {
+    /// `H[i, j]`, or `None` if `(i, j)` is outside the sorted adjacency.
+    pub fn get(&self, adjacency: &Adjacency, i: usize, j: usize) -> Option<T> {
+        if i == j {
+            return self.diagonal.get(i).copied();
+        }
+        let k = adjacency.get(i)?.binary_search(&j).ok()?;
+        Some(self.off_diagonal[i][k])
+    }
+}
+
+/// Recover a symmetric Hessian from its row-major `(n, n_colors)` product `B = H S`.
+///
+/// `adjacency` is the sorted, symmetric pattern without self loops and
+/// `colors` a distance-1 coloring of it. Diagonal entries are `B[i, colors[i]]`.
+/// An off-diagonal entry `h_ij` is `B[i, colors[j]]` when `j` is the only
+/// neighbor of `i` in its color, which a star coloring guarantees for one
+/// end of every edge (direct recovery). Acyclic colorings instead peel the
+/// leaves of each two-colored tree, subtracting recovered entries from the
+/// remaining sums (substitution).
+pub fn recover_hessian<T>(
+    adjacency: &Adjacency,
+    colors: &[usize],
+    compressed: &[T],
+    method: HessianRecovery,
+) -> Result<HessianValues<T>, String>
+where
+    T: Copy + Default + std::ops::Sub<Output = T>,
+{
+    let n = adjacency.len();
+    let n_colors = greedy::color_count(colors);
+    if compressed.len() != n * n_colors {
+        return Err(format!(
+            "compressed product must have shape ({n}, {n_colors}), got {} entries",
+            compressed.len()
+        ));
+    }
+    let product = |i: usize, c: usize| compressed[i * n_colors + c];
+    for (i, neighs) in adjacency.iter().enumerate() {
+        if neighs.iter().any(|&j| colors[j] == colors[i]) {
+            return Err(format!(
+                "vertex {i} shares its color with a neighbor; not a distance-1 coloring"
+            ));
+        }
+    }
+
+    let diagonal = (0..n).map(|i| product(i, colors[i])).collect();
+    let off_diagonal = match method {
+        HessianRecovery::Direct => direct_off_diagonal(adjacency, colors, &product)?,
+        HessianRecovery::Substitution => substituted_off_diagonal(adjacency, colors, &product)?,
+    };
+    Ok(HessianValues {
+        diagonal,
+        off_diagonal,
+    })
+}
+
+fn direct_off_diagonal<T: Copy + Default>(
+    adjacency: &Adjacency,
+    colors: &[usize],
+    product: &impl Fn(usize, usize) -> T,
+) -> Result<Vec<Vec<T>>, String> {
+    // unique[i][k]: adjacency[i][k] is the only neighbor of i in its color.
+    let mut count = vec![0u32; greedy::color_count(colors)];
+    let unique: Vec<Vec<bool>> = adjacency
+        .iter()
+        .map(|neighs| {
+            for &j in neighs {
+                count[colors[j]] += 1;
+            }
+            let flags = neighs.iter().map(|&j| count[colors[j]] == 1).collect();
+            for &j in neighs {
+                count[colors[j]] = 0;
+            }
+            flags
+        })
+        .collect();
+
+    let mut values: Vec<Vec<T>> = adjacency
+        .iter()
+        .map(|neighs| vec![T::default(); neighs.len()])
+        .collect();
+    for (i, neighs) in adjacency.iter().enumerate() {
+        for (k, &j) in neighs.iter().enumerate() {
+            if j < i {
+                continue;
+            }
+            let back = adjacency[j]
+                .binary_search(&i)
+                .expect("adjacency is symmetric");
+            let value = if unique[i][k] {
+                product(i, colors[j])
+            } else if unique[j][back] {
+                product(j, colors[i])
+            } else {
+                return Err(format!(
+                    "entry ({i}, {j}) cannot be recovered directly; not a star coloring"
+                ));
+            };
+            values[i][k] = value;
+            values[j][back] = value;
+        }
+    }
+    Ok(values)
+}
+
+fn substituted_off_diagonal<T>(
+    adjacency: &Adjacency,
+    colors: &[usize],
+    product: &impl Fn(usize, usize) -> T,
+) -> Result<Vec<Vec<T>>, String>
+where
+    T: Copy + Default + std::ops::Sub<Output = T>,
+{
+    // Edges (i, k) with i < adjacency[i][k], grouped by their color pair.
+    let mut edges: Vec<(usize, usize, usize, usize)> = Vec::new();
+    for (i, neighs) in adjacency.iter().enumerate() {
+        for (k, &j) in neighs.iter().enumerate() {
+            if i < j {
+                let (a, b) = (colors[i], colors[j]);
+                edges.push((a.min(b), a.max(b), i, k));
+            }
+        }
+    }
+    edges.sort_unstable();
+
+    let mut values: Vec<Vec<T>> = adjacency
+        .iter()
+        .map(|neighs| vec![T::default(); neighs.len()])
+        .collect();
+    let mut local: AHashMap<usize, usize> = AHashMap::new();
+    for group in edges.chunk_by(|e, f| (e.0, e.1) == (f.0, f.1)) {
+        // Forest of this color pair: local vertices, residual sums and incident edges.
+        local.clear();
+        let mut vertices: Vec<usize> = Vec::new();
+        let mut incident: Vec<Vec<usize>> = Vec::new();
+        let mut local_id = |v: usize, vertices: &mut Vec<usize>, incident: &mut Vec<Vec<usize>>| {
+            *local.entry(v).or_insert_with(|| {
+                vertices.push(v);
+                incident.push(Vec::new());
+                vertices.len() - 1
+            })
+        };
+        for (e, &(_, _, i, k)) in group.iter().enumerate() {
+            let li = local_id(i, &mut vertices, &mut incident);
+            let lj = local_id(adjacency[i][k], &mut vertices, &mut incident);
+            incident[li].push(e);
+            incident[lj].push(e);
+        }
+        let (a, b) = (group[0].0, group[0].1);
+        let mut residual: Vec<T> = vertices
+            .iter()
+            .map(|&v| product(v, if colors[v] == a { b } else { a }))
+            .collect();
+        let mut degree: Vec<usize> = incident.iter().map(Vec::len).collect();
+        let mut solved = vec![false; group.len()];
+        let mut leaves: Vec<usize> = (0..vertices.len()).filter(|&l| degree[l] == 1).collect();
+
+        while let Some(l) = leaves.pop() {
+            if degree[l] != 1 {
+                continue;
+            }
+            let &e = incident[l]
+                .iter()
+                .find(|&&e| !solved[e])
+                .expect("a leaf has one unsolved edge");
+            let (_, _, i, k) = group[e];
+            let j = adjacency[i][k];
+            let other = if vertices[l] == i { j } else { i };
+            let lo = local[&other];
+            let value = residual[l];
+            solved[e] = true;
+            degree[l] = 0;
+            degree[lo] -= 1;
+            residual[lo] = residual[lo] - value;
+            if degree[lo] == 1 {
+                leaves.push(lo);
+            }
+            let back = adjacency[j]
+                .binary_search(&i)
+                .expect("adjacency is symmetric");
+            values[i][k] = value;
+            values[j][back] = value;
+        }
+        if let Some(e) = solved.iter().position(|&done| !done) {
+            let (_, _, i, k) = group[e];
+            return Err(format!(
+                "entry ({i}, {}) lies on a two-colored cycle; not an acyclic coloring",
+                adjacency[i][k]
+            ));
+        }
+    }
+    Ok(values)
+}
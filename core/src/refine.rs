@@ -0,0 +1,334 @@
+//! Post-passes that shrink the color count of a valid coloring.
+
+use std::cmp::Reverse;
+
+use crate::adjacency::Adjacency;
+use crate::greedy::{color_classes, color_count, greedy_color_in_order};
+use crate::rng::SplitMix64;
+
+/// Refinement applied after the initial greedy pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Refinement {
+    /// Recolor greedily class by class until the count stops dropping.
+    IteratedGreedy,
+    /// Empty the smallest classes through Kempe-chain interchanges.
+    Kempe,
+    /// Legalize colorings with one color less by tabu search (TabuCol).
+    Tabu { iterations: usize, seed: u64 },
+}
+
+impl Refinement {
+    /// Look up a refinement by name; `iterations` and `seed` configure tabu search.
+    pub fn parse(name: &str, iterations: usize, seed: u64) -> Result<Self, String> {
+        match name {
+            "iterated_greedy" => Ok(Self::IteratedGreedy),
+            "kempe" => Ok(Self::Kempe),
+            "tabu" => Ok(Self::Tabu { iterations, seed }),
+            _ => Err(format!(
+                "unknown refinement '{name}', expected one of: iterated_greedy, kempe, tabu"
+            )),
+        }
+    }
+}
+
+/// Apply `refinement` to a valid coloring of `adjacency`.
+pub fn refine(adjacency: &Adjacency, colors: Vec<usize>, refinement: Refinement) -> Vec<usize> {
+    match refinement {
+        Refinement::IteratedGreedy => iterated_greedy(adjacency, colors),
+        Refinement::Kempe => kempe_chains(adjacency, colors),
+        Refinement::Tabu { iterations, seed } => tabu(adjacency, colors, iterations, seed),
+    }
+}
+
+/// Iterated greedy (Culberson): recolor vertices grouped by color class.
+///
+/// Visiting whole classes one after another never needs more colors than
+/// before. Class orders alternate between reversed and largest-first, and the
+/// loop stops once both fail to remove a color.
+fn iterated_greedy(adjacency: &Adjacency, mut colors: Vec<usize>) -> Vec<usize> {
+    let mut n_colors = color_count(&colors);
+    let mut stale = 0;
+    let mut round = 0;
+
+    while stale < 2 {
+        let classes = color_classes(&colors);
+        let mut class_order: Vec<usize> = (0..n_colors).collect();
+        if round % 2 == 0 {
+            class_order.reverse();
+        } else {
+            class_order.sort_by_key(|&c| Reverse(classes[c].len()));
+        }
+        let order = class_order.iter().flat_map(|&c| classes[c].iter().copied());
+        colors = greedy_color_in_order(adjacency, order);
+
+        let count = color_count(&colors);
+        if count < n_colors {
+            n_colors = count;
+            stale = 0;
+        } else {
+            stale += 1;
+        }
+        round += 1;
+    }
+    colors
+}
+
+/// Kempe-chain elimination of sparsely populated color classes.
+///
+/// Classes are visited from least to most populated. Every vertex of a class
+/// moves to another live color, directly when no neighbor uses it or else by
+/// swapping an (a, b) Kempe chain that frees color a around the vertex. A class
+/// that cannot be emptied is restored, and colors are relabeled at the end.
+fn kempe_chains(adjacency: &Adjacency, mut colors: Vec<usize>) -> Vec<usize> {
+    let n_colors = color_count(&colors);
+    let mut class_order: Vec<usize> = (0..n_colors).collect();
+    let sizes: Vec<usize> = color_classes(&colors).iter().map(Vec::len).collect();
+    class_order.sort_by_key(|&c| sizes[c]);
+
+    let mut live = vec![true; n_colors];
+    let mut search = ChainSearch::new(adjacency.len());
+    for target in class_order {
+        let snapshot = colors.clone();
+        live[target] = false;
+        let members: Vec<usize> = (0..colors.len()).filter(|&v| colors[v] == target).collect();
+        let emptied = members
+            .into_iter()
+            .all(|v| search.move_vertex(adjacency, &mut colors, v, &live));
+        if !emptied {
+            colors = snapshot;
+            live[target] = true;
+        }
+    }
+
+    // Relabel surviving colors to 0..k, keeping their relative order.
+    let mut relabel = vec![usize::MAX; n_colors];
+    let mut next = 0;
+    for (c, &alive) in live.iter().enumerate() {
+        if alive {
+            relabel[c] = next;
+            next += 1;
+        }
+    }
+    colors.iter().map(|&c| relabel[c]).collect()
+}
+
+/// Scratch space for Kempe-chain traversals.
+struct ChainSearch {
+    // stamp[v] == epoch marks v as part of the current chain.
+    stamp: Vec<usize>,
+    epoch: usize,
+    chain: Vec<usize>,
+}
+
+impl ChainSearch {
+    fn new(n: usize) -> Self {
+        Self {
+            stamp: vec![0; n],
+            epoch: 0,
+            chain: Vec::new(),
+        }
+    }
+
+    /// Recolor `v` with any live color, swapping one Kempe chain if needed.
+    fn move_vertex(
+        &mut self,
+        adjacency: &Adjacency,
+        colors: &mut [usize],
+        v: usize,
+        live: &[bool],
+    ) -> bool {
+        let live_colors = || (0..live.len()).filter(|&c| live[c]);
+        if let Some(a) = live_colors().find(|&a| adjacency[v].iter().all(|&w| colors[w] != a)) {
+            colors[v] = a;
+            return true;
+        }
+        for a in live_colors() {
+            for b in live_colors().filter(|&b| b != a) {
+                self.collect_chain(adjacency, colors, v, a, b);
+                // Swapping frees `a` only if no b-colored neighbor turns into `a`.
+                let blocked = adjacency[v]
+                    .iter()
+                    .any(|&w| colors[w] == b && self.stamp[w] == self.epoch);
+                if blocked {
+                    continue;
+                }
+                for &u in &self.chain {
+                    colors[u] = if colors[u] == a { b } else { a };
+                }
+                colors[v] = a;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Gather the (a, b)-colored component around all a-colored neighbors of `v`.
+    fn collect_chain(
+        &mut self,
+        adjacency: &Adjacency,
+        colors: &[usize],
+        v: usize,
+        a: usize,
+        b: usize,
+    ) {
+        self.epoch += 1;
+        self.chain.clear();
+        for &w in &adjacency[v] {
+            if colors[w] == a && self.stamp[w] != self.epoch {
+                self.stamp[w] = self.epoch;
+                self.chain.push(w);
+            }
+        }
+        let mut head = 0;
+        while head < self.chain.len() {
+            let u = self.chain[head];
+            head += 1;
+            for &x in &adjacency[u] {
+                if (colors[x] == a || colors[x] == b) && self.stamp[x] != self.epoch {
+                    self.stamp[x] = self.epoch;
+                    self.chain.push(x);
+                }
+            }
+        }
+    }
+}
+
+/// TabuCol: repeatedly drop the last color and repair conflicts by tabu search.
+///
+/// `iterations` bounds the total number of tabu moves; the best legal coloring
+/// found is returned.
+fn tabu(adjacency: &Adjacency, colors: Vec<usize>, iterations: usize, seed: u64) -> Vec<usize> {
+    let mut best = colors;
+    let mut rng = SplitMix64::new(seed);
+    let mut budget = iterations;
+    while budget > 0 {
+        let n_colors = color_count(&best);
+        if n_colors <= 1 {
+            break;
+        }
+        match tabu_search(adjacency, &best, n_colors - 1, &mut budget, &mut rng) {
+            Some(colors) => best = colors,
+            None => break,
+        }
+    }
+    best
+}
+
+/// Tabu search for a conflict-free `k`-coloring starting from `start`.
+fn tabu_search(
+    adjacency: &Adjacency,
+    start: &[usize],
+    k: usize,
+    budget: &mut usize,
+    rng: &mut SplitMix64,
+) -> Option<Vec<usize>> {
+    let n = adjacency.len();
+    // Scatter the dropped class over the remaining colors.
+    let mut colors: Vec<usize> = start
+        .iter()
+        .map(|&c| if c >= k { rng.next_below(k) } else { c })
+        .collect();
+
+    // gamma[v * k + c]: number of neighbors of v colored c.
+    let mut gamma = vec![0u32; n * k];
+    for (v, neighs) in adjacency.iter().enumerate() {
+        for &w in neighs {
+            gamma[v * k + colors[w]] += 1;
+        }
+    }
+    let mut conflicting = ConflictSet::new(n);
+    let mut conflicts = 0;
+    for v in 0..n {
+        let own = gamma[v * k + colors[v]] as usize;
+        conflicts += own;
+        conflicting.update(v, own > 0);
+    }
+    conflicts /= 2;
+
+    let mut tabu_until = vec![0usize; n * k];
+    let mut best_conflicts = conflicts;
+    let mut iter = 0;
+    while conflicts > 0 {
+        if *budget == 0 {
+            return None;
+        }
+        *budget -= 1;
+        iter += 1;
+
+        // Best non-tabu move among conflicting vertices; tabu moves are allowed
+        // when they beat the best conflict count so far (aspiration).
+        let mut best_move = None;
+        let mut best_delta = i64::MAX;
+        let mut ties = 0;
+        for &v in &conflicting.items {
+            let own = i64::from(gamma[v * k + colors[v]]);
+            for c in (0..k).filter(|&c| c != colors[v]) {
+                let delta = i64::from(gamma[v * k + c]) - own;
+                let aspiration = (conflicts as i64 + delta) < best_conflicts as i64;
+                if tabu_until[v * k + c] > iter && !aspiration {
+                    continue;
+                }
+                if delta < best_delta {
+                    best_delta = delta;
+                    best_move = Some((v, c));
+                    ties = 1;
+                } else if delta == best_delta {
+                    ties += 1;
+                    if rng.next_below(ties) == 0 {
+                        best_move = Some((v, c));
+                    }
+                }
+            }
+        }
+        let Some((v, c)) = best_move else {
+            continue;
+        };
+
+        let old = colors[v];
+        colors[v] = c;
+        conflicts = (conflicts as i64 + best_delta) as usize;
+        for &w in &adjacency[v] {
+            gamma[w * k + old] -= 1;
+            gamma[w * k + c] += 1;
+            conflicting.update(w, gamma[w * k + colors[w]] > 0);
+        }
+        conflicting.update(v, gamma[v * k + c] > 0);
+        tabu_until[v * k + old] = iter + rng.next_below(10) + 6 * conflicting.items.len() / 10;
+        best_conflicts = best_conflicts.min(conflicts);
+    }
+    Some(colors)
+}
+
+/// Set of conflicting vertices with O(1) insertion and removal.
+struct ConflictSet {
+    items: Vec<usize>,
+    // pos[v]: index of v in `items`, or usize::MAX if absent.
+    pos: Vec<usize>,
+}
+
+impl ConflictSet {
+    fn new(n: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            pos: vec![usize::MAX; n],
+        }
+    }
+
+    fn update(&mut self, v: usize, present: bool) {
+        match (present, self.pos[v] != usize::MAX) {
+            (true, false) => {
+                self.pos[v] = self.items.len();
+                self.items.push(v);
+            }
+            (false, true) => {
+                let i = self.pos[v];
+                self.items.swap_remove(i);
+                if let Some(&moved) = self.items.get(i) {
+                    self.pos[moved] = i;
+                }
+                self.pos[v] = usize::MAX;
+            }
+            _ => {}
+        }
+    }
+}
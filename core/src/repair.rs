@@ -0,0 +1,68 @@
+//! Repairing a distance-2 coloring after small pattern edits.
+
+use crate::distance2::Distance2Graph;
+
+/// Repair `colors` for `graph`, the distance-2 graph of the edited pattern.
+///
+/// Every conflict created by an added nonzero involves one of its endpoints
+/// (`added`), so only endpoints that now clash with a neighbor are recolored.
+/// Endpoints of removed nonzeros (`removed`) keep a valid color but may now
+/// fit a smaller one, which they take. Empty color classes are dropped at the
+/// end, keeping the order of the remaining ones. All other vertices keep
+/// their colors.
+pub fn repair_colors(
+    graph: &Distance2Graph,
+    mut colors: Vec<usize>,
+    added: &[usize],
+    removed: &[usize],
+) -> Vec<usize> {
+    let mut seen = vec![usize::MAX; graph.n_vertices()];
+    let conflicting: Vec<usize> = distinct(added)
+        .into_iter()
+        .filter(|&v| {
+            let mut clash = false;
+            graph.for_each_neighbor(v, &mut seen, |k| clash |= colors[k] == colors[v]);
+            clash
+        })
+        .collect();
+
+    for &v in &conflicting {
+        colors[v] = usize::MAX;
+    }
+    graph.color_in_order(&mut colors, conflicting);
+    graph.color_in_order(&mut colors, distinct(removed));
+    compact(colors)
+}
+
+/// Sorted vertices of `vertices` without duplicates.
+fn distinct(vertices: &[usize]) -> Vec<usize> {
+    let mut vertices = vertices.to_vec();
+    vertices.sort_unstable();
+    vertices.dedup();
+    vertices
+}
+
+/// Renumber colors to `0..k`, keeping the order of the colors in use.
+fn compact(mut colors: Vec<usize>) -> Vec<usize> {
+    let n_colors = colors.iter().max().map_or(0, |&c| c + 1);
+    let mut used = vec![false; n_colors];
+    for &c in &colors {
+        used[c] = true;
+    }
+    if used.iter().all(|&u| u) {
+        return colors;
+    }
+    // rank[c]: number of used colors below c.
+    let rank: Vec<usize> = used
+        .iter()
+        .scan(0, |below, &u| {
+            let r = *below;
+            *below += usize::from(u);
+            Some(r)
+        })
+        .collect();
+    for c in &mut colors {
+        *c = rank[*c];
+    }
+    colors
+}
@@ -0,0 +1,61 @@
+//! Recursive Largest First coloring.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::adjacency::Adjacency;
+
+/// Recursive Largest First (Leighton): build one maximal independent set per color.
+///
+/// Each class starts from the uncolored vertex with the most uncolored
+/// neighbors, then repeatedly adds the candidate adjacent to the most vertices
+/// already excluded from the class (ties by index). Expects symmetric adjacency.
+pub fn rlf_color(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut colors = vec![usize::MAX; n];
+    let mut uncolored_degree: Vec<usize> = adjacency.iter().map(<[usize]>::len).collect();
+    // Per class: excluded vertices neighbor the class, in_excluded counts such neighbors.
+    let mut excluded = vec![false; n];
+    let mut in_excluded = vec![0usize; n];
+    let mut remaining = n;
+    let mut color = 0;
+
+    while remaining > 0 {
+        let uncolored = || (0..n).filter(|&v| colors[v] == usize::MAX);
+        for v in uncolored() {
+            excluded[v] = false;
+            in_excluded[v] = 0;
+        }
+        let mut heap: BinaryHeap<(usize, Reverse<usize>)> =
+            uncolored().map(|v| (0, Reverse(v))).collect();
+        let mut next = uncolored().max_by_key(|&v| (uncolored_degree[v], Reverse(v)));
+
+        while let Some(v) = next {
+            colors[v] = color;
+            remaining -= 1;
+            for &w in &adjacency[v] {
+                uncolored_degree[w] -= 1;
+                if colors[w] != usize::MAX || excluded[w] {
+                    continue;
+                }
+                excluded[w] = true;
+                for &x in &adjacency[w] {
+                    if colors[x] == usize::MAX && !excluded[x] {
+                        in_excluded[x] += 1;
+                        heap.push((in_excluded[x], Reverse(x)));
+                    }
+                }
+            }
+            // Pop the best remaining candidate, skipping outdated entries.
+            next = None;
+            while let Some((count, Reverse(x))) = heap.pop() {
+                if colors[x] == usize::MAX && !excluded[x] && count == in_excluded[x] {
+                    next = Some(x);
+                    break;
+                }
+            }
+        }
+        color += 1;
+    }
+    colors
+}
@@ -0,0 +1,41 @@
+//! Small deterministic random number generator.
+//!
+//! SplitMix64 keeps randomized algorithms bit-reproducible across runs,
+//! platforms, and dependency versions.
+
+/// Stateless 64-bit hash of `(seed, key)`, used for per-vertex random weights.
+pub fn hash64(seed: u64, key: u64) -> u64 {
+    SplitMix64::new(seed ^ key.wrapping_mul(0xD6E8_FEB8_6659_FD93)).next_u64()
+}
+
+/// SplitMix64 pseudo-random generator.
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..bound` (multiply-shift reduction).
+    pub fn next_below(&mut self, bound: usize) -> usize {
+        ((u128::from(self.next_u64()) * bound as u128) >> 64) as usize
+    }
+
+    /// Fisher–Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.next_below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
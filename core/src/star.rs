@@ -0,0 +1,111 @@
+//! Star coloring for symmetric sparsity patterns.
+
+use ahash::AHashMap;
+
+use crate::adjacency::Adjacency;
+
+/// Greedy star coloring: a distance-1 coloring without bicolored paths on four vertices.
+///
+/// Expects symmetric adjacency without self loops (see `symmetric_adjacency`).
+pub fn star_color(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut colors = vec![usize::MAX; n];
+    // Per vertex: how many colored neighbors carry each color.
+    let mut neighbor_colors: Vec<AHashMap<usize, u32>> = vec![AHashMap::new(); n];
+    // forbidden[c] == v marks color c as unavailable for vertex v.
+    let mut forbidden: Vec<usize> = Vec::new();
+
+    for v in 0..n {
+        let mut forbid = |c: usize| {
+            if c >= forbidden.len() {
+                forbidden.resize(c + 1, usize::MAX);
+            }
+            forbidden[c] = v;
+        };
+
+        for &w in &adjacency[v] {
+            let cw = colors[w];
+            if cw == usize::MAX {
+                continue;
+            }
+            // Distance-1 conflict.
+            forbid(cw);
+            // v as the end of a path v-w-x-y colored (c, cw, c, cw).
+            for &x in &adjacency[w] {
+                let cx = colors[x];
+                if x == v || cx == usize::MAX {
+                    continue;
+                }
+                if neighbor_colors[x].get(&cw).is_some_and(|&count| count >= 2) {
+                    forbid(cx);
+                }
+            }
+            // v as an inner vertex of a path x-v-w-y colored (cw, c, cw, c).
+            if neighbor_colors[v].get(&cw).is_some_and(|&count| count >= 2) {
+                for &y in &adjacency[w] {
+                    let cy = colors[y];
+                    if y != v && cy != usize::MAX {
+                        forbid(cy);
+                    }
+                }
+            }
+        }
+
+        let mut c = 0;
+        while forbidden.get(c) == Some(&v) {
+            c += 1;
+        }
+        colors[v] = c;
+        for &w in &adjacency[v] {
+            *neighbor_colors[w].entry(c).or_insert(0) += 1;
+        }
+    }
+    colors
+}
+
+/// Greedy restricted star coloring.
+///
+/// A distance-1 coloring in which every path u-v-w with equal end colors has
+/// a smaller center color. Each entry h_ij with color(i) > color(j) is then
+/// read directly from row i of the product with seed color(j). Expects
+/// symmetric adjacency without self loops.
+pub fn restricted_star_color(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut colors = vec![usize::MAX; n];
+    // forbidden[c] == v marks color c as unavailable for vertex v.
+    let mut forbidden: Vec<usize> = Vec::new();
+
+    for v in 0..n {
+        let mut forbid = |c: usize| {
+            if c >= forbidden.len() {
+                forbidden.resize(c + 1, usize::MAX);
+            }
+            forbidden[c] = v;
+        };
+
+        for &w in &adjacency[v] {
+            let cw = colors[w];
+            if cw != usize::MAX {
+                forbid(cw);
+            }
+            for &x in &adjacency[w] {
+                let cx = colors[x];
+                if x == v || cx == usize::MAX {
+                    continue;
+                }
+                // Ends of a path must differ through an uncolored center, and
+                // may only match around a center with a smaller color.
+                if cw == usize::MAX || cw > cx {
+                    forbid(cx);
+                }
+            }
+        }
+
+        let mut c = 0;
+        while forbidden.get(c) == Some(&v) {
+            c += 1;
+        }
+        colors[v] = c;
+    }
+    colors
+}
@@ -0,0 +1,86 @@
+//! Supervariables: vertices with identical pattern structure.
+//!
+//! Structurally identical columns, common in models built from repeated
+//! substructures, have the same distance-2 neighbors and usually conflict
+//! with each other. Each group of them is merged into one vertex of a
+//! condensed graph, which can be far smaller, and a coloring of that graph
+//! is expanded with distinct colors for the members of every group.
+//! Identical rows, as from replicated boundary conditions, only repeat
+//! constraints and are dropped from a column graph outright.
+
+use ahash::{AHashMap, AHashSet};
+
+use crate::adjacency::Adjacency;
+
+/// Group of each of `n` vertices, those with equal nonempty `key` lists sharing one.
+///
+/// Groups are numbered by their smallest vertex, so the natural order of
+/// the groups follows the vertices. Vertices with empty keys conflict with
+/// nothing and stay alone.
+pub fn identical_groups<'a>(
+    n: usize,
+    key: impl Fn(usize) -> (&'a [usize], &'a [usize]),
+) -> Vec<usize> {
+    let mut first: AHashMap<(&[usize], &[usize]), usize> = AHashMap::new();
+    let mut n_groups = 0;
+    (0..n)
+        .map(|v| {
+            let key = key(v);
+            if let Some(&g) = first.get(&key) {
+                return g;
+            }
+            if !key.0.is_empty() || !key.1.is_empty() {
+                first.insert(key, n_groups);
+            }
+            n_groups += 1;
+            n_groups - 1
+        })
+        .collect()
+}
+
+/// Vertex colors from `colors` of the groups of a condensed graph.
+///
+/// Member `r` (by index) of a group colored `c` gets color `offset[c] + r`,
+/// where every color class takes as many colors as its largest group.
+/// Members of one group thus differ, two vertices share a color only if
+/// their groups do, and groups of equal size `m` give `m` times the colors.
+pub fn expand(colors: &[usize], groups: &[usize]) -> Vec<usize> {
+    let mut sizes = vec![0; colors.len()];
+    let rank: Vec<usize> = groups
+        .iter()
+        .map(|&g| {
+            sizes[g] += 1;
+            sizes[g] - 1
+        })
+        .collect();
+    let n_colors = colors.iter().max().map_or(0, |&c| c + 1);
+    let mut widths = vec![0; n_colors];
+    for (&c, &size) in colors.iter().zip(&sizes) {
+        widths[c] = widths[c].max(size);
+    }
+    let mut offsets = Vec::with_capacity(n_colors);
+    let mut next = 0;
+    for width in widths {
+        offsets.push(next);
+        next += width;
+    }
+    groups
+        .iter()
+        .zip(rank)
+        .map(|(&g, r)| offsets[colors[g]] + r)
+        .collect()
+}
+
+/// `rows` keeping only the first of every set of identical rows, and no empty rows.
+///
+/// Columns conflict when they share a row, so a repeated row adds no
+/// conflict and an empty one none at all. `None` if `rows` has neither;
+/// rows match only with their entries in the same order.
+pub fn distinct_rows(rows: &Adjacency) -> Option<Adjacency> {
+    let mut seen = AHashSet::new();
+    let kept: Vec<&[usize]> = rows
+        .iter()
+        .filter(|row| !row.is_empty() && seen.insert(*row))
+        .collect();
+    (kept.len() < rows.len()).then(|| kept.into_iter().map(|row| row.iter().copied()).collect())
+}
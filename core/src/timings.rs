@@ -0,0 +1,28 @@
+//! Wall-clock time spent in each stage of a coloring call.
+
+use std::time::{Duration, Instant};
+
+/// Stages in the order they first ran, each with its accumulated duration.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    /// Run `f` and add its wall-clock time to `stage`.
+    pub fn time<T>(&mut self, stage: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+        match self.stages.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += elapsed,
+            None => self.stages.push((stage, elapsed)),
+        }
+        result
+    }
+
+    /// Stages with their durations, in the order they first ran.
+    pub fn stages(&self) -> &[(&'static str, Duration)] {
+        &self.stages
+    }
+}
@@ -0,0 +1,76 @@
+//! Validity checks of existing colorings.
+
+use rayon::prelude::*;
+
+use crate::adjacency::Adjacency;
+
+/// Vertex pairs `(u, v)`, `u < v`, joined by an edge of `adjacency` that share a color.
+///
+/// Self loops are ignored and each pair is listed once, in sorted order, even
+/// if the adjacency stores the edge in both directions. An empty result means
+/// `colors` is a valid coloring.
+pub fn conflicts(adjacency: &Adjacency, colors: &[usize]) -> Vec<(usize, usize)> {
+    let mut pairs: Vec<(usize, usize)> = (0..adjacency.len())
+        .into_par_iter()
+        .flat_map_iter(|u| {
+            adjacency[u]
+                .iter()
+                .filter(move |&&v| v != u && colors[u] == colors[v])
+                .map(move |&v| (u.min(v), u.max(v)))
+        })
+        .collect();
+    pairs.par_sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+/// Rows shared by two columns of one group, as `(row, first, other)` triples.
+///
+/// `cols` lists the rows of every column (the transpose of the pattern) and
+/// each row of `groups` the columns of one group, from any source; groups may
+/// overlap. Within a group, `other` hits a row first reached by column
+/// `first` of the same group. Triples are sorted and unique; an empty result
+/// means every group is structurally orthogonal.
+pub fn orthogonality_violations(
+    cols: &Adjacency,
+    groups: &Adjacency,
+    n_rows: usize,
+) -> Vec<(usize, usize, usize)> {
+    let mut violations: Vec<(usize, usize, usize)> = (0..groups.len())
+        .into_par_iter()
+        .map_init(
+            // owner[i] == (g, c): row i was first reached by column c of group g.
+            || vec![(usize::MAX, 0); n_rows],
+            |owner, g| {
+                let mut found = Vec::new();
+                for &c in &groups[g] {
+                    for &i in &cols[c] {
+                        match owner[i] {
+                            (h, first) if h == g && first != c => found.push((i, first, c)),
+                            (h, _) if h == g => {}
+                            _ => owner[i] = (g, c),
+                        }
+                    }
+                }
+                found
+            },
+        )
+        .flatten()
+        .collect();
+    violations.par_sort_unstable();
+    violations.dedup();
+    violations
+}
+
+/// First entry `(i, j)` of a square pattern, in row order, whose mirror `(j, i)` is missing.
+///
+/// Rows must be sorted. `None` means the pattern is structurally symmetric,
+/// as an assembled symmetric matrix is.
+pub fn asymmetric_entry(rows: &Adjacency) -> Option<(usize, usize)> {
+    (0..rows.len()).into_par_iter().find_map_first(|i| {
+        rows[i]
+            .iter()
+            .find(|&&j| j != i && rows[j].binary_search(&i).is_err())
+            .map(|&j| (i, j))
+    })
+}
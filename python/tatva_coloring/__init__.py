@@ -4,5 +4,46 @@ Provides lightweight typing hints and user-facing docs when importing
 `tatva_coloring` from Python. The compiled extension lives in `_tatva_coloring`.
 """
 
+from ._base import ColoringGraph as ColoringGraph
+from ._base import ColoringStats as ColoringStats
+from ._base import Distance2Coloring as Distance2Coloring
+from ._base import acyclic_color_and_seeds as acyclic_color_and_seeds
+from ._base import bicolor as bicolor
+from ._base import cell_colors as cell_colors
+from ._base import color_edge_list as color_edge_list
+from ._base import color_matrix_market as color_matrix_market
+from ._base import color_permutation as color_permutation
+from ._base import cpr_column_groups as cpr_column_groups
+from ._base import decompress_jacobian as decompress_jacobian
+from ._base import distance1_color as distance1_color
+from ._base import distance2_adjacency as distance2_adjacency
 from ._base import distance2_color_and_seeds as distance2_color_and_seeds
 from ._base import distance2_colors as distance2_colors
+from ._base import distance2_lower_bound as distance2_lower_bound
+from ._base import distance2_memory_estimate as distance2_memory_estimate
+from ._base import distance_k_color as distance_k_color
+from ._base import distributed_distance2_colors as distributed_distance2_colors
+from ._base import element_distance2_colors as element_distance2_colors
+from ._base import hessian_coloring as hessian_coloring
+from ._base import load_coloring as load_coloring
+from ._base import networkx_coloring as networkx_coloring
+from ._base import normal_pattern as normal_pattern
+from ._base import (
+    partial_distance2_color_and_seeds as partial_distance2_color_and_seeds,
+)
+from ._base import partitioned_distance2_colors as partitioned_distance2_colors
+from ._base import pattern_from_elements as pattern_from_elements
+from ._base import pattern_hash as pattern_hash
+from ._base import petsc_coloring as petsc_coloring
+from ._base import rcm_permutation as rcm_permutation
+from ._base import recover_hessian as recover_hessian
+from ._base import recovery_map as recovery_map
+from ._base import repair_distance2_colors as repair_distance2_colors
+from ._base import (
+    restricted_star_color_and_seeds as restricted_star_color_and_seeds,
+)
+from ._base import save_coloring as save_coloring
+from ._base import save_metis_graph as save_metis_graph
+from ._base import star_color_and_seeds as star_color_and_seeds
+from ._base import verify_coloring as verify_coloring
+from ._base import verify_column_groups as verify_column_groups
@@ -7,7 +7,10 @@ Provides lightweight typing hints and user-facing docs when importing
 
 from __future__ import annotations
 
-from typing import List, Tuple
+import hashlib
+import json
+import os
+from typing import Any, Callable, Dict, Hashable, List, Optional, Tuple, Union
 
 import numpy as np
 from numpy.typing import NDArray
@@ -19,54 +22,3514 @@ try:
     from ._tatva_coloring import (  # ty:ignore[unresolved-import]
         distance2_colors as _distance2_colors_ext,
     )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        distance2_memory_estimate as _distance2_memory_estimate_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        distance2_lower_bound as _distance2_lower_bound_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        distance2_adjacency as _distance2_adjacency_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        save_metis_graph as _save_metis_graph_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        rcm_permutation as _rcm_permutation_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        partitioned_distance2_colors as _partitioned_distance2_colors_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        pattern_from_elements as _pattern_from_elements_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        normal_pattern as _normal_pattern_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        repair_distance2_colors as _repair_distance2_colors_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        coloring_conflicts as _coloring_conflicts_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        orthogonality_violations as _orthogonality_violations_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        distance1_color as _distance1_color_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        distance_k_color as _distance_k_color_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        star_color_and_seeds as _star_color_and_seeds_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        restricted_star_color_and_seeds as _restricted_star_color_and_seeds_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        acyclic_color_and_seeds as _acyclic_color_and_seeds_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        partial_distance2_color_and_seeds as _partial_distance2_color_and_seeds_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        cpr_column_groups as _cpr_column_groups_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        bicolor as _bicolor_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        color_matrix_market as _color_matrix_market_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        element_distance2_colors as _element_distance2_colors_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        cell_colors as _cell_colors_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        color_edge_list as _color_edge_list_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        color_permutation as _color_permutation_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        petsc_coloring as _petsc_coloring_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        recovery_map as _recovery_map_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        decompress_jacobian as _decompress_jacobian_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        recover_hessian as _recover_hessian_ext,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        ColoringGraph as _ColoringGraphExt,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        ColoringStats as ColoringStats,
+    )
+    from ._tatva_coloring import (  # ty:ignore[unresolved-import]
+        HaloGraph as _HaloGraphExt,
+    )
 except ImportError:
     _distance2_color_and_seeds_ext = None
     _distance2_colors_ext = None
+    _distance2_memory_estimate_ext = None
+    _distance2_lower_bound_ext = None
+    _distance2_adjacency_ext = None
+    _save_metis_graph_ext = None
+    _rcm_permutation_ext = None
+    _partitioned_distance2_colors_ext = None
+    _pattern_from_elements_ext = None
+    _normal_pattern_ext = None
+    _repair_distance2_colors_ext = None
+    _coloring_conflicts_ext = None
+    _orthogonality_violations_ext = None
+    _distance1_color_ext = None
+    _distance_k_color_ext = None
+    _star_color_and_seeds_ext = None
+    _restricted_star_color_and_seeds_ext = None
+    _acyclic_color_and_seeds_ext = None
+    _partial_distance2_color_and_seeds_ext = None
+    _cpr_column_groups_ext = None
+    _bicolor_ext = None
+    _color_matrix_market_ext = None
+    _element_distance2_colors_ext = None
+    _cell_colors_ext = None
+    _color_edge_list_ext = None
+    _color_permutation_ext = None
+    _petsc_coloring_ext = None
+    _recovery_map_ext = None
+    _decompress_jacobian_ext = None
+    _recover_hessian_ext = None
+    _ColoringGraphExt = None
+    ColoringStats = None
+    _HaloGraphExt = None
+
+
+__all__ = [
+    "ColoringGraph",
+    "ColoringStats",
+    "Distance2Coloring",
+    "acyclic_color_and_seeds",
+    "bicolor",
+    "cell_colors",
+    "color_edge_list",
+    "color_matrix_market",
+    "color_permutation",
+    "cpr_column_groups",
+    "decompress_jacobian",
+    "distance1_color",
+    "distance2_adjacency",
+    "distance2_color_and_seeds",
+    "distance2_colors",
+    "distance2_lower_bound",
+    "distance2_memory_estimate",
+    "distance_k_color",
+    "distributed_distance2_colors",
+    "networkx_coloring",
+    "normal_pattern",
+    "element_distance2_colors",
+    "hessian_coloring",
+    "load_coloring",
+    "partial_distance2_color_and_seeds",
+    "partitioned_distance2_colors",
+    "pattern_from_elements",
+    "pattern_hash",
+    "rcm_permutation",
+    "petsc_coloring",
+    "recover_hessian",
+    "recovery_map",
+    "repair_distance2_colors",
+    "restricted_star_color_and_seeds",
+    "save_coloring",
+    "save_metis_graph",
+    "star_color_and_seeds",
+    "verify_coloring",
+    "verify_column_groups",
+]
 
 
-__all__ = ["distance2_color_and_seeds", "distance2_colors"]
+# Seed matrix in any of the ``seed_format`` layouts.
+_Seeds = Union[List[NDArray], NDArray, Tuple[NDArray, NDArray, NDArray]]
 
 
-def distance2_colors(row_ptr: NDArray, col_idx: NDArray, n_dofs: int) -> NDArray:
+def _as_index_array(values) -> NDArray:
+    """Pass (u)int32/(u)int64 index arrays through without a copy, convert others."""
+    arr = np.ascontiguousarray(values)
+    if arr.dtype not in (np.int32, np.int64, np.uint32, np.uint64):
+        arr = arr.astype(np.int64)
+    return arr
+
+
+def _check_seed_output(
+    seed_format: str, from_dlpack: Optional[Callable], backend: str, pin_memory: bool
+) -> None:
+    """Reject unusable ``from_dlpack``, ``backend`` and ``pin_memory`` early."""
+    if backend not in ("numpy", "torch"):
+        raise ValueError(f"backend must be 'numpy' or 'torch', got {backend!r}")
+    if backend == "torch":
+        if from_dlpack is not None:
+            raise ValueError("from_dlpack cannot be combined with backend='torch'")
+        if seed_format != "dense":
+            raise ValueError(
+                f"backend='torch' needs seed_format 'dense', got {seed_format!r}"
+            )
+    elif pin_memory:
+        raise ValueError("pin_memory needs backend='torch'")
+    if from_dlpack is not None and seed_format not in ("dense", "csr"):
+        raise ValueError(
+            "from_dlpack needs seed_format 'dense' or 'csr', "
+            f"got {seed_format!r}"
+        )
+
+
+def _output_seeds(
+    seeds: Any,
+    seed_format: str,
+    from_dlpack: Optional[Callable],
+    backend: str,
+    pin_memory: bool,
+) -> Any:
+    """``seeds`` as the arrays of ``from_dlpack`` or ``backend``, checked beforehand."""
+    if backend == "torch":
+        import torch
+
+        # Shares the NumPy buffer; pinning is the only copy.
+        tensor = torch.from_numpy(seeds)
+        return tensor.pin_memory() if pin_memory else tensor
+    if from_dlpack is None:
+        return seeds
+    if seed_format == "dense":
+        return from_dlpack(seeds)
+    return tuple(from_dlpack(a) for a in seeds)
+
+
+def _compressed_array(compressed: Any) -> NDArray:
+    """C-contiguous 2D float32/float64 compressed product, converting others."""
+    arr = np.ascontiguousarray(compressed)
+    if arr.dtype not in (np.float32, np.float64):
+        arr = arr.astype(np.float64)
+    if arr.ndim != 2:
+        raise ValueError(f"compressed must have shape (n, n_colors), got {arr.shape}")
+    return arr
+
+def _sparse_pattern(matrix: Any) -> Tuple[NDArray, NDArray, Tuple[int, int], str]:
+    """Index arrays, shape and format of a ``scipy.sparse`` matrix or dense mask.
+
+    Matrices without canonical format (unsorted indices or duplicate entries)
+    are passed on as they are; the extension sorts and merges rows itself.
+    """
+    if isinstance(matrix, np.ndarray) and matrix.ndim == 2:
+        # Dense mask such as ``jacobian != 0``: its nonzeros as COO triplets.
+        rows, cols = np.nonzero(matrix)
+        return rows, cols, matrix.shape, "coo"
+    if not hasattr(matrix, "tocsr"):
+        raise TypeError(
+            "col_idx may only be omitted when passing a scipy.sparse matrix/array "
+            "or a dense 2D mask"
+        )
+    if matrix.format in ("csr", "csc"):
+        return matrix.indptr, matrix.indices, matrix.shape, matrix.format
+    if matrix.format == "coo":
+        return matrix.row, matrix.col, matrix.shape, "coo"
+    csr = matrix.tocsr()
+    return csr.indptr, csr.indices, csr.shape, "csr"
+
+
+def _pattern_arrays(
+    row_ptr: Any,
+    col_idx: Optional[NDArray],
+    n_rows: Optional[int],
+    n_cols: Optional[int],
+    format: str,
+) -> Tuple[NDArray, NDArray, int, int, str]:
+    """Index arrays, shape and format from raw arrays, a sparse matrix or a mask.
+
+    The shape of a sparse matrix must agree with ``n_rows``/``n_cols`` when
+    those are given, catching patterns paired with the wrong size.
+    """
+    if col_idx is None:
+        row_ptr, col_idx, (rows, cols), format = _sparse_pattern(row_ptr)
+        if (n_rows is not None and n_rows != rows) or (
+            n_cols is not None and n_cols != cols
+        ):
+            raise ValueError(
+                f"matrix shape {(rows, cols)} does not match ({n_rows}, {n_cols})"
+            )
+        n_rows, n_cols = rows, cols
+    if n_rows is None or n_cols is None:
+        raise TypeError("the pattern size is required when passing index arrays")
+    return _as_index_array(row_ptr), _as_index_array(col_idx), n_rows, n_cols, format
+
+
+def _square_pattern_arrays(
+    row_ptr: Any, col_idx: Optional[NDArray], n_dofs: Optional[int], format: str
+) -> Tuple[NDArray, NDArray, int, str]:
+    """`_pattern_arrays` for square patterns sized by ``n_dofs``."""
+    row_ptr_arr, col_idx_arr, n_rows, n_cols, format = _pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_dofs, format
+    )
+    if n_rows != n_cols:
+        raise ValueError(f"expected a square pattern, got shape {(n_rows, n_cols)}")
+    return row_ptr_arr, col_idx_arr, n_rows, format
+
+
+def _distance2_pattern_arrays(
+    row_ptr: Any,
+    col_idx: Optional[NDArray],
+    n_dofs: Optional[int],
+    n_cols: Optional[int],
+    format: str,
+) -> Tuple[NDArray, NDArray, int, Optional[int], str]:
+    """`_pattern_arrays` where ``n_cols`` stays ``None`` for square patterns."""
+    square_cols = n_dofs if n_cols is None and col_idx is not None else n_cols
+    row_ptr_arr, col_idx_arr, n_rows, found_cols, format = _pattern_arrays(
+        row_ptr, col_idx, n_dofs, square_cols, format
+    )
+    if n_cols is None and found_cols != n_rows:
+        n_cols = found_cols
+    return row_ptr_arr, col_idx_arr, n_rows, n_cols, format
+
+
+def _element_arrays(
+    elements: Any, elem_ptr: Optional[NDArray]
+) -> Tuple[NDArray, NDArray]:
+    """Ragged CSR connectivity (pointer, flat nodes) from any element input."""
+    if elem_ptr is not None:
+        return _as_index_array(elem_ptr), _as_index_array(elements)
+    if isinstance(elements, np.ndarray) and elements.ndim == 2:
+        n_elements, nodes_per_element = elements.shape
+        ptr = np.arange(n_elements + 1, dtype=np.int64) * nodes_per_element
+        return ptr, _as_index_array(elements.reshape(-1))
+    lengths = [len(element) for element in elements]
+    ptr = np.concatenate(([0], np.cumsum(lengths, dtype=np.int64)))
+    nodes = np.fromiter(
+        (node for element in elements for node in element),
+        dtype=np.int64,
+        count=int(ptr[-1]),
+    )
+    return ptr, nodes
+
+
+def _allowed_masks(allowed_colors: Any) -> NDArray:
+    """uint64 bitmasks of admissible colors from masks or a boolean matrix."""
+    arr = np.asarray(allowed_colors)
+    if arr.ndim != 2:
+        return np.ascontiguousarray(arr, dtype=np.uint64)
+    if arr.shape[1] > 64:
+        raise ValueError(f"allowed_colors holds at most 64 colors, got {arr.shape[1]}")
+    bits = np.arange(arr.shape[1], dtype=np.uint64)
+    return (arr.astype(bool).astype(np.uint64) << bits).sum(axis=1, dtype=np.uint64)
+
+
+def distance2_colors(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    restarts: int = 1,
+    max_colors: Optional[int] = None,
+    require_max_colors: bool = True,
+    priority: Optional[NDArray] = None,
+    precolors: Optional[NDArray] = None,
+    allowed_colors: Optional[Any] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    dofs_per_node: int = 1,
+    ties: Optional[Any] = None,
+    supervariables: bool = False,
+    n_cols: Optional[int] = None,
+    low_memory: bool = False,
+    return_stats: bool = False,
+    return_timings: bool = False,
+    colors_dtype: Any = "int32",
+    progress: Optional[Callable[[int, int, str], Any]] = None,
+    progress_interval: int = 100_000,
+    verbose: bool = False,
+    validate: str = "strict",
+) -> Union[NDArray, Tuple[Any, ...]]:
     """Color the distance-2 graph induced by a CSR sparse matrix.
 
+    Greedy coloring in ``"natural"``, ``"random"``, ``"largest_first"`` or
+    ``"rcm"`` ordering, or by ``priority``, without ``refine`` walks the 2-hop
+    neighborhoods straight from the pattern, so the distance-2 graph is never
+    stored; ``algorithm="banded"`` only reads the bandwidth off the pattern.
+    Other strategies build it explicitly; see
+    ``distance2_memory_estimate`` for its size.
+
+    Ctrl-C stops the degree count, the distance-2 graph build and greedy
+    coloring at the next vertex with ``KeyboardInterrupt``; strategies that
+    color the explicit graph are only interrupted once they finish.
+
     Args:
-        row_ptr: CSR row pointer of length ``n_dofs + 1``.
-        col_idx: CSR column indices.
-        n_dofs: Number of rows/cols (degrees of freedom).
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        ordering: Vertex ordering of the greedy colorer: ``"natural"`` (index
+            order), ``"dsatur"`` (highest saturation degree first),
+            ``"dsatur_degree"`` (DSATUR with saturation ties going to the
+            highest degree, i.e. distance-2 degree for distance-2 colorings),
+            ``"smallest_last"`` (reverse minimum-degree elimination order),
+            ``"largest_first"`` (decreasing degree), ``"incidence_degree"``
+            (most already-colored neighbors first), ``"random"`` (random
+            permutation drawn from ``seed``) or ``"rcm"`` (reverse
+            Cuthill-McKee order of the pattern, which sweeps the DOFs in a
+            narrow band).
+        seed: Seed for randomized orderings and algorithms; equal seeds give
+            bit-identical colorings on every machine.
+        refine: Optional post-pass that tries to remove colors:
+            ``"iterated_greedy"`` recolors class by class until no color is
+            saved, ``"kempe"`` empties the least-populated classes through
+            Kempe-chain interchanges and ``"tabu"`` repeatedly drops a color
+            and repairs the conflicts by tabu search (TabuCol).
+        algorithm: ``"greedy"`` (sequential greedy in ``ordering``),
+            ``"exact"`` (DSATUR branch and bound for graphs with at most
+            10,000 vertices, larger ones get the DSATUR coloring;
+            ``ordering`` is ignored),
+            ``"balanced"`` (every class capped at ``ceil(n / k)`` of the
+            ``n`` vertices, ``k`` being the color count of a greedy pass;
+            the cap may open colors beyond ``k``), ``"rlf"`` (Recursive
+            Largest First, one maximal independent set per color;
+            ``ordering`` is ignored),
+            ``"jones_plassmann"`` (multi-threaded rounds with random vertex
+            weights drawn from ``seed``; ``ordering`` is ignored),
+            ``"speculative"`` (multi-threaded speculative coloring with
+            conflict repair; fastest, but unless ``deterministic`` the result
+            may vary between runs) or ``"banded"`` (color ``v mod (w + 1)``,
+            ``w`` bounding the index distance of any two DOFs within distance
+            2 as read off the pattern, so no distance-2 graph is built;
+            optimal for a full band, i.e. ``2b + 1`` colors at half-bandwidth
+            ``b``, and valid for any pattern; ``ordering`` is ignored).
+        time_limit: Time budget in seconds for ``algorithm="exact"``. The
+            result is optimal if the search finishes in time, otherwise the
+            best coloring found so far is returned.
+        tabu_iterations: Total move budget of ``refine="tabu"``.
+        deterministic: Make ``algorithm="speculative"`` give identical
+            colorings for every thread count and run: each round colors from
+            the previous round's colors and conflicts are settled by random
+            vertex weights drawn from ``seed``, at the cost of a few more
+            rounds. All other algorithms are deterministic already.
+        restarts: Greedy runs to keep the best of: the first in ``ordering``
+            (or by ``priority``), the others in random orders drawn from
+            ``seed`` and run in parallel; the coloring with the fewest colors
+            wins, the earliest on ties. Only for ``algorithm="greedy"``.
+        max_colors: Colors the result must not exceed. A coloring above it
+            is followed by greedy runs in the ``"largest_first"``,
+            ``"smallest_last"``, ``"dsatur"`` and ``"dsatur_degree"``
+            orderings and with random restarts, then by ``"iterated_greedy"``,
+            ``"kempe"`` and ``"tabu"`` refinement of the best coloring so far
+            and, on graphs of at most 10,000 vertices, an exact search within
+            ``time_limit``, stopping at the first coloring that fits. Steps
+            needing the explicit distance-2 graph are skipped under
+            ``low_memory``. Counts DOF colors, so with blocks the vertex
+            colors are held to ``max_colors // block_size``: the budget is
+            rounded down to a multiple of the DOFs of a block (``block_size``
+            times ``dofs_per_node``), and a budget below one block raises
+            ``ValueError``.
+        require_max_colors: Raise ``ValueError`` if no step reaches
+            ``max_colors``; otherwise return the best coloring found, which
+            the caller can check against ``max_colors``.
+        priority: Optional per-vertex priority (float64). Higher-priority
+            vertices are colored first by ``algorithm="greedy"`` (overriding
+            ``ordering``), and for every algorithm the colors are renumbered
+            so that classes holding higher priorities get lower color ids.
+        precolors: Optional per-DOF colors to keep (integer, ``-1`` for free
+            DOFs), e.g. interface DOFs colored by a global coordinator. The
+            free DOFs are colored greedily around them and the colors are not
+            renumbered, so ``priority`` only orders the free DOFs. Needs
+            ``algorithm="greedy"`` with a ``low_memory`` ordering and no
+            ``refine``. DOFs of one block or tie must follow one vertex color
+            (``c * block_size + r`` for DOF ``r`` of a block) and fixed DOFs
+            within distance 2 of each other must differ, or ``ValueError``
+            is raised.
+        allowed_colors: Optional admissible colors per DOF, as uint64
+            bitmasks (bit ``c`` allowing color ``c``) or a boolean
+            ``(n_dofs, k)`` matrix for colors below ``k <= 64``. Needs the
+            strategies of ``precolors``, whose colors are kept even if not
+            allowed. With blocks, block color ``c`` is allowed if every DOF
+            ``r`` allows ``c * block_size + r``. ``ValueError`` is raised if
+            every greedy run leaves some vertex without an allowed color.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        symmetrize: Mirror the pattern before building the distance-2 graph,
+            for symmetric matrices stored as one triangle (half storage).
+        block_size: Block size ``b`` of BSR input, where ``row_ptr`` and
+            ``col_idx`` index ``b x b`` blocks and ``n_dofs`` counts scalar
+            DOFs. The block graph is colored and DOF ``i * b + r`` of block
+            ``i`` gets color ``block_color[i] * b + r``. Priorities act per
+            block through their maximum.
+        dofs_per_node: Number ``d`` of DOFs per mesh node of scalar input,
+            DOF ``i * d + r`` being component ``r`` of node ``i`` (e.g. 3 for
+            3D elasticity). The pattern is merged into its nodal pattern,
+            ``d * d`` times smaller, whose colors are expanded as for
+            ``block_size``. With dense nodal blocks, as in elasticity, the
+            nodal graph has the same conflicts at a fraction of the memory.
+            Priorities act per node through their maximum. Only the columns
+            are merged for a rectangular pattern.
+        ties: Groups of DOFs constrained together, such as multi-point
+            constraints or periodic boundary pairs, as a list of DOF
+            sequences or an ``(n_ties, k)`` array. Probing through the
+            constraint operator perturbs tied DOFs together, so each group
+            gets one color; it is colored as a single vertex carrying the
+            conflicts of all its DOFs, and groups sharing a DOF are merged.
+            Priorities act per group through their maximum. Groups refer to
+            columns for a rectangular pattern and cannot be combined with
+            ``block_size`` or ``dofs_per_node``.
+        supervariables: Merge DOFs whose conflicts are identical, i.e.
+            columns with the same nonzero rows (for a square pattern, equal
+            rows and columns), and color the condensed graph. Members of a
+            supervariable need distinct colors, so each takes the next color
+            after its group's; the result is still a valid distance-2 coloring
+            and often found faster on patterns with repeated columns. It
+            cannot be combined with ``precolors``, ``allowed_colors`` or
+            ``max_colors``.
+        n_cols: Number of columns of a rectangular ``n_dofs x n_cols`` pattern
+            (taken from the shape of a non-square sparse or mask input). The
+            pattern is then read as a bipartite row/column graph and the
+            columns are colored so that columns sharing a row differ; ``colors``
+            and ``priority`` refer to columns.
+        low_memory: Never build the distance-2 graph, bounding peak memory
+            by the pattern and its transpose; strategies that need the
+            explicit graph raise ``ValueError`` instead.
+        return_stats: Also return a ``ColoringStats`` with the number of
+            colors, the min/max/mean color class size and the max degree and
+            nnz of the distance-2 graph of the DOFs, also when blocks, nodes,
+            ties or supervariables were colored; these take one extra pass
+            over the colored graph.
+        return_timings: Also return a ``{stage: seconds}`` dict with the
+            wall-clock time of every stage that ran, in order: ``"adjacency"``
+            (parsing, ``symmetrize`` and transposing the pattern),
+            ``"distance2"`` (degrees or the explicit distance-2 graph, when
+            the strategy needs them), ``"ordering"`` (fixed orderings only;
+            dynamic ones such as DSATUR count as coloring), ``"coloring"``
+            (including ``refine``), ``"stats"``.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        progress: Optional callable ``progress(done, total, phase)``, called
+            at the start and end of every phase and after each
+            ``progress_interval`` processed vertices (blocks for BSR input).
+            The phases are ``"degrees"``, ``"distance2"`` (building the
+            explicit graph) and ``"coloring"``; strategies that color the
+            explicit graph only report the start and end of ``"coloring"``.
+            Calls may come from worker threads, so ``done`` need not increase
+            monotonically. An exception raised by ``progress`` aborts the
+            coloring and propagates.
+        progress_interval: Processed vertices between two ``progress`` calls.
+        verbose: Log the strategy, the start and end of every phase, the
+            size of the distance-2 graph (once its degrees are known) and the
+            number of colors at ``INFO`` level to the ``"tatva_coloring"``
+            logger of Python's ``logging`` module.
+        validate: Input checks: ``"strict"`` checks every index against the
+            shape and the structure of ``row_ptr``, and sorts rows and merges
+            duplicate entries where needed; ``"fast"`` only checks array
+            lengths and the ends of ``row_ptr``; ``"off"`` trusts the input
+            to be canonical CSR/CSC. Malformed input under ``"fast"`` or
+            ``"off"`` may raise an internal error or give invalid colors.
+            COO input is always checked. Structural symmetry is not checked
+            here: square patterns are colored through both the pattern and
+            its transpose, so one-sided entries are safe. The star, acyclic,
+            Hessian recovery and distributed functions assume symmetry and,
+            under ``"strict"``, reject an entry without its mirror unless
+            ``symmetrize=True`` declares half storage.
 
     Returns:
-        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (int32).
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (``colors_dtype``).
+        stats: ``ColoringStats``, only with ``return_stats``.
+        timings: ``dict`` of stage times in seconds, only with ``return_timings``.
     """
     if _distance2_colors_ext is None:
         raise ImportError(
             "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
         )
 
-    row_ptr_arr = np.asarray(row_ptr, dtype=np.int64)
-    col_idx_arr = np.asarray(col_idx, dtype=np.int64)
-    return _distance2_colors_ext(row_ptr_arr, col_idx_arr, n_dofs)
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    priority_arr = None if priority is None else np.asarray(priority, dtype=np.float64)
+    precolors_arr = None if precolors is None else np.asarray(precolors, dtype=np.int64)
+    allowed_arr = None if allowed_colors is None else _allowed_masks(allowed_colors)
+    tie_ptr, tie_dofs = (None, None) if ties is None else _element_arrays(ties, None)
+    colors, stats, timings = _distance2_colors_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        restarts,
+        max_colors,
+        require_max_colors,
+        priority_arr,
+        precolors_arr,
+        allowed_arr,
+        format,
+        symmetrize,
+        block_size,
+        dofs_per_node,
+        tie_ptr,
+        tie_dofs,
+        supervariables,
+        n_cols,
+        low_memory,
+        return_stats,
+        return_timings,
+        np.dtype(colors_dtype).name,
+        progress,
+        progress_interval,
+        verbose,
+        validate,
+    )
+    result = [colors]
+    if return_stats:
+        result.append(stats)
+    if return_timings:
+        result.append(timings)
+    return tuple(result) if len(result) > 1 else colors
 
 
-def distance2_color_and_seeds(
-    row_ptr: NDArray, col_idx: NDArray, n_dofs: int
-) -> Tuple[NDArray, List[NDArray]]:
-    """Color the distance-2 graph induced by a CSR sparse matrix.
+class ColoringGraph:
+    """Distance-2 graph of a pattern, built once and queried repeatedly.
+
+    Construction parses the pattern, mirrors it with ``symmetrize`` and
+    builds its transpose once. Vertex degrees and the explicit distance-2
+    graph are kept as soon as a strategy needs them, so recoloring with
+    another strategy or regenerating seeds in another layout reuses them.
+
+    Args:
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows (degrees of freedom).
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        symmetrize: Mirror a half-stored pattern first.
+        block_size: Block size of BSR input; the block graph is colored.
+        dofs_per_node: DOFs per node of scalar input, whose nodal graph is
+            colored, as for ``distance2_colors``.
+        n_cols: Number of columns of a rectangular pattern, whose columns
+            are colored instead.
+        validate: Input checks, as for ``distance2_colors``.
+    """
+
+    def __init__(
+        self,
+        row_ptr: Any,
+        col_idx: Optional[NDArray] = None,
+        n_dofs: Optional[int] = None,
+        format: str = "csr",
+        symmetrize: bool = False,
+        block_size: int = 1,
+        dofs_per_node: int = 1,
+        n_cols: Optional[int] = None,
+        validate: str = "strict",
+    ) -> None:
+        if _ColoringGraphExt is None:
+            raise ImportError(
+                "tatva_color extension is not built. "
+                "Run `maturin develop` in rust/coloring`."
+            )
+
+        row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+            row_ptr, col_idx, n_dofs, n_cols, format
+        )
+        self._graph = _ColoringGraphExt(
+            row_ptr_arr,
+            col_idx_arr,
+            n_dofs,
+            format,
+            symmetrize,
+            block_size,
+            dofs_per_node,
+            n_cols,
+            validate,
+        )
+
+    @property
+    def n_dofs(self) -> int:
+        """Number of colored DOFs (the columns of a rectangular pattern)."""
+        return self._graph.n_dofs
+
+    @property
+    def block_size(self) -> int:
+        """DOFs per colored vertex: the block size or ``dofs_per_node``."""
+        return self._graph.block_size
+
+    def colors(
+        self,
+        ordering: str = "natural",
+        seed: int = 0,
+        refine: Optional[str] = None,
+        algorithm: str = "greedy",
+        time_limit: float = 10.0,
+        tabu_iterations: int = 10000,
+        deterministic: bool = False,
+        restarts: int = 1,
+        max_colors: Optional[int] = None,
+        require_max_colors: bool = True,
+        priority: Optional[NDArray] = None,
+        precolors: Optional[NDArray] = None,
+        allowed_colors: Optional[Any] = None,
+        low_memory: bool = False,
+        return_stats: bool = False,
+        colors_dtype: Any = "int32",
+        progress: Optional[Callable[[int, int, str], Any]] = None,
+        progress_interval: int = 100_000,
+        verbose: bool = False,
+    ) -> Union[NDArray, Tuple[NDArray, ColoringStats]]:
+        """Distance-2 colors for one strategy.
+
+        The arguments and results are those of ``distance2_colors``.
+        """
+        priority_arr = (
+            None if priority is None else np.asarray(priority, dtype=np.float64)
+        )
+        precolors_arr = (
+            None if precolors is None else np.asarray(precolors, dtype=np.int64)
+        )
+        allowed_arr = (
+            None if allowed_colors is None else _allowed_masks(allowed_colors)
+        )
+        colors, stats = self._graph.colors(
+            ordering,
+            seed,
+            refine,
+            algorithm,
+            time_limit,
+            tabu_iterations,
+            deterministic,
+            restarts,
+            max_colors,
+            require_max_colors,
+            priority_arr,
+            precolors_arr,
+            allowed_arr,
+            low_memory,
+            return_stats,
+            np.dtype(colors_dtype).name,
+            progress,
+            progress_interval,
+            verbose,
+        )
+        return (colors, stats) if return_stats else colors
+
+    def seeds(
+        self,
+        colors: NDArray,
+        seed_format: str = "list",
+        seed_dtype: Any = "bool",
+        rademacher_seed: Optional[int] = None,
+        out: Optional[Union[NDArray, List[NDArray]]] = None,
+        from_dlpack: Optional[Callable[[Any], Any]] = None,
+        backend: str = "numpy",
+        pin_memory: bool = False,
+    ) -> _Seeds:
+        """Seed matrix of ``colors``, as for ``distance2_color_and_seeds``.
+
+        With ``out``, the seeds are written into that preallocated dense
+        array or list of arrays, which is returned; with ``from_dlpack`` or
+        ``backend="torch"`` they are handed to that library.
+        """
+        _check_seed_output(seed_format, from_dlpack, backend, pin_memory)
+        seeds = self._graph.seeds(
+            _as_index_array(colors),
+            seed_format,
+            np.dtype(seed_dtype).name,
+            rademacher_seed,
+            out,
+        )
+        return _output_seeds(seeds, seed_format, from_dlpack, backend, pin_memory)
+
+    def groups(self, colors: NDArray) -> List[NDArray]:
+        """Index array (int64) of each color class of ``colors``."""
+        return self._graph.groups(_as_index_array(colors))
+
+    def verify(self, colors: NDArray) -> bool:
+        """Whether ``colors`` separates every pair of DOFs within distance 2."""
+        return self._graph.verify(_as_index_array(colors))
+
+    def memory_estimate(self) -> int:
+        """Bytes of the explicit distance-2 graph, as ``distance2_memory_estimate``."""
+        return self._graph.memory_estimate()
+
+    def adjacency(self) -> Tuple[NDArray, NDArray]:
+        """Explicit distance-2 graph as in ``distance2_adjacency``.
+
+        The graph is built on the first call (or first strategy needing it)
+        and kept.
+        """
+        return self._graph.adjacency()
+
+    def lower_bound(self, n_seeds: int = 32) -> int:
+        """Lower bound on the number of colors, as ``distance2_lower_bound``."""
+        return self._graph.lower_bound(n_seeds)
+
+    def coloring(
+        self,
+        ordering: str = "natural",
+        seed: int = 0,
+        refine: Optional[str] = None,
+        algorithm: str = "greedy",
+        time_limit: float = 10.0,
+        tabu_iterations: int = 10000,
+        deterministic: bool = False,
+        restarts: int = 1,
+        max_colors: Optional[int] = None,
+        require_max_colors: bool = True,
+        priority: Optional[NDArray] = None,
+        precolors: Optional[NDArray] = None,
+        allowed_colors: Optional[Any] = None,
+        low_memory: bool = False,
+        colors_dtype: Any = "int32",
+        progress: Optional[Callable[[int, int, str], Any]] = None,
+        progress_interval: int = 100_000,
+        verbose: bool = False,
+    ) -> "Distance2Coloring":
+        """``Distance2Coloring`` of this graph for one strategy.
+
+        The arguments are those of ``distance2_colors``.
+        """
+        colors = self.colors(
+            ordering,
+            seed,
+            refine,
+            algorithm,
+            time_limit,
+            tabu_iterations,
+            deterministic,
+            restarts,
+            max_colors,
+            require_max_colors,
+            priority,
+            precolors,
+            allowed_colors,
+            low_memory,
+            colors_dtype=colors_dtype,
+            progress=progress,
+            progress_interval=progress_interval,
+            verbose=verbose,
+        )
+        return Distance2Coloring._from_colors(self, colors)
+
+
+class Distance2Coloring:
+    """Distance-2 coloring of a pattern, with its outputs available on demand.
+
+    The pattern is colored once on construction; colors, seeds in any layout
+    and dtype, color classes and a validity check are then separate queries
+    on the kept graph, instead of options of one tuple-returning call. Use
+    ``ColoringGraph.coloring`` to color an existing graph.
+
+    Args:
+        row_ptr, col_idx, n_dofs, format, symmetrize, block_size,
+        dofs_per_node, n_cols, validate: The pattern, its node structure and
+            its input checks, as for ``ColoringGraph``.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+        deterministic, restarts, max_colors, require_max_colors, priority,
+        precolors, allowed_colors, low_memory, colors_dtype, progress,
+        progress_interval, verbose: The strategy, colors dtype and progress
+            reports, as for ``distance2_colors``.
+    """
+
+    def __init__(
+        self,
+        row_ptr: Any,
+        col_idx: Optional[NDArray] = None,
+        n_dofs: Optional[int] = None,
+        ordering: str = "natural",
+        seed: int = 0,
+        refine: Optional[str] = None,
+        algorithm: str = "greedy",
+        time_limit: float = 10.0,
+        tabu_iterations: int = 10000,
+        deterministic: bool = False,
+        restarts: int = 1,
+        max_colors: Optional[int] = None,
+        require_max_colors: bool = True,
+        priority: Optional[NDArray] = None,
+        precolors: Optional[NDArray] = None,
+        allowed_colors: Optional[Any] = None,
+        format: str = "csr",
+        symmetrize: bool = False,
+        block_size: int = 1,
+        dofs_per_node: int = 1,
+        n_cols: Optional[int] = None,
+        low_memory: bool = False,
+        colors_dtype: Any = "int32",
+        progress: Optional[Callable[[int, int, str], Any]] = None,
+        progress_interval: int = 100_000,
+        verbose: bool = False,
+        validate: str = "strict",
+    ) -> None:
+        graph = ColoringGraph(
+            row_ptr,
+            col_idx,
+            n_dofs,
+            format,
+            symmetrize,
+            block_size,
+            dofs_per_node,
+            n_cols,
+            validate,
+        )
+        colors = graph.colors(
+            ordering,
+            seed,
+            refine,
+            algorithm,
+            time_limit,
+            tabu_iterations,
+            deterministic,
+            restarts,
+            max_colors,
+            require_max_colors,
+            priority,
+            precolors,
+            allowed_colors,
+            low_memory,
+            colors_dtype=colors_dtype,
+            progress=progress,
+            progress_interval=progress_interval,
+            verbose=verbose,
+        )
+        self._init(graph, colors)
+
+    @classmethod
+    def _from_colors(cls, graph: ColoringGraph, colors: NDArray) -> "Distance2Coloring":
+        coloring = cls.__new__(cls)
+        coloring._init(graph, colors)
+        return coloring
+
+    def _init(self, graph: ColoringGraph, colors: NDArray) -> None:
+        self.graph = graph
+        # Shared by every query, so callers must not change it in place.
+        colors.flags.writeable = False
+        self._colors = colors
+
+    @property
+    def n_dofs(self) -> int:
+        """Number of colored DOFs."""
+        return self.graph.n_dofs
+
+    @property
+    def n_colors(self) -> int:
+        """Number of colors used."""
+        return int(self._colors.max()) + 1 if self._colors.size else 0
+
+    def colors(self) -> NDArray:
+        """Read-only color id of every DOF, of the ``colors_dtype`` chosen."""
+        return self._colors
+
+    def seeds(
+        self,
+        format: str = "list",
+        dtype: Any = "bool",
+        rademacher_seed: Optional[int] = None,
+        out: Optional[Union[NDArray, List[NDArray]]] = None,
+        from_dlpack: Optional[Callable[[Any], Any]] = None,
+        backend: str = "numpy",
+        pin_memory: bool = False,
+    ) -> _Seeds:
+        """Seed matrix in the given layout and dtype.
+
+        The arguments are ``seed_format``, ``seed_dtype``, ``rademacher_seed``,
+        ``out``, ``from_dlpack``, ``backend`` and ``pin_memory`` of
+        ``distance2_color_and_seeds``.
+        """
+        return self.graph.seeds(
+            self._colors,
+            format,
+            dtype,
+            rademacher_seed,
+            out,
+            from_dlpack,
+            backend,
+            pin_memory,
+        )
+
+    def groups(self) -> List[NDArray]:
+        """Index array (int64) of each color class."""
+        return self.graph.groups(self._colors)
+
+    def verify(self) -> bool:
+        """Whether the coloring separates every pair of DOFs within distance 2."""
+        return self.graph.verify(self._colors)
+
+    def __repr__(self) -> str:
+        return f"Distance2Coloring(n_dofs={self.n_dofs}, n_colors={self.n_colors})"
+
+
+def distance2_memory_estimate(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    n_cols: Optional[int] = None,
+    validate: str = "strict",
+) -> int:
+    """Bytes the explicit distance-2 graph of a pattern would occupy.
+
+    The count is exact: the 2-hop neighborhoods are walked once without
+    storing them, so this is safe to call on patterns whose distance-2 graph
+    does not fit in memory. Compare it against the available memory before
+    choosing a strategy that builds the graph, or pass ``low_memory=True``.
 
     Args:
-        row_ptr: CSR row pointer of length ``n_dofs + 1``.
-        col_idx: CSR column indices.
-        n_dofs: Number of rows/cols (degrees of freedom).
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows (degrees of freedom).
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        symmetrize: Mirror a half-stored pattern first.
+        block_size: Block size of BSR input; the graph is over blocks.
+        n_cols: Number of columns of a rectangular pattern, whose column
+            intersection graph is measured instead.
+        validate: Input checks, as for ``distance2_colors``.
 
     Returns:
-        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (int32).
-        seeds: ``List[np.ndarray]`` of one-hot vectors (bool), one per color.
+        Size in bytes of the graph's flat neighbor and offset arrays.
     """
-    if _distance2_color_and_seeds_ext is None:
+    if _distance2_memory_estimate_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    return _distance2_memory_estimate_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        format,
+        symmetrize,
+        block_size,
+        n_cols,
+        validate,
+    )
+
+
+def distance2_adjacency(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    n_cols: Optional[int] = None,
+    validate: str = "strict",
+) -> Tuple[NDArray, NDArray]:
+    """Distance-2 graph of a pattern as CSR index arrays.
+
+    This is the conflict graph the coloring functions use, built in parallel
+    from the pattern, so tools like partitioners or plotting can take it
+    directly instead of a symbolic ``A @ A`` product. Wrap it for scipy with
+    ``scipy.sparse.csr_array((np.ones(len(indices), bool), indices, indptr))``.
+
+    Args:
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows (degrees of freedom).
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        symmetrize: Mirror a half-stored pattern first.
+        block_size: Block size of BSR input; the graph is over blocks.
+        n_cols: Number of columns of a rectangular pattern, whose column
+            intersection graph is returned instead.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        indptr: ``np.ndarray`` (int64) of length ``n + 1``.
+        indices: ``np.ndarray`` (int64) with the sorted neighbors of each
+            vertex, without the vertex itself.
+    """
+    if _distance2_adjacency_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    return _distance2_adjacency_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        format,
+        symmetrize,
+        block_size,
+        n_cols,
+        validate,
+    )
+
+
+def save_metis_graph(
+    path: Union[str, os.PathLike],
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    distance: int = 2,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    n_cols: Optional[int] = None,
+    validate: str = "strict",
+) -> None:
+    """Write the conflict graph of a pattern as a METIS graph file.
+
+    The file is built and written in Rust, so partitioners such as
+    ``gpmetis``, ParMETIS or KaHIP can study the locality of the color
+    classes without the graph ever passing through Python. Vertices are
+    numbered from 1 in the file, as METIS expects.
+
+    Args:
+        path: Destination file, overwritten if it exists.
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows (degrees of freedom).
+        distance: ``2`` for the distance-2 graph of ``distance2_adjacency``,
+            ``1`` for the symmetrized pattern without its diagonal, as
+            colored by ``distance1_color`` (square patterns only).
+        format, symmetrize, block_size, n_cols, validate: The pattern and its
+            input checks, as for ``distance2_adjacency``.
+    """
+    if _save_metis_graph_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    _save_metis_graph_ext(
+        os.fspath(path),
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        distance,
+        format,
+        symmetrize,
+        block_size,
+        n_cols,
+        validate,
+    )
+
+
+def rcm_permutation(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    format: str = "csr",
+    block_size: int = 1,
+    validate: str = "strict",
+) -> NDArray:
+    """Reverse Cuthill–McKee permutation of a square pattern.
+
+    Reorders the DOFs so that the nonzeros gather near the diagonal, for
+    banded solvers or better locality of the coloring, without a
+    ``scipy.sparse.csgraph`` round trip. Every connected component starts at
+    a pseudo-peripheral vertex and the pattern is symmetrized first.
+
+    Args:
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance1_color``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows (degrees of freedom).
+        format: Storage layout of the index arrays, as for ``distance1_color``.
+        block_size: Block size of BSR input; blocks are reordered as a whole.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        perm: ``np.ndarray`` (int64) of length ``n_dofs`` whose entry ``k`` is
+        the old index of the DOF at position ``k``, so ``A[perm][:, perm]`` is
+        the reordered matrix, as from ``scipy.sparse.csgraph.reverse_cuthill_mckee``.
+    """
+    if _rcm_permutation_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    return _rcm_permutation_ext(
+        row_ptr_arr, col_idx_arr, n_dofs, format, block_size, validate
+    )
+
+
+def distance2_lower_bound(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    n_cols: Optional[int] = None,
+    n_seeds: int = 32,
+    validate: str = "strict",
+) -> int:
+    """Lower bound on the number of colors of any distance-2 coloring.
+
+    A clique of the distance-2 graph needs one color per vertex. Starting from
+    each of the ``n_seeds`` vertices of highest degree, neighbors are added in
+    decreasing degree while they stay adjacent to the whole clique, and the
+    largest clique found gives the bound. It costs about one pass over the
+    2-hop neighborhoods, like ``distance2_memory_estimate``. A coloring with
+    as many colors as the bound is optimal; the maximum degree plus one
+    (``ColoringStats.max_degree + 1``) bounds greedy colorings from above.
+
+    Args:
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows (degrees of freedom).
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        symmetrize: Mirror a half-stored pattern first.
+        block_size: Block size of BSR input; the block clique is scaled by
+            ``block_size``, as every DOF of a block needs its own color.
+        n_cols: Number of columns of a rectangular pattern, whose column
+            coloring is bounded instead.
+        n_seeds: Number of start vertices; more seeds may find larger cliques.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        Minimum number of colors, in DOFs (columns with ``n_cols``).
+    """
+    if _distance2_lower_bound_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    return _distance2_lower_bound_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        format,
+        symmetrize,
+        block_size,
+        n_cols,
+        n_seeds,
+        validate,
+    )
+
+
+def _edited_entries(entries: Optional[Tuple[Any, Any]]) -> Tuple[NDArray, NDArray]:
+    """Row and column index arrays of edited entries; empty for ``None``."""
+    if entries is None:
+        empty = np.empty(0, dtype=np.int64)
+        return empty, empty
+    rows, cols = entries
+    return _as_index_array(rows), _as_index_array(cols)
+
+
+def repair_distance2_colors(
+    colors: NDArray,
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    added: Optional[Tuple[Any, Any]] = None,
+    removed: Optional[Tuple[Any, Any]] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    n_cols: Optional[int] = None,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> NDArray:
+    """Repair a distance-2 coloring after a few nonzeros changed.
+
+    Instead of recoloring from scratch, only vertices touched by the edits
+    are revisited: an endpoint of an added entry that now shares its color
+    with a distance-2 neighbor is recolored greedily, and an endpoint of a
+    removed entry moves to a smaller color if one became free. Colors left
+    without vertices are dropped, keeping the order of the others; every
+    other vertex keeps its color.
+
+    Args:
+        colors: Valid distance-2 coloring of the pattern before the edits,
+            as returned by ``distance2_colors``.
+        row_ptr: Row pointer of the pattern after the edits, or a
+            ``scipy.sparse`` matrix/array or dense mask, as for
+            ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows (degrees of freedom).
+        added: ``(rows, cols)`` index arrays of the added entries.
+        removed: ``(rows, cols)`` index arrays of the removed entries.
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        symmetrize: Mirror a half-stored pattern first; edits then refer to
+            the stored triangle.
+        n_cols: Number of columns of a rectangular pattern, whose column
+            coloring is repaired instead.
+        colors_dtype: Integer dtype of the returned colors, as for
+            ``distance2_colors``.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` with the repaired color ids (``colors_dtype``).
+    """
+    if _repair_distance2_colors_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    added_rows, added_cols = _edited_entries(added)
+    removed_rows, removed_cols = _edited_entries(removed)
+    return _repair_distance2_colors_ext(
+        _as_index_array(colors),
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        added_rows,
+        added_cols,
+        removed_rows,
+        removed_cols,
+        format,
+        symmetrize,
+        n_cols,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+
+
+def verify_coloring(
+    colors: NDArray,
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    distance: int = 2,
+    format: str = "csr",
+    symmetrize: bool = False,
+    n_cols: Optional[int] = None,
+    validate: str = "strict",
+) -> Union[bool, List[Tuple[int, int]]]:
+    """Check that no two DOFs within ``distance`` of each other share a color.
+
+    Meant for colorings loaded from a cache or produced by other tools; the
+    check runs in parallel in Rust.
+
+    Args:
+        colors: Color id of every DOF (of every column with ``n_cols``).
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows; defaults to ``len(colors)``.
+        distance: ``2`` checks the conflicts of ``distance2_colors``
+            (including ``symmetrize`` and ``n_cols``); ``1`` and larger
+            distances check those of ``distance1_color`` and
+            ``distance_k_color`` on the symmetrized pattern.
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        symmetrize: Mirror a half-stored pattern first (distance 2).
+        n_cols: Number of columns of a rectangular pattern whose column
+            coloring is checked (distance 2 only).
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        ``True`` for a valid coloring, otherwise the sorted list of
+        conflicting DOF pairs ``(u, v)`` with ``u < v``.
+    """
+    if _coloring_conflicts_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    colors_arr = _as_index_array(colors)
+    if n_dofs is None and n_cols is None and col_idx is not None:
+        n_dofs = len(colors_arr)
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    conflicts = _coloring_conflicts_ext(
+        colors_arr,
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        distance,
+        format,
+        symmetrize,
+        n_cols,
+        validate,
+    )
+    return conflicts if conflicts else True
+
+
+def verify_column_groups(
+    groups: Any,
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_rows: Optional[int] = None,
+    n_cols: Optional[int] = None,
+    group_ptr: Optional[NDArray] = None,
+    format: str = "csr",
+    validate: str = "strict",
+) -> Union[bool, List[Tuple[int, int, int]]]:
+    """Check that the columns of every group are structurally orthogonal.
+
+    Meant for column groupings imported from other tools, which need not
+    cover every column and may even overlap; the check runs in parallel in
+    Rust. Colorings of this package are checked by ``verify_coloring``.
+
+    Args:
+        groups: Column indices of every group as a ragged sequence, an
+            ``(n_groups, group_size)`` array, or the flat column list of all
+            groups when ``group_ptr`` is given.
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense
+            mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_rows: Number of rows; taken from the shape of a sparse or mask
+            input.
+        n_cols: Number of columns, likewise.
+        group_ptr: Optional group pointer of length ``n_groups + 1`` into a
+            flat ``groups`` array.
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        ``True`` if all groups are structurally orthogonal, otherwise the
+        sorted list of violations ``(row, first, other)``: columns ``first``
+        and ``other`` of one group both have an entry in ``row``.
+    """
+    if _orthogonality_violations_ext is None:
         raise ImportError(
             "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
         )
 
-    row_ptr_arr = np.asarray(row_ptr, dtype=np.int64)
-    col_idx_arr = np.asarray(col_idx, dtype=np.int64)
-    return _distance2_color_and_seeds_ext(row_ptr_arr, col_idx_arr, n_dofs)
+    ptr, cols = _element_arrays(groups, group_ptr)
+    row_ptr_arr, col_idx_arr, n_rows, n_cols, format = _pattern_arrays(
+        row_ptr, col_idx, n_rows, n_cols, format
+    )
+    violations = _orthogonality_violations_ext(
+        ptr, cols, row_ptr_arr, col_idx_arr, n_rows, n_cols, format, validate
+    )
+    return violations if violations else True
+
+
+def _csr_rows(
+    indptr: NDArray, indices: NDArray, rows: NDArray
+) -> Tuple[NDArray, NDArray]:
+    """CSR ``(indptr, indices)`` of the given rows of a CSR pattern."""
+    starts = indptr[rows].astype(np.int64)
+    lengths = indptr[rows + 1].astype(np.int64) - starts
+    ptr = np.concatenate(([0], np.cumsum(lengths)))
+    gather = np.repeat(starts - ptr[:-1], lengths) + np.arange(ptr[-1])
+    return ptr, indices[gather]
+
+
+def _raise_collective_error(rank: int, errors: List[Optional[str]]) -> None:
+    """Raise ``ValueError`` on every rank if any rank reported an error.
+
+    ``errors`` holds one message or ``None`` per rank, as gathered from all
+    of them; a rank raises its own message, the others name the first rank
+    that failed.
+    """
+    failed = [r for r, error in enumerate(errors) if error is not None]
+    if not failed:
+        return
+    if errors[rank] is not None:
+        raise ValueError(errors[rank])
+    raise ValueError(f"rank {failed[0]} failed: {errors[failed[0]]}")
+
+
+def distributed_distance2_colors(
+    comm: Any,
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    priority: Optional[NDArray] = None,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> NDArray:
+    """Distance-2 colors of a pattern whose rows are spread over MPI ranks.
+
+    Every rank passes only its own rows, so the pattern is never gathered on
+    one rank. Rank ``r`` holds the consecutive global rows following those
+    of ranks ``0..r``, as in PETSc's layout, which is all the ownership
+    information needed for the ghost columns. Each rank fetches the rows of
+    its ghosts from their owners once, then colors its boundary in rounds:
+    the pending boundary rows are colored greedily, the colors within
+    distance 2 of other ranks are exchanged, and of every pair of rows that
+    clash across ranks the one of lower precedence is recolored in the next
+    round. Precedence is ``priority`` if given, ties going to the smaller
+    global row; the pending row of highest precedence always keeps its
+    color, so the rounds end. Rows without other ranks within distance 2
+    are colored last, without communication. The colors agree across
+    ranks: no two rows within distance 2 share one, wherever they live.
+
+    The pattern must be structurally symmetric, as an assembled FEM matrix
+    is; ``validate="strict"`` checks this for every entry of the local rows.
+    Called collectively on every rank of ``comm``; invalid arguments or
+    input on any rank raise ``ValueError`` on all of them.
+
+    Args:
+        comm: ``mpi4py`` communicator. mpi4py itself is not imported; any
+            object with ``Get_rank``, ``Get_size``, ``allgather``, ``alltoall``
+            and ``allreduce`` works.
+        row_ptr: Row pointer of the local rows, or a ``scipy.sparse``
+            matrix/array of shape ``(n_local_rows, n_dofs)``.
+        col_idx: Global column indices; omitted for a sparse input.
+        n_dofs: Global number of rows; taken from a sparse input's shape.
+        priority: Optional float per local row; rows of higher priority keep
+            their color in a clash across ranks, e.g. to favor rows of
+            higher degree. Defaults to the global row index, smallest first.
+            Given on every rank or on none.
+        colors_dtype: Integer dtype of the returned colors, as for
+            ``distance2_colors``.
+        validate: Input checks of the local and ghost rows, as for
+            ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` with the color of every local row
+            (``colors_dtype``).
+    """
+    if _HaloGraphExt is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    if col_idx is None:
+        if not hasattr(row_ptr, "tocsr"):
+            raise TypeError(
+                "col_idx may only be omitted when passing a scipy.sparse matrix/array"
+            )
+        matrix = row_ptr.tocsr()
+        if n_dofs is not None and n_dofs != matrix.shape[1]:
+            raise ValueError(
+                f"matrix shape {matrix.shape} does not match n_dofs = {n_dofs}"
+            )
+        row_ptr, col_idx, n_dofs = matrix.indptr, matrix.indices, matrix.shape[1]
+    if n_dofs is None:
+        raise TypeError("n_dofs is required when passing index arrays")
+    row_ptr = _as_index_array(row_ptr)
+    col_idx = _as_index_array(col_idx)
+    n_local = len(row_ptr) - 1
+    rank, size = comm.Get_rank(), comm.Get_size()
+
+    # Local argument errors are agreed on before any other collective call,
+    # so that a rank raising alone cannot leave the others blocked.
+    error = None
+    priority_arr = None
+    try:
+        dtype_name = np.dtype(colors_dtype).name
+    except TypeError:
+        dtype_name = str(colors_dtype)
+    if dtype_name not in ("int32", "int64", "uint16"):
+        error = (
+            f"unknown colors_dtype '{dtype_name}', "
+            "expected one of: int32, int64, uint16"
+        )
+    elif priority is not None:
+        try:
+            priority_arr = np.asarray(priority, dtype=np.float64)
+        except (TypeError, ValueError) as exc:
+            error = f"invalid priority: {exc}"
+        else:
+            if priority_arr.shape != (n_local,):
+                error = (
+                    f"priority must have shape ({n_local},), "
+                    f"got {priority_arr.shape}"
+                )
+    gathered = comm.allgather((n_local, error, priority is None))
+    _raise_collective_error(rank, [error for _, error, _ in gathered])
+    dtype = np.dtype(dtype_name)
+    if len({no_priority for _, _, no_priority in gathered}) > 1:
+        raise ValueError("priority must be given on every rank or on none")
+    starts = np.concatenate(([0], np.cumsum([n for n, _, _ in gathered])))
+    if starts[-1] != n_dofs:
+        raise ValueError(f"the ranks hold {starts[-1]} rows, expected {n_dofs}")
+    row_start = int(starts[rank])
+
+    def owners(rows: NDArray) -> NDArray:
+        return np.searchsorted(starts, rows, side="right") - 1
+
+    def by_owner(rows: NDArray) -> List[NDArray]:
+        return np.split(rows, np.searchsorted(owners(rows), np.arange(1, size)))
+
+    # Rows of the ghosts from their owners, in increasing global order.
+    columns = col_idx[: row_ptr[-1]].astype(np.int64)
+    outside = (columns < row_start) | (columns >= row_start + n_local)
+    ghosts = np.unique(columns[outside])
+    requested = comm.alltoall(by_owner(ghosts))
+    replies = comm.alltoall(
+        [_csr_rows(row_ptr, col_idx, rows - row_start) for rows in requested]
+    )
+    ghost_lengths = np.concatenate([np.diff(ptr) for ptr, _ in replies])
+    ghost_ptr = np.concatenate(([0], np.cumsum(ghost_lengths)))
+    ghost_idx = np.concatenate([idx.astype(np.int64) for _, idx in replies])
+    graph, error = None, None
+    try:
+        graph = _HaloGraphExt(
+            row_ptr,
+            col_idx,
+            row_start,
+            n_local,
+            n_dofs,
+            ghosts,
+            ghost_ptr,
+            ghost_idx,
+            validate,
+        )
+    except ValueError as exc:
+        error = str(exc)
+    _raise_collective_error(rank, comm.allgather(error))
+
+    # Local rows whose values every other rank needs, and the halo update.
+    sent = [rows - row_start for rows in comm.alltoall(by_owner(graph.halo()))]
+
+    def exchange(values: NDArray) -> None:
+        received = comm.alltoall([values[rows] for rows in sent])
+        values[n_local:] = np.concatenate(received)
+
+    n_halo = len(graph.halo())
+    local_priority = None
+    if priority_arr is not None:
+        local_priority = np.concatenate((priority_arr, np.empty(n_halo)))
+        exchange(local_priority)
+
+    colors = np.full(n_local + n_halo, -1, dtype=np.int64)
+    boundary = graph.boundary()
+    pending = boundary
+    while comm.allreduce(len(pending)) > 0:
+        colors = graph.color(colors, pending, local_priority)
+        exchange(colors)
+        colors, pending = graph.yield_conflicts(colors, pending, local_priority)
+    interior = np.setdiff1d(np.arange(n_local), boundary)
+    colors = graph.color(colors, interior, local_priority)[:n_local]
+
+    if n_local and colors.max() > np.iinfo(dtype).max:
+        raise ValueError(f"color {colors.max()} does not fit in {dtype.name}")
+    return colors.astype(dtype)
+
+
+def distance2_color_and_seeds(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    restarts: int = 1,
+    max_colors: Optional[int] = None,
+    require_max_colors: bool = True,
+    priority: Optional[NDArray] = None,
+    precolors: Optional[NDArray] = None,
+    allowed_colors: Optional[Any] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    dofs_per_node: int = 1,
+    ties: Optional[Any] = None,
+    supervariables: bool = False,
+    n_cols: Optional[int] = None,
+    low_memory: bool = False,
+    seed_format: str = "list",
+    seed_dtype: Any = "bool",
+    rademacher_seed: Optional[int] = None,
+    out: Optional[Union[NDArray, List[NDArray]]] = None,
+    from_dlpack: Optional[Callable[[Any], Any]] = None,
+    dlpack_colors: bool = False,
+    backend: str = "numpy",
+    pin_memory: bool = False,
+    return_seeds: bool = True,
+    return_stats: bool = False,
+    return_timings: bool = False,
+    colors_dtype: Any = "int32",
+    progress: Optional[Callable[[int, int, str], Any]] = None,
+    progress_interval: int = 100_000,
+    verbose: bool = False,
+    validate: str = "strict",
+) -> Union[NDArray, Tuple[Any, ...]]:
+    """Color the distance-2 graph induced by a CSR sparse matrix.
+
+    Greedy coloring in ``"natural"``, ``"random"``, ``"largest_first"`` or
+    ``"rcm"`` ordering, or by ``priority``, without ``refine`` walks the 2-hop
+    neighborhoods straight from the pattern, so the distance-2 graph is never
+    stored; ``algorithm="banded"`` only reads the bandwidth off the pattern.
+    Other strategies build it explicitly; see
+    ``distance2_memory_estimate`` for its size.
+
+    Ctrl-C stops the degree count, the distance-2 graph build and greedy
+    coloring at the next vertex with ``KeyboardInterrupt``; strategies that
+    color the explicit graph are only interrupted once they finish.
+
+    Args:
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``.
+        restarts: Greedy runs to keep the best of: the first in ``ordering``
+            (or by ``priority``), the others in random orders drawn from
+            ``seed`` and run in parallel; the coloring with the fewest colors
+            wins, the earliest on ties. Only for ``algorithm="greedy"``.
+        max_colors: Colors the result must not exceed. A coloring above it
+            is followed by greedy runs in the ``"largest_first"``,
+            ``"smallest_last"``, ``"dsatur"`` and ``"dsatur_degree"``
+            orderings and with random restarts, then by ``"iterated_greedy"``,
+            ``"kempe"`` and ``"tabu"`` refinement of the best coloring so far
+            and, on graphs of at most 10,000 vertices, an exact search within
+            ``time_limit``, stopping at the first coloring that fits. Steps
+            needing the explicit distance-2 graph are skipped under
+            ``low_memory``. Counts DOF colors, so with blocks the vertex
+            colors are held to ``max_colors // block_size``: the budget is
+            rounded down to a multiple of the DOFs of a block (``block_size``
+            times ``dofs_per_node``), and a budget below one block raises
+            ``ValueError``.
+        require_max_colors: Raise ``ValueError`` if no step reaches
+            ``max_colors``; otherwise return the best coloring found, which
+            the caller can check against ``max_colors``.
+        priority: Optional per-vertex priority (float64). Higher-priority
+            vertices are colored first by ``algorithm="greedy"`` (overriding
+            ``ordering``), and for every algorithm the colors are renumbered
+            so that classes holding higher priorities get lower color ids.
+        precolors: Optional per-DOF colors to keep (integer, ``-1`` for free
+            DOFs), e.g. interface DOFs colored by a global coordinator. The
+            free DOFs are colored greedily around them and the colors are not
+            renumbered, so ``priority`` only orders the free DOFs. Needs
+            ``algorithm="greedy"`` with a ``low_memory`` ordering and no
+            ``refine``. DOFs of one block or tie must follow one vertex color
+            (``c * block_size + r`` for DOF ``r`` of a block) and fixed DOFs
+            within distance 2 of each other must differ, or ``ValueError``
+            is raised.
+        allowed_colors: Optional admissible colors per DOF, as uint64
+            bitmasks (bit ``c`` allowing color ``c``) or a boolean
+            ``(n_dofs, k)`` matrix for colors below ``k <= 64``. Needs the
+            strategies of ``precolors``, whose colors are kept even if not
+            allowed. With blocks, block color ``c`` is allowed if every DOF
+            ``r`` allows ``c * block_size + r``. ``ValueError`` is raised if
+            every greedy run leaves some vertex without an allowed color.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        symmetrize: Mirror the pattern before building the distance-2 graph,
+            for symmetric matrices stored as one triangle (half storage).
+        block_size: Block size ``b`` of BSR input, where ``row_ptr`` and
+            ``col_idx`` index ``b x b`` blocks and ``n_dofs`` counts scalar
+            DOFs. The block graph is colored and DOF ``i * b + r`` of block
+            ``i`` gets color ``block_color[i] * b + r``. Priorities act per
+            block through their maximum.
+        dofs_per_node: Number ``d`` of DOFs per mesh node of scalar input,
+            DOF ``i * d + r`` being component ``r`` of node ``i`` (e.g. 3 for
+            3D elasticity). The pattern is merged into its nodal pattern,
+            ``d * d`` times smaller, whose colors are expanded as for
+            ``block_size``. With dense nodal blocks, as in elasticity, the
+            nodal graph has the same conflicts at a fraction of the memory.
+            Priorities act per node through their maximum. Only the columns
+            are merged for a rectangular pattern.
+        ties: Groups of DOFs constrained together, such as multi-point
+            constraints or periodic boundary pairs, as a list of DOF
+            sequences or an ``(n_ties, k)`` array. Probing through the
+            constraint operator perturbs tied DOFs together, so each group
+            gets one color; it is colored as a single vertex carrying the
+            conflicts of all its DOFs, and groups sharing a DOF are merged.
+            Priorities act per group through their maximum. Groups refer to
+            columns for a rectangular pattern and cannot be combined with
+            ``block_size`` or ``dofs_per_node``.
+        supervariables: Merge DOFs whose conflicts are identical, i.e.
+            columns with the same nonzero rows (for a square pattern, equal
+            rows and columns), and color the condensed graph. Members of a
+            supervariable need distinct colors, so each takes the next color
+            after its group's; the result is still a valid distance-2 coloring
+            and often found faster on patterns with repeated columns. It
+            cannot be combined with ``precolors``, ``allowed_colors`` or
+            ``max_colors``.
+        n_cols: Number of columns of a rectangular ``n_dofs x n_cols`` pattern
+            (taken from the shape of a non-square sparse or mask input). The
+            pattern is then read as a bipartite row/column graph and the
+            columns are colored so that columns sharing a row differ; ``colors``
+            and ``priority`` refer to columns.
+        low_memory: Never build the distance-2 graph, bounding peak memory
+            by the pattern and its transpose; strategies that need the
+            explicit graph raise ``ValueError`` instead.
+        seed_format: Layout of ``seeds``: ``"list"`` (one 1D vector per
+            color), ``"dense"`` (one contiguous ``(n_dofs, n_colors)`` array
+            whose column ``c`` is the seed of color ``c``, built directly
+            without an intermediate list), ``"csr"`` (the same matrix as
+            ``(data, indices, indptr)`` with one entry per row, e.g. for
+            ``scipy.sparse.csr_array((data, indices, indptr))``) or
+            ``"groups"`` (the indices of each color class as int64 arrays).
+        seed_dtype: Value type of the seeds: ``"bool"``, ``"float32"``,
+            ``"float64"`` or ``"complex128"`` (or the matching NumPy dtype),
+            e.g. float32 to match a single-precision device.
+        rademacher_seed: Draw every nonzero seed entry as +1 or -1
+            (Rademacher) from this RNG seed instead of 1, as needed for
+            stochastic diagonal or trace estimation with coloring-based
+            deflation. Requires a float or complex ``seed_dtype`` and a
+            ``seed_format`` other than ``"groups"``.
+        out: Preallocated seeds to overwrite instead of allocating new ones:
+            a writable ``(n_dofs, n_colors)`` array for ``seed_format="dense"``
+            or a list of ``n_colors`` writable length-``n_dofs`` arrays for
+            ``"list"``, of ``seed_dtype``. Strided views (e.g. ``buf[:, :k]``
+            of a larger buffer) are accepted. ``seeds`` is then ``out``
+            itself, so a solver loop rebuilding the seeds allocates nothing.
+        from_dlpack: Consumer of the DLPack protocol, e.g.
+            ``jax.numpy.from_dlpack``, ``cupy.from_dlpack`` or
+            ``torch.from_dlpack``, applied to the seeds so they arrive as
+            that library's arrays. Needs a single-buffer ``seed_format``:
+            ``"dense"`` is moved as one array (one host allocation and at
+            most one device transfer instead of ``n_colors``), ``"csr"`` as
+            its three arrays. CPU consumers share the buffer zero-copy.
+        dlpack_colors: Pass ``colors`` through ``from_dlpack`` as well.
+        backend: Array library of ``seeds``: ``"numpy"`` or ``"torch"``, the
+            latter a single CPU ``torch.Tensor`` of ``seed_dtype`` sharing
+            the buffer of ``seed_format="dense"`` (required), e.g. for a
+            model consuming the seeds in PyTorch. PyTorch is only imported
+            then.
+        pin_memory: Return the torch seeds in page-locked memory, for
+            asynchronous transfers to a GPU; costs one copy.
+        return_seeds: Build and return ``seeds``; with ``False`` only
+            ``colors`` is returned, e.g. when seeds are formed on an
+            accelerator from the colors.
+        return_stats: Also return a ``ColoringStats`` with the number of
+            colors, the min/max/mean color class size and the max degree and
+            nnz of the distance-2 graph of the DOFs, also when blocks, nodes,
+            ties or supervariables were colored; these take one extra pass
+            over the colored graph.
+        return_timings: Also return a ``{stage: seconds}`` dict with the
+            wall-clock time of every stage that ran, in order: ``"adjacency"``
+            (parsing, ``symmetrize`` and transposing the pattern),
+            ``"distance2"`` (degrees or the explicit distance-2 graph, when
+            the strategy needs them), ``"ordering"`` (fixed orderings only;
+            dynamic ones such as DSATUR count as coloring), ``"coloring"``
+            (including ``refine``), ``"stats"`` and ``"seeds"``.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        progress: Optional callable ``progress(done, total, phase)``, called
+            at the start and end of every phase and after each
+            ``progress_interval`` processed vertices (blocks for BSR input).
+            The phases are ``"degrees"``, ``"distance2"`` (building the
+            explicit graph) and ``"coloring"``; strategies that color the
+            explicit graph only report the start and end of ``"coloring"``.
+            Calls may come from worker threads, so ``done`` need not increase
+            monotonically. An exception raised by ``progress`` aborts the
+            coloring and propagates.
+        progress_interval: Processed vertices between two ``progress`` calls.
+        verbose: Log the strategy, the start and end of every phase, the
+            size of the distance-2 graph (once its degrees are known) and the
+            number of colors at ``INFO`` level to the ``"tatva_coloring"``
+            logger of Python's ``logging`` module.
+        validate: Input checks: ``"strict"`` checks every index against the
+            shape and the structure of ``row_ptr``, and sorts rows and merges
+            duplicate entries where needed; ``"fast"`` only checks array
+            lengths and the ends of ``row_ptr``; ``"off"`` trusts the input
+            to be canonical CSR/CSC. Malformed input under ``"fast"`` or
+            ``"off"`` may raise an internal error or give invalid colors.
+            COO input is always checked. Structural symmetry is not checked
+            here: square patterns are colored through both the pattern and
+            its transpose, so one-sided entries are safe. The star, acyclic,
+            Hessian recovery and distributed functions assume symmetry and,
+            under ``"strict"``, reject an entry without its mirror unless
+            ``symmetrize=True`` declares half storage.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (``colors_dtype``).
+        seeds: ``List[np.ndarray]`` of one-hot (or signed, with
+            ``rademacher_seed``) vectors, one per color, an
+            ``np.ndarray`` of shape ``(n_dofs, n_colors)`` for
+            ``seed_format="dense"`` or ``(data, indices, indptr)`` with
+            int64 indices for ``seed_format="csr"``, with values of
+            ``seed_dtype``; or ``List[np.ndarray]`` of class indices (int64)
+            for ``seed_format="groups"``; converted by ``from_dlpack`` or
+            ``backend`` if given.
+        stats: ``ColoringStats``, only with ``return_stats``.
+        timings: ``dict`` of stage times in seconds, only with ``return_timings``.
+    """
+    if _distance2_color_and_seeds_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    _check_seed_output(seed_format, from_dlpack, backend, pin_memory)
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    priority_arr = None if priority is None else np.asarray(priority, dtype=np.float64)
+    precolors_arr = None if precolors is None else np.asarray(precolors, dtype=np.int64)
+    allowed_arr = None if allowed_colors is None else _allowed_masks(allowed_colors)
+    tie_ptr, tie_dofs = (None, None) if ties is None else _element_arrays(ties, None)
+    colors, seeds, stats, timings = _distance2_color_and_seeds_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        restarts,
+        max_colors,
+        require_max_colors,
+        priority_arr,
+        precolors_arr,
+        allowed_arr,
+        format,
+        symmetrize,
+        block_size,
+        dofs_per_node,
+        tie_ptr,
+        tie_dofs,
+        supervariables,
+        n_cols,
+        low_memory,
+        seed_format,
+        np.dtype(seed_dtype).name,
+        rademacher_seed,
+        out,
+        return_seeds,
+        return_stats,
+        return_timings,
+        np.dtype(colors_dtype).name,
+        progress,
+        progress_interval,
+        verbose,
+        validate,
+    )
+    if dlpack_colors and from_dlpack is not None:
+        colors = from_dlpack(colors)
+    result = [colors]
+    if return_seeds:
+        result.append(
+            _output_seeds(seeds, seed_format, from_dlpack, backend, pin_memory)
+        )
+    if return_stats:
+        result.append(stats)
+    if return_timings:
+        result.append(timings)
+    return tuple(result) if len(result) > 1 else colors
+
+
+def distance1_color(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    priority: Optional[NDArray] = None,
+    format: str = "csr",
+    block_size: int = 1,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> NDArray:
+    """Color the adjacency graph of a CSR sparse matrix (distance-1 coloring).
+
+    Only DOFs coupled by a nonzero receive different colors, which is what
+    conflict-free parallel assembly needs. No distance-2 graph is built. The
+    pattern is symmetrized, so a coupling stored in only one triangle counts.
+
+    Args:
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``,
+            with degrees and bandwidths taken in the graph colored here.
+        priority: Optional per-vertex priority (float64). Higher-priority
+            vertices are colored first by ``algorithm="greedy"`` (overriding
+            ``ordering``), and for every algorithm the colors are renumbered
+            so that classes holding higher priorities get lower color ids.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        block_size: Block size ``b`` of BSR input, where ``row_ptr`` and
+            ``col_idx`` index ``b x b`` blocks and ``n_dofs`` counts scalar
+            DOFs. The block graph is colored and DOF ``i * b + r`` of block
+            ``i`` gets color ``block_color[i] * b + r``. Priorities act per
+            block through their maximum.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (``colors_dtype``).
+    """
+    if _distance1_color_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    priority_arr = None if priority is None else np.asarray(priority, dtype=np.float64)
+    return _distance1_color_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        priority_arr,
+        format,
+        block_size,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+
+
+def distance_k_color(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    k: int = 2,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    priority: Optional[NDArray] = None,
+    format: str = "csr",
+    block_size: int = 1,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> NDArray:
+    """Color a CSR sparse matrix so that DOFs within distance ``k`` differ.
+
+    Two DOFs conflict when a path of at most ``k`` nonzeros joins them in the
+    symmetrized pattern, found by breadth-first search to depth ``k``. ``k=1``
+    matches :func:`distance1_color`; larger ``k`` serves probing schemes with
+    overlapping stencils.
+
+    Args:
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        k: Conflict distance, at least 1.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``,
+            with degrees and bandwidths taken in the graph colored here.
+        priority: Optional per-vertex priority (float64). Higher-priority
+            vertices are colored first by ``algorithm="greedy"`` (overriding
+            ``ordering``), and for every algorithm the colors are renumbered
+            so that classes holding higher priorities get lower color ids.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        block_size: Block size ``b`` of BSR input, where ``row_ptr`` and
+            ``col_idx`` index ``b x b`` blocks and ``n_dofs`` counts scalar
+            DOFs. The block graph is colored and DOF ``i * b + r`` of block
+            ``i`` gets color ``block_color[i] * b + r``. Priorities act per
+            block through their maximum.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (``colors_dtype``).
+    """
+    if _distance_k_color_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    priority_arr = None if priority is None else np.asarray(priority, dtype=np.float64)
+    return _distance_k_color_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        k,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        priority_arr,
+        format,
+        block_size,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+
+
+def star_color_and_seeds(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    seed_format: str = "list",
+    seed_dtype: Any = "bool",
+    rademacher_seed: Optional[int] = None,
+    return_seeds: bool = True,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> Union[Tuple[NDArray, _Seeds], NDArray]:
+    """Star-color the adjacency graph of a symmetric CSR sparse matrix.
+
+    A star coloring is a distance-1 coloring in which every path on four
+    vertices uses at least three colors. It usually needs far fewer colors than
+    distance-2 coloring while still allowing direct recovery of a symmetric
+    Hessian from the compressed products.
+
+    Args:
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        symmetrize: Mirror a half-stored pattern first. Without it, strict
+            validation rejects an entry whose mirror is not stored.
+        block_size: Block size ``b`` of BSR input, where ``row_ptr`` and
+            ``col_idx`` index ``b x b`` blocks and ``n_dofs`` counts scalar
+            DOFs. The block graph is colored and DOF ``i * b + r`` of block
+            ``i`` gets color ``block_color[i] * b + r``.
+        seed_format: Layout of ``seeds``: ``"list"`` (one 1D vector per
+            color), ``"dense"`` (one contiguous ``(n_dofs, n_colors)`` array
+            whose column ``c`` is the seed of color ``c``, built directly
+            without an intermediate list), ``"csr"`` (the same matrix as
+            ``(data, indices, indptr)`` with one entry per row, e.g. for
+            ``scipy.sparse.csr_array((data, indices, indptr))``) or
+            ``"groups"`` (the indices of each color class as int64 arrays).
+        seed_dtype: Value type of the seeds: ``"bool"``, ``"float32"``,
+            ``"float64"`` or ``"complex128"`` (or the matching NumPy dtype),
+            e.g. float32 to match a single-precision device.
+        rademacher_seed: Draw every nonzero seed entry as +1 or -1
+            (Rademacher) from this RNG seed instead of 1, as needed for
+            stochastic diagonal or trace estimation with coloring-based
+            deflation. Requires a float or complex ``seed_dtype`` and a
+            ``seed_format`` other than ``"groups"``.
+        return_seeds: Build and return ``seeds``; with ``False`` only
+            ``colors`` is returned, e.g. when seeds are formed on an
+            accelerator from the colors.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (``colors_dtype``).
+        seeds: ``List[np.ndarray]`` of one-hot (or signed, with
+            ``rademacher_seed``) vectors, one per color, an
+            ``np.ndarray`` of shape ``(n_dofs, n_colors)`` for
+            ``seed_format="dense"`` or ``(data, indices, indptr)`` with
+            int64 indices for ``seed_format="csr"``, with values of
+            ``seed_dtype``; or ``List[np.ndarray]`` of class indices (int64)
+            for ``seed_format="groups"``.
+    """
+    if _star_color_and_seeds_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    colors, seeds = _star_color_and_seeds_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        format,
+        symmetrize,
+        block_size,
+        seed_format,
+        np.dtype(seed_dtype).name,
+        rademacher_seed,
+        return_seeds,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+    return (colors, seeds) if return_seeds else colors
+
+
+def restricted_star_color_and_seeds(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    seed_format: str = "list",
+    seed_dtype: Any = "bool",
+    rademacher_seed: Optional[int] = None,
+    return_seeds: bool = True,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> Union[Tuple[NDArray, _Seeds], NDArray]:
+    """Restricted-star-color the adjacency graph of a symmetric CSR sparse matrix.
+
+    A restricted star coloring is a distance-1 coloring in which every path
+    ``u - v - w`` with ``colors[u] == colors[w]`` has ``colors[v] < colors[u]``.
+    It needs more colors than a star coloring, but recovery is a single rule:
+    for ``colors[i] > colors[j]``, ``H[i, j] = (H @ seeds[colors[j]])[i]``; the
+    diagonal is ``H[i, i] = (H @ seeds[colors[i]])[i]``.
+
+    Args:
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        symmetrize: Mirror a half-stored pattern first. Without it, strict
+            validation rejects an entry whose mirror is not stored.
+        block_size: Block size ``b`` of BSR input, where ``row_ptr`` and
+            ``col_idx`` index ``b x b`` blocks and ``n_dofs`` counts scalar
+            DOFs. The block graph is colored and DOF ``i * b + r`` of block
+            ``i`` gets color ``block_color[i] * b + r``.
+        seed_format: Layout of ``seeds``: ``"list"`` (one 1D vector per
+            color), ``"dense"`` (one contiguous ``(n_dofs, n_colors)`` array
+            whose column ``c`` is the seed of color ``c``, built directly
+            without an intermediate list), ``"csr"`` (the same matrix as
+            ``(data, indices, indptr)`` with one entry per row, e.g. for
+            ``scipy.sparse.csr_array((data, indices, indptr))``) or
+            ``"groups"`` (the indices of each color class as int64 arrays).
+        seed_dtype: Value type of the seeds: ``"bool"``, ``"float32"``,
+            ``"float64"`` or ``"complex128"`` (or the matching NumPy dtype),
+            e.g. float32 to match a single-precision device.
+        rademacher_seed: Draw every nonzero seed entry as +1 or -1
+            (Rademacher) from this RNG seed instead of 1, as needed for
+            stochastic diagonal or trace estimation with coloring-based
+            deflation. Requires a float or complex ``seed_dtype`` and a
+            ``seed_format`` other than ``"groups"``.
+        return_seeds: Build and return ``seeds``; with ``False`` only
+            ``colors`` is returned, e.g. when seeds are formed on an
+            accelerator from the colors.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (``colors_dtype``).
+        seeds: ``List[np.ndarray]`` of one-hot (or signed, with
+            ``rademacher_seed``) vectors, one per color, an
+            ``np.ndarray`` of shape ``(n_dofs, n_colors)`` for
+            ``seed_format="dense"`` or ``(data, indices, indptr)`` with
+            int64 indices for ``seed_format="csr"``, with values of
+            ``seed_dtype``; or ``List[np.ndarray]`` of class indices (int64)
+            for ``seed_format="groups"``.
+    """
+    if _restricted_star_color_and_seeds_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    colors, seeds = _restricted_star_color_and_seeds_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        format,
+        symmetrize,
+        block_size,
+        seed_format,
+        np.dtype(seed_dtype).name,
+        rademacher_seed,
+        return_seeds,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+    return (colors, seeds) if return_seeds else colors
+
+
+def acyclic_color_and_seeds(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    block_size: int = 1,
+    seed_format: str = "list",
+    seed_dtype: Any = "bool",
+    rademacher_seed: Optional[int] = None,
+    return_seeds: bool = True,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> Union[Tuple[NDArray, _Seeds], NDArray]:
+    """Acyclic-color the adjacency graph of a symmetric CSR sparse matrix.
+
+    An acyclic coloring is a distance-1 coloring in which every cycle uses at
+    least three colors, i.e. every two-colored subgraph is a forest. It yields
+    the fewest seed vectors for symmetric Hessians, which are then recovered by
+    substitution along those two-colored trees rather than directly.
+
+    Args:
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        symmetrize: Mirror a half-stored pattern first. Without it, strict
+            validation rejects an entry whose mirror is not stored.
+        block_size: Block size ``b`` of BSR input, where ``row_ptr`` and
+            ``col_idx`` index ``b x b`` blocks and ``n_dofs`` counts scalar
+            DOFs. The block graph is colored and DOF ``i * b + r`` of block
+            ``i`` gets color ``block_color[i] * b + r``.
+        seed_format: Layout of ``seeds``: ``"list"`` (one 1D vector per
+            color), ``"dense"`` (one contiguous ``(n_dofs, n_colors)`` array
+            whose column ``c`` is the seed of color ``c``, built directly
+            without an intermediate list), ``"csr"`` (the same matrix as
+            ``(data, indices, indptr)`` with one entry per row, e.g. for
+            ``scipy.sparse.csr_array((data, indices, indptr))``) or
+            ``"groups"`` (the indices of each color class as int64 arrays).
+        seed_dtype: Value type of the seeds: ``"bool"``, ``"float32"``,
+            ``"float64"`` or ``"complex128"`` (or the matching NumPy dtype),
+            e.g. float32 to match a single-precision device.
+        rademacher_seed: Draw every nonzero seed entry as +1 or -1
+            (Rademacher) from this RNG seed instead of 1, as needed for
+            stochastic diagonal or trace estimation with coloring-based
+            deflation. Requires a float or complex ``seed_dtype`` and a
+            ``seed_format`` other than ``"groups"``.
+        return_seeds: Build and return ``seeds``; with ``False`` only
+            ``colors`` is returned, e.g. when seeds are formed on an
+            accelerator from the colors.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (``colors_dtype``).
+        seeds: ``List[np.ndarray]`` of one-hot (or signed, with
+            ``rademacher_seed``) vectors, one per color, an
+            ``np.ndarray`` of shape ``(n_dofs, n_colors)`` for
+            ``seed_format="dense"`` or ``(data, indices, indptr)`` with
+            int64 indices for ``seed_format="csr"``, with values of
+            ``seed_dtype``; or ``List[np.ndarray]`` of class indices (int64)
+            for ``seed_format="groups"``.
+    """
+    if _acyclic_color_and_seeds_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    colors, seeds = _acyclic_color_and_seeds_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        format,
+        symmetrize,
+        block_size,
+        seed_format,
+        np.dtype(seed_dtype).name,
+        rademacher_seed,
+        return_seeds,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+    return (colors, seeds) if return_seeds else colors
+
+
+def partial_distance2_color_and_seeds(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_rows: Optional[int] = None,
+    n_cols: Optional[int] = None,
+    format: str = "csr",
+    seed_format: str = "list",
+    seed_dtype: Any = "bool",
+    rademacher_seed: Optional[int] = None,
+    return_seeds: bool = True,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> Union[Tuple[NDArray, _Seeds], NDArray]:
+    """Color the columns of a general ``n_rows x n_cols`` CSR sparsity pattern.
+
+    Columns that share a nonzero row receive different colors (partial
+    distance-2 coloring of the bipartite row/column graph), so each seed groups
+    structurally orthogonal columns and ``J @ seed`` directly recovers them.
+
+    Args:
+        row_ptr: Row pointer of length ``n_rows + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_rows``, ``n_cols`` and ``format``.
+        col_idx: Column indices in ``[0, n_cols)``; omitted for a
+            sparse or mask input.
+        n_rows: Number of rows (e.g. residual entries); taken from a
+            sparse or mask input.
+        n_cols: Number of columns (e.g. degrees of freedom); taken from a
+            sparse or mask input.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        seed_format: Layout of ``seeds``: ``"list"`` (one 1D vector per
+            color), ``"dense"`` (one contiguous ``(n_cols, n_colors)`` array
+            whose column ``c`` is the seed of color ``c``, built directly
+            without an intermediate list), ``"csr"`` (the same matrix as
+            ``(data, indices, indptr)`` with one entry per row, e.g. for
+            ``scipy.sparse.csr_array((data, indices, indptr))``) or
+            ``"groups"`` (the indices of each color class as int64 arrays).
+        seed_dtype: Value type of the seeds: ``"bool"``, ``"float32"``,
+            ``"float64"`` or ``"complex128"`` (or the matching NumPy dtype),
+            e.g. float32 to match a single-precision device.
+        rademacher_seed: Draw every nonzero seed entry as +1 or -1
+            (Rademacher) from this RNG seed instead of 1, as needed for
+            stochastic diagonal or trace estimation with coloring-based
+            deflation. Requires a float or complex ``seed_dtype`` and a
+            ``seed_format`` other than ``"groups"``.
+        return_seeds: Build and return ``seeds``; with ``False`` only
+            ``colors`` is returned, e.g. when seeds are formed on an
+            accelerator from the colors.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_cols,)`` with color ids (``colors_dtype``).
+        seeds: ``List[np.ndarray]`` of one-hot (or signed, with
+            ``rademacher_seed``) vectors of length ``n_cols``, one per color,
+            an ``np.ndarray`` of shape ``(n_cols, n_colors)`` for
+            ``seed_format="dense"`` or ``(data, indices, indptr)`` with
+            int64 indices for ``seed_format="csr"``, with values of
+            ``seed_dtype``; or ``List[np.ndarray]`` of class indices (int64)
+            for ``seed_format="groups"``.
+    """
+    if _partial_distance2_color_and_seeds_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_rows, n_cols, format = _pattern_arrays(
+        row_ptr, col_idx, n_rows, n_cols, format
+    )
+    colors, seeds = _partial_distance2_color_and_seeds_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_rows,
+        n_cols,
+        format,
+        seed_format,
+        np.dtype(seed_dtype).name,
+        rademacher_seed,
+        return_seeds,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+    return (colors, seeds) if return_seeds else colors
+
+
+def partitioned_distance2_colors(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    partition: Optional[NDArray] = None,
+    color_budget: Optional[int] = None,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    priority: Optional[NDArray] = None,
+    format: str = "csr",
+    symmetrize: bool = False,
+    n_cols: Optional[int] = None,
+    colors_dtype: Any = "int32",
+    validate: str = "strict",
+) -> Tuple[NDArray, NDArray]:
+    """Distance-2 colors that never straddle two subdomains.
+
+    Each subdomain of ``partition`` is colored on its own within a contiguous
+    range of colors no other subdomain uses, so the probes of one color can
+    be evaluated in a batch per subdomain. DOFs of different subdomains may
+    still be coupled; the disjoint ranges keep them apart, at the cost of
+    more colors than a global coloring.
+
+    Args:
+        row_ptr, col_idx, n_dofs: The pattern, as for ``distance2_colors``.
+        partition: Subdomain of every DOF (of every column for a rectangular
+            pattern), numbered from 0 and below the number of DOFs, since ids
+            index the color ranges; renumber sparse ids (e.g. with
+            ``np.unique(partition, return_inverse=True)``) first.
+        color_budget: Colors reserved per subdomain: subdomain ``p`` then owns
+            colors ``p * color_budget`` to ``(p + 1) * color_budget - 1``,
+            possibly leaving some unused, and ``ValueError`` is raised if it
+            needs more. Without it the ranges are packed.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic, priority: The strategy of every subdomain, as for
+            ``distance2_colors``; ``priority`` orders within a subdomain.
+        format, symmetrize, n_cols, colors_dtype, validate: As for
+            ``distance2_colors``.
+
+    Returns:
+        colors: ``np.ndarray`` of color ids (``colors_dtype``).
+        offsets: ``np.ndarray`` (int64) of length ``n_parts + 1``; subdomain
+            ``p`` uses colors ``offsets[p]`` to ``offsets[p + 1] - 1``.
+    """
+    if _partitioned_distance2_colors_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+    if partition is None:
+        raise TypeError("partition is required")
+
+    row_ptr_arr, col_idx_arr, n_dofs, n_cols, format = _distance2_pattern_arrays(
+        row_ptr, col_idx, n_dofs, n_cols, format
+    )
+    priority_arr = None if priority is None else np.asarray(priority, dtype=np.float64)
+    return _partitioned_distance2_colors_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        _as_index_array(partition),
+        color_budget,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        priority_arr,
+        format,
+        symmetrize,
+        n_cols,
+        np.dtype(colors_dtype).name,
+        validate,
+    )
+
+
+def cpr_column_groups(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_rows: Optional[int] = None,
+    n_cols: Optional[int] = None,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    priority: Optional[NDArray] = None,
+    format: str = "csr",
+    validate: str = "strict",
+) -> List[NDArray]:
+    """Group the columns of a general CSR pattern with Curtis-Powell-Reid coloring.
+
+    Builds the column intersection graph (columns sharing a nonzero row are
+    adjacent) directly from the CSR arrays and colors it, so every group holds
+    structurally orthogonal columns. Summing the columns of a group gives one
+    seed for one-sided Jacobian compression, without forming ``A.T @ A``.
+
+    Args:
+        row_ptr: Row pointer of length ``n_rows + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_rows``, ``n_cols`` and ``format``.
+        col_idx: Column indices in ``[0, n_cols)``; omitted for a
+            sparse or mask input.
+        n_rows: Number of rows; taken from a
+            sparse or mask input.
+        n_cols: Number of columns; taken from a
+            sparse or mask input.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``,
+            with degrees and bandwidths taken in the graph colored here.
+        priority: Optional per-column priority (float64). Higher-priority
+            columns are grouped first by ``algorithm="greedy"`` (overriding
+            ``ordering``), and for every algorithm the groups are ordered so
+            that groups holding higher priorities come first.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        groups: ``List[np.ndarray]`` of column indices (int64), one per color.
+    """
+    if _cpr_column_groups_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_rows, n_cols, format = _pattern_arrays(
+        row_ptr, col_idx, n_rows, n_cols, format
+    )
+    priority_arr = None if priority is None else np.asarray(priority, dtype=np.float64)
+    return _cpr_column_groups_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_rows,
+        n_cols,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        priority_arr,
+        format,
+        validate,
+    )
+
+
+def bicolor(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_rows: Optional[int] = None,
+    n_cols: Optional[int] = None,
+    dense_row_threshold: Optional[int] = None,
+    format: str = "csr",
+    validate: str = "strict",
+) -> Tuple[NDArray, NDArray]:
+    """Bicolor the rows and columns of a general CSR sparsity pattern.
+
+    Rows with at least ``dense_row_threshold`` nonzeros ("dense rows") are
+    recovered from reverse products ``W.T @ J`` and receive row colors; every
+    other entry is recovered from forward products ``J @ V`` via column
+    colors. With a few dense rows, this needs far fewer products in total than
+    column-only compression. Without a threshold, the largest row lengths are
+    tried as thresholds and the split with the fewest products is kept.
+
+    Recovery, with ``V[:, c] = col_colors == c`` and ``W[:, r] = row_colors == r``:
+    ``J[i, j] = (W.T @ J)[row_colors[i], j]`` if ``row_colors[i] >= 0``, else
+    ``J[i, j] = (J @ V)[i, col_colors[j]]``.
+
+    Args:
+        row_ptr: Row pointer of length ``n_rows + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_rows``, ``n_cols`` and ``format``.
+        col_idx: Column indices in ``[0, n_cols)``; omitted for a
+            sparse or mask input.
+        n_rows: Number of rows; taken from a
+            sparse or mask input.
+        n_cols: Number of columns; taken from a
+            sparse or mask input.
+        dense_row_threshold: Fixed minimal nonzero count of a dense row, or
+            ``None`` to choose it automatically.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``. For CSC, ``row_ptr`` holds the column pointers and
+            ``col_idx`` the row indices, so column-oriented assemblies need no
+            transpose. For COO, ``row_ptr`` and ``col_idx`` hold the row and
+            column of every stored entry; duplicate entries are merged.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        row_colors: ``np.ndarray`` of shape ``(n_rows,)`` (int32); ``-1`` for
+            rows recovered from column products.
+        col_colors: ``np.ndarray`` of shape ``(n_cols,)`` (int32); ``-1`` for
+            columns that only appear in dense rows.
+    """
+    if _bicolor_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_rows, n_cols, format = _pattern_arrays(
+        row_ptr, col_idx, n_rows, n_cols, format
+    )
+    return _bicolor_ext(
+        row_ptr_arr,
+        col_idx_arr,
+        n_rows,
+        n_cols,
+        dense_row_threshold,
+        format,
+        validate,
+    )
+
+
+def color_matrix_market(
+    path: Union[str, os.PathLike],
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    colors_dtype: Any = "int32",
+) -> NDArray:
+    """Read a Matrix Market coordinate file and color its sparsity pattern.
+
+    The file is parsed in Rust and its values are ignored, so no scipy matrix is
+    ever built. Symmetric, skew-symmetric and Hermitian files are mirrored.
+    Square matrices get a distance-2 coloring as in :func:`distance2_colors`;
+    rectangular ones a column coloring as in :func:`cpr_column_groups`.
+
+    Args:
+        path: Path of the ``.mtx`` file.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_cols,)`` with color ids (``colors_dtype``).
+    """
+    if _color_matrix_market_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    return _color_matrix_market_ext(
+        os.fspath(path),
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        np.dtype(colors_dtype).name,
+    )
+
+
+def pattern_from_elements(
+    connectivity: Any,
+    n_dofs: Optional[int] = None,
+    elem_ptr: Optional[NDArray] = None,
+) -> Tuple[NDArray, NDArray]:
+    """Assembled CSR sparsity pattern of a mesh, without values.
+
+    DOFs are coupled when they share an element, exactly as in the matrix
+    assembled from ``connectivity``. The pattern is built in parallel in
+    Rust, so matrix-free codes can hand it to the coloring functions or to
+    ``scipy.sparse`` without assembling anything.
+
+    Args:
+        connectivity: Element-to-DOF connectivity as an
+            ``(n_elements, dofs_per_element)`` array, a ragged sequence of
+            per-element DOF lists, or the flat DOF list of all elements when
+            ``elem_ptr`` is given.
+        n_dofs: Number of DOFs; defaults to the largest DOF index + 1.
+        elem_ptr: Optional element pointer of length ``n_elements + 1`` into a
+            flat ``connectivity`` array (ragged CSR connectivity).
+
+    Returns:
+        indptr: ``np.ndarray`` (int64) of length ``n_dofs + 1``.
+        indices: ``np.ndarray`` (int64) with the sorted columns of each row,
+            the diagonal included for every DOF in some element.
+    """
+    if _pattern_from_elements_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    ptr, nodes = _element_arrays(connectivity, elem_ptr)
+    return _pattern_from_elements_ext(ptr, nodes, n_dofs)
+
+
+def normal_pattern(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_rows: Optional[int] = None,
+    n_cols: Optional[int] = None,
+    product: str = "AtA",
+    format: str = "csr",
+    validate: str = "strict",
+) -> Tuple[NDArray, NDArray]:
+    """Symbolic sparsity of ``A.T @ A`` or ``A @ A.T`` from the pattern of ``A``.
+
+    Gives the Hessian or normal-equation pattern of a least-squares problem
+    from its Jacobian pattern alone, ready for ``star_color_and_seeds`` or
+    ``hessian_coloring``. No values are formed, so memory stays at the size
+    of the result, unlike a symbolic product in ``scipy.sparse``.
+
+    Args:
+        row_ptr: Row pointer of ``A``, or a ``scipy.sparse`` matrix/array or
+            dense mask, as for ``distance2_colors``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_rows: Number of rows of ``A``; taken from the shape of a sparse or
+            mask input.
+        n_cols: Number of columns of ``A``, likewise.
+        product: ``"AtA"`` (``n_cols x n_cols``, columns sharing a row) or
+            ``"AAt"`` (``n_rows x n_rows``, rows sharing a column).
+        format: Storage layout of the index arrays, as for
+            ``distance2_colors``.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        indptr: ``np.ndarray`` (int64) of the product's row pointer.
+        indices: ``np.ndarray`` (int64) with the sorted columns of each row,
+            the diagonal included for every nonempty column (row) of ``A``.
+    """
+    if _normal_pattern_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    row_ptr_arr, col_idx_arr, n_rows, n_cols, format = _pattern_arrays(
+        row_ptr, col_idx, n_rows, n_cols, format
+    )
+    return _normal_pattern_ext(
+        row_ptr_arr, col_idx_arr, n_rows, n_cols, product, format, validate
+    )
+
+
+def element_distance2_colors(
+    elements: Any,
+    n_nodes: Optional[int] = None,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    elem_ptr: Optional[NDArray] = None,
+    colors_dtype: Any = "int32",
+) -> NDArray:
+    """Distance-2 color the DOFs of a mesh from its element-to-node connectivity.
+
+    Two DOFs are coupled when they share an element, exactly as in the assembled
+    matrix, but the global sparsity pattern is never formed: the distance-2
+    graph is built directly from the connectivity. Suited to matrix-free solvers.
+
+    Args:
+        elements: Connectivity as an ``(n_elements, nodes_per_element)`` array,
+            a ragged sequence of per-element node lists, or the flat node list
+            of all elements when ``elem_ptr`` is given.
+        n_nodes: Number of nodes (DOFs); defaults to the largest node index + 1.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``.
+        elem_ptr: Optional element pointer of length ``n_elements + 1`` into a
+            flat ``elements`` array (ragged CSR connectivity).
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_nodes,)``
+            with color ids (``colors_dtype``).
+    """
+    if _element_distance2_colors_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    elem_ptr_arr, elem_nodes_arr = _element_arrays(elements, elem_ptr)
+    return _element_distance2_colors_ext(
+        elem_ptr_arr,
+        elem_nodes_arr,
+        n_nodes,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        np.dtype(colors_dtype).name,
+    )
+
+
+def cell_colors(
+    cells: Any,
+    n_vertices: Optional[int] = None,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    cell_ptr: Optional[NDArray] = None,
+    colors_dtype: Any = "int32",
+) -> NDArray:
+    """Color mesh cells so that no two cells sharing a vertex get the same color.
+
+    The cells of one color never write to the same vertex, so assembly kernels
+    can process each color class in parallel without atomics.
+
+    Args:
+        cells: Cell-to-vertex connectivity as an ``(n_cells, vertices_per_cell)``
+            array, a ragged sequence of per-cell vertex lists, or the flat
+            vertex list of all cells when ``cell_ptr`` is given.
+        n_vertices: Number of vertices; defaults to the largest vertex index + 1.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``,
+            with degrees and bandwidths taken in the graph colored here.
+        cell_ptr: Optional cell pointer of length ``n_cells + 1`` into a flat
+            ``cells`` array (ragged CSR connectivity).
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_cells,)``
+            with color ids (``colors_dtype``).
+    """
+    if _cell_colors_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    cell_ptr_arr, cell_vertices_arr = _element_arrays(cells, cell_ptr)
+    return _cell_colors_ext(
+        cell_ptr_arr,
+        cell_vertices_arr,
+        n_vertices,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        np.dtype(colors_dtype).name,
+    )
+
+
+def color_edge_list(
+    edges: NDArray,
+    n_vertices: Optional[int] = None,
+    distance: int = 1,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+    colors_dtype: Any = "int32",
+) -> NDArray:
+    """Color a graph given as a plain list of vertex pairs.
+
+    Suited to interaction pairs such as contact candidates or constraint
+    couplings, which never form an assembled matrix. Edges are undirected;
+    duplicates and self loops are ignored.
+
+    Args:
+        edges: ``(n_edges, 2)`` array of vertex pairs.
+        n_vertices: Number of vertices; defaults to the largest vertex index + 1.
+        distance: Vertices joined by a path of at most ``distance`` edges
+            receive different colors (``1``: ordinary graph coloring, ``2``:
+            distance-2 coloring).
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``,
+            with degrees and bandwidths taken in the graph colored here.
+        colors_dtype: Integer dtype of ``colors``: ``"int32"``, ``"int64"`` or
+            ``"uint16"`` (or the matching NumPy dtype), built directly in
+            that type so no ``astype`` pass is needed.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_vertices,)``
+            with color ids (``colors_dtype``).
+    """
+    if _color_edge_list_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    edges_arr = _as_index_array(edges)
+    if edges_arr.ndim != 2 or edges_arr.shape[1] != 2:
+        raise ValueError(f"edges must have shape (n_edges, 2), got {edges_arr.shape}")
+    return _color_edge_list_ext(
+        edges_arr.reshape(-1),
+        n_vertices,
+        distance,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+        np.dtype(colors_dtype).name,
+    )
+
+
+def color_permutation(colors: NDArray) -> Tuple[NDArray, NDArray]:
+    """Permutation that sorts DOFs by color, with the original index as tiebreak.
+
+    Renumbering the unknowns with ``perm`` places every color class in one
+    contiguous block, e.g. for multicolor Gauss-Seidel sweeps.
+
+    Args:
+        colors: Color ids as returned by the coloring functions.
+
+    Returns:
+        perm: ``np.ndarray`` (int64) with the DOF placed at each new position.
+        inverse: ``np.ndarray`` (int64) with the new position of each DOF, so
+            ``inverse[perm] == np.arange(len(colors))``.
+    """
+    if _color_permutation_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    return _color_permutation_ext(_as_index_array(colors))
+
+
+def petsc_coloring(
+    colors: NDArray,
+    n_colors: Optional[int] = None,
+    offset: int = 0,
+    color_dtype: Any = "uint16",
+    index_dtype: Any = "int32",
+) -> Tuple[int, NDArray, List[NDArray]]:
+    """A coloring in the layout of PETSc's ``ISColoring``.
+
+    The outputs are the arguments of ``ISColoringCreate``, and through it of
+    ``MatFDColoringCreate``: the color count, the color of every local DOF
+    as ``ISColoringValue``, and the index set of every color as sorted
+    ``PetscInt`` indices in the global numbering. With petsc4py, each index
+    set becomes ``PETSc.IS().createGeneral(index_set)``.
+
+    Args:
+        colors: Color of every local DOF, e.g. from ``distance2_colors`` or
+            ``distributed_distance2_colors``.
+        n_colors: Global number of colors; defaults to the largest local
+            color + 1. Pass the maximum over all ranks in parallel, as every
+            rank must report the same count, colors without local DOFs
+            getting an empty index set.
+        offset: Global index of the first local DOF, i.e. the start of
+            ``MatGetOwnershipRange``.
+        color_dtype: Dtype of the colors: ``"uint16"``, PETSc's default
+            ``unsigned short``, or ``"int32"`` for a PETSc configured with
+            ``--with-is-color-value-type=int``.
+        index_dtype: Dtype of ``PetscInt``: ``"int32"``, or ``"int64"`` for a
+            PETSc configured with ``--with-64-bit-indices``.
+
+    Returns:
+        n_colors: Number of colors.
+        colors: ``np.ndarray`` of the local colors (``color_dtype``).
+        index_sets: ``list`` of ``n_colors`` arrays (``index_dtype``)
+            holding the global indices of the DOFs of each color.
+    """
+    if _petsc_coloring_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    return _petsc_coloring_ext(
+        _as_index_array(colors),
+        n_colors,
+        offset,
+        np.dtype(color_dtype).name,
+        np.dtype(index_dtype).name,
+    )
+
+
+def recovery_map(
+    colors: NDArray,
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    format: str = "csr",
+) -> NDArray:
+    """Where each stored entry of a column-colored pattern lands in ``J @ S``.
+
+    With the column coloring ``colors`` and the seed matrix ``S`` (one-hot per
+    color), entry ``J[i, j]`` is read from ``(J @ S)[i, colors[j]]``. This
+    returns that position for every stored entry as a flat index into the
+    row-major ``(n_rows, n_colors)`` product, so the sparse ``data`` array is
+    ``np.ravel(compressed)[recovery_map(...)]``.
+
+    Args:
+        colors: Column colors, e.g. from ``partial_distance2_color_and_seeds``.
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense 2D
+            mask whose index arrays and format are used instead of
+            ``col_idx`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``, with the same meaning as for the coloring functions.
+
+    Returns:
+        positions: ``np.ndarray`` (int64) with one flat index per stored entry,
+            in storage order (for a scipy matrix in another format, in the
+            order of its CSR conversion; for a mask, of ``np.nonzero``).
+    """
+    if _recovery_map_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    if col_idx is None:
+        row_ptr, col_idx, _, format = _sparse_pattern(row_ptr)
+    return _recovery_map_ext(
+        _as_index_array(colors),
+        _as_index_array(row_ptr),
+        _as_index_array(col_idx),
+        format,
+    )
+
+
+def decompress_jacobian(
+    compressed: NDArray,
+    colors: NDArray,
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    format: str = "csr",
+) -> NDArray:
+    """Scatter a column-compressed Jacobian product back into sparse ``data``.
+
+    ``compressed`` is ``J @ S`` for the seed matrix ``S`` of the column
+    coloring ``colors``; entry ``J[i, j]`` is read from
+    ``compressed[i, colors[j]]`` (see ``recovery_map``).
+
+    Args:
+        compressed: ``(n_rows, n_colors)`` product (float32 or float64; other
+            dtypes are converted to float64).
+        colors: Column colors, e.g. from ``partial_distance2_color_and_seeds``.
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense 2D
+            mask whose index arrays and format are used instead of
+            ``col_idx`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``, with the same meaning as for the coloring functions.
+
+    Returns:
+        data: ``np.ndarray`` with the value of every stored entry in storage
+            order, in the dtype of ``compressed``; e.g.
+            ``scipy.sparse.csr_array((data, col_idx, row_ptr))``.
+    """
+    if _decompress_jacobian_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    compressed_arr = _compressed_array(compressed)
+    if col_idx is None:
+        row_ptr, col_idx, _, format = _sparse_pattern(row_ptr)
+    return _decompress_jacobian_ext(
+        compressed_arr,
+        _as_index_array(colors),
+        _as_index_array(row_ptr),
+        _as_index_array(col_idx),
+        format,
+    )
+
+
+def recover_hessian(
+    compressed: NDArray,
+    colors: NDArray,
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    method: str = "direct",
+    format: str = "csr",
+    symmetrize: bool = False,
+    validate: str = "strict",
+) -> NDArray:
+    """Recover a symmetric Hessian from its compressed product ``H @ S``.
+
+    Diagonal entries are ``compressed[i, colors[i]]``. With ``"direct"``
+    recovery, every off-diagonal entry is read from a single product entry,
+    ``H[i, j] = compressed[i, colors[j]]`` for whichever end has no other
+    neighbor in the color of the other; a star coloring guarantees one does.
+    With ``"substitution"``, the entries of each two-colored tree of an
+    acyclic coloring are solved from its leaves inward, subtracting already
+    recovered entries from the remaining sums.
+
+    Args:
+        compressed: ``(n_dofs, n_colors)`` product (float32 or float64; other
+            dtypes are converted to float64). Other shapes, such as the
+            transposed ``(n_colors, n_dofs)``, raise ``ValueError``.
+        colors: Star coloring (``star_color_and_seeds`` or
+            ``restricted_star_color_and_seeds``) for ``"direct"``, or acyclic
+            coloring (``acyclic_color_and_seeds``) for ``"substitution"``.
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense 2D
+            mask whose index arrays, shape and format are used instead of
+            ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols; defaults to ``len(colors)``.
+        method: ``"direct"`` or ``"substitution"``.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``, with the same meaning as for the coloring functions.
+        symmetrize: Accept a half-stored pattern, from either triangle.
+            Without it, strict validation rejects an entry whose mirror is
+            not stored.
+        validate: Input checks, as for ``distance2_colors``.
+
+    Returns:
+        data: ``np.ndarray`` with the value of every stored entry in storage
+            order, in the dtype of ``compressed``.
+    """
+    if _recover_hessian_ext is None:
+        raise ImportError(
+            "tatva_color extension is not built. Run `maturin develop` in rust/coloring`."
+        )
+
+    compressed_arr = _compressed_array(compressed)
+    colors_arr = _as_index_array(colors)
+    if n_dofs is None and col_idx is not None:
+        n_dofs = len(colors_arr)
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    return _recover_hessian_ext(
+        compressed_arr,
+        colors_arr,
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        method,
+        format,
+        symmetrize,
+        validate,
+    )
+
+
+def networkx_coloring(
+    graph: Any,
+    distance: int = 1,
+    ordering: str = "natural",
+    seed: int = 0,
+    refine: Optional[str] = None,
+    algorithm: str = "greedy",
+    time_limit: float = 10.0,
+    tabu_iterations: int = 10000,
+    deterministic: bool = False,
+) -> Dict[Hashable, int]:
+    """Color a networkx graph and return a ``{node: color}`` dict.
+
+    Nodes are numbered in ``graph.nodes`` order and the edges are passed to
+    :func:`color_edge_list`, so arbitrary hashable node labels work and no CSR
+    conversion is needed. Directed graphs are treated as undirected. networkx
+    itself is not imported; any object with ``nodes`` and ``edges`` works.
+
+    Args:
+        graph: ``networkx.Graph`` (or ``DiGraph``/``MultiGraph``).
+        distance: Nodes joined by a path of at most ``distance`` edges receive
+            different colors.
+        ordering, seed, refine, algorithm, time_limit, tabu_iterations,
+            deterministic: Coloring strategy, as for ``distance2_colors``,
+            with degrees and bandwidths taken in the graph colored here.
+
+    Returns:
+        coloring: ``dict`` mapping every node to its color id, like
+            ``networkx.greedy_color``.
+    """
+    nodes = list(graph.nodes)
+    index = {node: i for i, node in enumerate(nodes)}
+    edges = np.fromiter(
+        (index[node] for edge in graph.edges for node in edge[:2]),
+        dtype=np.int64,
+    ).reshape(-1, 2)
+    colors = color_edge_list(
+        edges,
+        len(nodes),
+        distance,
+        ordering,
+        seed,
+        refine,
+        algorithm,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )
+    return dict(zip(nodes, colors.tolist()))
+
+
+def hessian_coloring(
+    row_ptr: Any,
+    col_idx: Optional[NDArray] = None,
+    n_dofs: Optional[int] = None,
+    mode: str = "direct",
+    format: str = "csr",
+    seed_format: str = "list",
+    seed_dtype: Any = "bool",
+    symmetrize: bool = False,
+) -> Tuple[NDArray, _Seeds, Callable[[NDArray], NDArray]]:
+    """Color a symmetric Hessian pattern together with its matching recovery.
+
+    ``mode`` picks both halves so that they always fit: ``"direct"`` star-colors
+    the pattern and reads every entry from a single product entry, while
+    ``"substitution"`` acyclic-colors it (fewer colors) and solves for the
+    entries along two-colored trees.
+
+    Args:
+        row_ptr: Row pointer of length ``n_dofs + 1``, or a ``scipy.sparse``
+            matrix/array or dense 2D boolean/integer mask (nonzeros are
+            entries) whose index arrays, shape and format are used instead
+            of ``col_idx``, ``n_dofs`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        n_dofs: Number of rows/cols (degrees of freedom); taken from the shape
+            of a sparse or mask input, which must agree if both are given.
+        mode: ``"direct"`` or ``"substitution"``.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``, with the same meaning as for the coloring functions.
+        seed_format: Layout of ``seeds``, as for ``star_color_and_seeds``.
+        seed_dtype: Value type of ``seeds``, as for ``star_color_and_seeds``.
+        symmetrize: Accept a half-stored pattern, as for
+            ``star_color_and_seeds``.
+
+    Returns:
+        colors: ``np.ndarray`` of shape ``(n_dofs,)`` with color ids (int32).
+        seeds: Seed matrix ``S`` in the requested layout.
+        recover: Callable mapping the ``(n_dofs, n_colors)`` product ``H @ S``
+            to the ``data`` of every stored entry in storage order (see
+            ``recover_hessian``).
+    """
+    colorings = {
+        "direct": star_color_and_seeds,
+        "substitution": acyclic_color_and_seeds,
+    }
+    if mode not in colorings:
+        raise ValueError(
+            f"unknown mode '{mode}', expected one of: direct, substitution"
+        )
+
+    row_ptr_arr, col_idx_arr, n_dofs, format = _square_pattern_arrays(
+        row_ptr, col_idx, n_dofs, format
+    )
+    colors, seeds = colorings[mode](
+        row_ptr_arr,
+        col_idx_arr,
+        n_dofs,
+        format,
+        symmetrize,
+        seed_format=seed_format,
+        seed_dtype=seed_dtype,
+    )
+
+    def recover(compressed: NDArray) -> NDArray:
+        return recover_hessian(
+            compressed,
+            colors,
+            row_ptr_arr,
+            col_idx_arr,
+            n_dofs,
+            mode,
+            format,
+            symmetrize,
+        )
+
+    return colors, seeds, recover
+
+
+def pattern_hash(
+    row_ptr: Any, col_idx: Optional[NDArray] = None, format: str = "csr"
+) -> str:
+    """SHA-256 hex digest identifying a sparsity pattern.
+
+    The digest covers the format and both index arrays (as int64), so it
+    changes whenever an entry is added, removed or reordered.
+
+    Args:
+        row_ptr: Row pointer, or a ``scipy.sparse`` matrix/array or dense 2D
+            mask whose index arrays and format are used instead of
+            ``col_idx`` and ``format``.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        format: Storage layout of the index arrays: ``"csr"``, ``"csc"`` or
+            ``"coo"``.
+
+    Returns:
+        digest: Hex string of the hash.
+    """
+    if col_idx is None:
+        row_ptr, col_idx, _, format = _sparse_pattern(row_ptr)
+    digest = hashlib.sha256(format.encode())
+    for arr in (row_ptr, col_idx):
+        arr = np.ascontiguousarray(arr, dtype=np.int64)
+        digest.update(np.int64(arr.size).tobytes())
+        digest.update(arr.tobytes())
+    return digest.hexdigest()
+
+
+def save_coloring(
+    path: Union[str, os.PathLike],
+    colors: NDArray,
+    row_ptr: Any = None,
+    col_idx: Optional[NDArray] = None,
+    format: str = "csr",
+    strategy: Optional[Dict[str, Any]] = None,
+) -> None:
+    """Store a coloring in an ``.npz`` file for reuse between runs.
+
+    The file holds ``colors``, ``n_colors``, the ``strategy`` (as JSON) and,
+    when the pattern is given, its ``pattern_hash`` so that
+    ``load_coloring`` can detect a stale cache.
+
+    Args:
+        path: Destination file; NumPy appends ``.npz`` if missing.
+        colors: Color ids to store.
+        row_ptr: Optional pattern the coloring belongs to: a row pointer, or a
+            ``scipy.sparse`` matrix/array or dense 2D mask.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        format: Storage layout of the index arrays.
+        strategy: JSON-serializable description of how the coloring was
+            computed, e.g. ``{"algorithm": "greedy", "ordering": "dsatur"}``.
+    """
+    colors_arr = np.asarray(colors)
+    digest = "" if row_ptr is None else pattern_hash(row_ptr, col_idx, format)
+    np.savez(
+        path,
+        colors=colors_arr,
+        n_colors=np.int64(colors_arr.max() + 1 if colors_arr.size else 0),
+        strategy=np.str_(json.dumps(strategy or {}, sort_keys=True)),
+        pattern_hash=np.str_(digest),
+    )
+
+
+def load_coloring(
+    path: Union[str, os.PathLike],
+    row_ptr: Any = None,
+    col_idx: Optional[NDArray] = None,
+    format: str = "csr",
+) -> Tuple[NDArray, Dict[str, Any]]:
+    """Load a coloring stored by ``save_coloring``, optionally checking its pattern.
+
+    Args:
+        path: File written by ``save_coloring``.
+        row_ptr: Optional pattern to validate against: a row pointer, or a
+            ``scipy.sparse`` matrix/array or dense 2D mask. A ``ValueError``
+            is raised if its hash differs from the stored one.
+        col_idx: Column indices; omitted for a sparse or mask input.
+        format: Storage layout of the index arrays.
+
+    Returns:
+        colors: Stored color ids.
+        info: ``dict`` with ``n_colors``, ``strategy`` and ``pattern_hash``
+            (empty if no pattern was stored).
+    """
+    with np.load(path, allow_pickle=False) as data:
+        colors = data["colors"]
+        info = {
+            "n_colors": int(data["n_colors"]),
+            "strategy": json.loads(str(data["strategy"])),
+            "pattern_hash": str(data["pattern_hash"]),
+        }
+    if row_ptr is not None:
+        digest = pattern_hash(row_ptr, col_idx, format)
+        if digest != info["pattern_hash"]:
+            raise ValueError(
+                f"coloring in {os.fspath(path)!r} was computed for a different "
+                "sparsity pattern"
+            )
+    return colors, info
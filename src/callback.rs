@@ -0,0 +1,169 @@
+//! Progress reports handed to a Python callable or logger, and Ctrl-C handling.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::options::Distance2Error;
+use crate::progress::{Cancelled, Progress};
+
+/// How often `PyProgress::run` checks for Python signals.
+const SIGNAL_POLL: Duration = Duration::from_millis(50);
+
+/// Progress reported as `callback(done, total, phase)` every `interval` vertices.
+///
+/// The GIL is taken only for the call itself, from whichever thread reports.
+/// An exception raised by the callback, or by a signal handler while `run`
+/// waits, stops the computation and is returned by `run`. With a `logger`,
+/// the start and end of every phase are logged at INFO as well.
+pub(crate) struct PyProgress {
+    callback: Option<Py<PyAny>>,
+    interval: usize,
+    logger: Option<Py<PyAny>>,
+    stopped: AtomicBool,
+    raised: Mutex<Option<PyErr>>,
+}
+
+impl PyProgress {
+    /// Reports to `callback`, and to the `tatva_coloring` logger if `verbose`.
+    pub(crate) fn new(
+        py: Python<'_>,
+        callback: Option<Py<PyAny>>,
+        interval: usize,
+        verbose: bool,
+    ) -> PyResult<Self> {
+        if interval == 0 {
+            return Err(PyValueError::new_err("progress_interval must be positive"));
+        }
+        let logger = verbose
+            .then(|| -> PyResult<_> {
+                let logging = py.import("logging")?;
+                Ok(logging
+                    .call_method1("getLogger", ("tatva_coloring",))?
+                    .unbind())
+            })
+            .transpose()?;
+        Ok(Self {
+            callback,
+            interval,
+            logger,
+            stopped: AtomicBool::new(false),
+            raised: Mutex::new(None),
+        })
+    }
+
+    /// No callback or logger: only Ctrl-C stops the computation.
+    pub(crate) fn signals_only() -> Self {
+        Self {
+            callback: None,
+            interval: usize::MAX,
+            logger: None,
+            stopped: AtomicBool::new(false),
+            raised: Mutex::new(None),
+        }
+    }
+
+    /// Log `message` at INFO if verbose.
+    pub(crate) fn log(&self, py: Python<'_>, message: &str) -> PyResult<()> {
+        if let Some(logger) = &self.logger {
+            logger.call_method1(py, "info", (message,))?;
+        }
+        Ok(())
+    }
+
+    /// Run `f` without the GIL on a helper thread, stopping it on Ctrl-C.
+    ///
+    /// CPython runs signal handlers only on the main thread, so the calling
+    /// thread stays behind and checks for signals every 50 ms until `f`
+    /// returns. `f` notices a raised `KeyboardInterrupt` at its next
+    /// processed vertex; strategies that color the explicit graph only at
+    /// the end of coloring. The exception is returned even if `f` finished
+    /// first, so no interrupt is lost.
+    pub(crate) fn run<T: Send>(&self, py: Python<'_>, f: impl FnOnce() -> T + Send) -> PyResult<T> {
+        let result = py.detach(|| {
+            thread::scope(|scope| {
+                let waiting = thread::current();
+                let worker = scope.spawn(move || {
+                    let result = f();
+                    waiting.unpark();
+                    result
+                });
+                while !worker.is_finished() {
+                    thread::park_timeout(SIGNAL_POLL);
+                    if let Err(err) = Python::attach(|py| py.check_signals()) {
+                        self.stop(err);
+                    }
+                }
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+        });
+        match self.raised.lock().expect("progress lock poisoned").take() {
+            Some(err) => Err(err),
+            None => Ok(result),
+        }
+    }
+
+    /// Cancel the computation, keeping the first exception for `run`.
+    fn stop(&self, err: PyErr) {
+        self.raised
+            .lock()
+            .expect("progress lock poisoned")
+            .get_or_insert(err);
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Python exception for a `color_distance2` failure that `run` did not raise.
+pub(crate) fn distance2_error(error: Distance2Error) -> PyErr {
+    match error {
+        Distance2Error::LowMemory
+        | Distance2Error::Constrained
+        | Distance2Error::NoAllowedColor(_) => PyValueError::new_err(error.to_string()),
+        Distance2Error::Cancelled => PyRuntimeError::new_err(error.to_string()),
+    }
+}
+
+impl Progress for PyProgress {
+    fn interval(&self) -> usize {
+        match self.callback {
+            Some(_) => self.interval,
+            None => usize::MAX,
+        }
+    }
+
+    fn report(&self, phase: &'static str, done: usize, total: usize) -> Result<(), Cancelled> {
+        if self.cancelled() {
+            return Err(Cancelled);
+        }
+        let boundary = done == 0 || done == total;
+        if self.callback.is_none() && !(boundary && self.logger.is_some()) {
+            return Ok(());
+        }
+        Python::attach(|py| -> PyResult<()> {
+            if done == 0 {
+                self.log(py, &format!("{phase}: started on {total} vertices"))?;
+            }
+            if done == total {
+                self.log(py, &format!("{phase}: finished"))?;
+            }
+            if let Some(callback) = &self.callback {
+                callback.call1(py, (done, total, phase))?;
+            }
+            Ok(())
+        })
+        .map_err(|err| {
+            self.stop(err);
+            Cancelled
+        })
+    }
+
+    fn cancelled(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,54 @@
+//! Color id arrays handed back to Python.
+
+use numpy::{Element, PyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Integer dtype of returned color ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ColorDtype {
+    Int32,
+    Int64,
+    UInt16,
+}
+
+impl ColorDtype {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "int32" => Ok(Self::Int32),
+            "int64" => Ok(Self::Int64),
+            "uint16" => Ok(Self::UInt16),
+            _ => Err(format!(
+                "unknown colors_dtype '{name}', expected one of: int32, int64, uint16"
+            )),
+        }
+    }
+}
+
+/// Color ids as a NumPy array of `dtype`, raising `ValueError` if a color does not fit.
+pub(crate) fn colors_to_py(
+    py: Python<'_>,
+    colors: &[usize],
+    dtype: ColorDtype,
+) -> PyResult<Py<PyAny>> {
+    match dtype {
+        ColorDtype::Int32 => typed_colors::<i32>(py, colors, "int32"),
+        ColorDtype::Int64 => typed_colors::<i64>(py, colors, "int64"),
+        ColorDtype::UInt16 => typed_colors::<u16>(py, colors, "uint16"),
+    }
+}
+
+fn typed_colors<T: Element + TryFrom<usize>>(
+    py: Python<'_>,
+    colors: &[usize],
+    name: &str,
+) -> PyResult<Py<PyAny>> {
+    let values = colors
+        .iter()
+        .map(|&c| {
+            T::try_from(c)
+                .map_err(|_| PyValueError::new_err(format!("color {c} does not fit in {name}")))
+        })
+        .collect::<PyResult<Vec<T>>>()?;
+    Ok(PyArray1::from_vec(py, values).into_any().unbind())
+}
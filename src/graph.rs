@@ -0,0 +1,213 @@
+//! Distance-2 conflict graph kept across coloring queries.
+
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::callback::{PyProgress, distance2_error};
+use crate::distance2::Distance2Graph;
+use crate::index::IndexArray;
+use crate::options::Distance2Error;
+use crate::stats::ColoringStats;
+use crate::timings::Timings;
+use crate::{
+    CsrArrays, VertexDofs, block_maxima, blocked_graph, check_max_colors, color_distance2_graph,
+    color_ids, coloring_options, colors, colors_dtype_value, csr_arrays, priority_values,
+    seed_options, seeds, set_allowed, set_max_colors, set_precolors, set_restarts, validation,
+};
+
+/// Distance-2 conflict graph of a pattern, built once and queried repeatedly.
+///
+/// Parsing, symmetrization and the transpose happen on construction; vertex
+/// degrees and the explicit distance-2 adjacency are kept once a query has
+/// needed them, so later queries with other strategies or seed layouts skip
+/// all of that work.
+#[pyclass(frozen, module = "tatva_coloring")]
+pub(crate) struct ColoringGraph {
+    graph: Distance2Graph,
+    block_size: usize,
+}
+
+impl ColoringGraph {
+    /// Copy `colors` after checking that it colors every DOF.
+    fn dof_colors(&self, colors: &IndexArray<'_>) -> PyResult<Vec<usize>> {
+        let colors = color_ids(colors)?;
+        let n_dofs = self.n_dofs();
+        if colors.len() != n_dofs {
+            return Err(PyValueError::new_err(format!(
+                "colors length must be {n_dofs}, got {}",
+                colors.len()
+            )));
+        }
+        Ok(colors)
+    }
+}
+
+#[pymethods]
+impl ColoringGraph {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        row_ptr, col_idx, n_dofs, format="csr", symmetrize=false, block_size=1,
+        dofs_per_node=1, n_cols=None, validate="strict",
+    ))]
+    fn new(
+        py: Python<'_>,
+        row_ptr: IndexArray<'_>,
+        col_idx: IndexArray<'_>,
+        n_dofs: usize,
+        format: &str,
+        symmetrize: bool,
+        block_size: usize,
+        dofs_per_node: usize,
+        n_cols: Option<usize>,
+        validate: &str,
+    ) -> PyResult<Self> {
+        let (graph, block_size) = blocked_graph(
+            py,
+            &row_ptr,
+            &col_idx,
+            n_dofs,
+            n_cols,
+            format,
+            symmetrize,
+            validation(validate)?,
+            block_size,
+            dofs_per_node,
+            None,
+        )?;
+        Ok(Self { graph, block_size })
+    }
+
+    /// Number of colored DOFs, i.e. columns of a rectangular pattern.
+    #[getter]
+    fn n_dofs(&self) -> usize {
+        self.graph.n_vertices() * self.block_size
+    }
+
+    #[getter]
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Distance-2 colors for the given strategy, plus statistics if requested.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        ordering="natural", seed=0, refine=None, algorithm="greedy", time_limit=10.0,
+        tabu_iterations=10000, deterministic=false, restarts=1, max_colors=None,
+        require_max_colors=true, priority=None, precolors=None, allowed_colors=None,
+        low_memory=false, return_stats=false, colors_dtype="int32", progress=None,
+        progress_interval=100000, verbose=false,
+    ))]
+    fn colors(
+        &self,
+        py: Python<'_>,
+        ordering: &str,
+        seed: u64,
+        refine: Option<&str>,
+        algorithm: &str,
+        time_limit: f64,
+        tabu_iterations: usize,
+        deterministic: bool,
+        restarts: usize,
+        max_colors: Option<usize>,
+        require_max_colors: bool,
+        priority: Option<PyReadonlyArray1<'_, f64>>,
+        precolors: Option<PyReadonlyArray1<'_, i64>>,
+        allowed_colors: Option<PyReadonlyArray1<'_, u64>>,
+        low_memory: bool,
+        return_stats: bool,
+        colors_dtype: &str,
+        progress: Option<Py<PyAny>>,
+        progress_interval: usize,
+        verbose: bool,
+    ) -> PyResult<(Py<PyAny>, Option<ColoringStats>)> {
+        let colors_dtype = colors_dtype_value(colors_dtype)?;
+        let progress = PyProgress::new(py, progress, progress_interval, verbose)?;
+        let mut options = coloring_options(
+            algorithm,
+            ordering,
+            seed,
+            refine,
+            time_limit,
+            tabu_iterations,
+            deterministic,
+        )?;
+        set_restarts(&mut options, restarts)?;
+        options.priority =
+            priority_values(priority, self.n_dofs())?.map(|p| block_maxima(&p, self.block_size));
+        let dofs = VertexDofs::Blocks(self.block_size);
+        set_max_colors(&mut options, max_colors, dofs)?;
+        set_precolors(&mut options, precolors, self.n_dofs(), dofs)?;
+        set_allowed(&mut options, allowed_colors, self.n_dofs(), dofs)?;
+        let (colors, stats) = color_distance2_graph(
+            py,
+            &self.graph,
+            None,
+            dofs,
+            &options,
+            low_memory,
+            return_stats,
+            &mut Timings::default(),
+            &progress,
+        )?;
+        check_max_colors(&colors, max_colors, require_max_colors)?;
+        Ok((colors::colors_to_py(py, &colors, colors_dtype)?, stats))
+    }
+
+    /// Seed matrix of `colors` in the requested layout and dtype, written into `out` if given.
+    #[pyo3(signature = (
+        colors, seed_format="list", seed_dtype="bool", rademacher_seed=None, out=None,
+    ))]
+    fn seeds(
+        &self,
+        py: Python<'_>,
+        colors: IndexArray<'_>,
+        seed_format: &str,
+        seed_dtype: &str,
+        rademacher_seed: Option<u64>,
+        out: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<Py<PyAny>> {
+        let seed_options = seed_options(seed_format, seed_dtype, rademacher_seed)?;
+        seeds::seeds_into_py(py, &self.dof_colors(&colors)?, seed_options, out.as_ref())
+    }
+
+    /// Index array of each color class of `colors` (int64).
+    fn groups(&self, py: Python<'_>, colors: IndexArray<'_>) -> PyResult<Vec<Py<PyArray1<i64>>>> {
+        Ok(seeds::groups_from_colors(py, &self.dof_colors(&colors)?))
+    }
+
+    /// Whether `colors` gives different colors to all DOFs within distance 2.
+    fn verify(&self, py: Python<'_>, colors: IndexArray<'_>) -> PyResult<bool> {
+        let colors = self.dof_colors(&colors)?;
+        Ok(py.detach(|| self.graph.conflicts(&colors, self.block_size).is_empty()))
+    }
+
+    /// Explicit conflict graph as CSR `(indptr, indices)`, as `distance2_adjacency`.
+    fn adjacency(&self, py: Python<'_>) -> PyResult<CsrArrays> {
+        let progress = PyProgress::signals_only();
+        progress
+            .run(py, || {
+                self.graph.adjacency_with_progress(&progress).map(|_| ())
+            })?
+            .map_err(|_| distance2_error(Distance2Error::Cancelled))?;
+        Ok(csr_arrays(py, self.graph.adjacency()))
+    }
+
+    /// Lower bound on the colors of any coloring, as `distance2_lower_bound`.
+    #[pyo3(signature = (n_seeds=32))]
+    fn lower_bound(&self, py: Python<'_>, n_seeds: usize) -> usize {
+        py.detach(|| self.graph.greedy_clique(n_seeds).len()) * self.block_size
+    }
+
+    /// Bytes the explicit distance-2 graph occupies once a strategy needs it.
+    fn memory_estimate(&self, py: Python<'_>) -> PyResult<usize> {
+        let progress = PyProgress::signals_only();
+        progress
+            .run(py, || {
+                self.graph.degrees_with_progress(&progress).map(|_| ())
+            })?
+            .map_err(|_| distance2_error(Distance2Error::Cancelled))?;
+        Ok(self.graph.adjacency_bytes())
+    }
+}
@@ -0,0 +1,193 @@
+//! Rank-local distance-2 graph for colorings spread over MPI ranks.
+
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::distributed;
+use crate::index::{IndexArray, Validation, checked_index, with_index_slice};
+use crate::{pattern_rows, priority_values, validation};
+
+/// Local colors plus the vertices just uncolored, both as int64.
+type ColorsAndLosers = (Py<PyArray1<i64>>, Py<PyArray1<i64>>);
+
+/// Owned rows of one rank plus the rows of its ghosts, as `distributed::HaloGraph`.
+///
+/// Colors go in and out as int64 arrays over the local vertices, owned ones
+/// first and then `halo`, with -1 marking uncolored vertices; the Python
+/// driver exchanges the halo entries between rounds.
+#[pyclass(frozen, module = "tatva_coloring")]
+pub(crate) struct HaloGraph {
+    graph: distributed::HaloGraph,
+}
+
+impl HaloGraph {
+    /// Copy local colors, -1 becoming `usize::MAX`.
+    fn local_colors(&self, colors: &PyReadonlyArray1<'_, i64>) -> PyResult<Vec<usize>> {
+        let colors = colors.as_slice()?;
+        let n = self.graph.n_vertices();
+        if colors.len() != n {
+            return Err(PyValueError::new_err(format!(
+                "colors length must be {n}, got {}",
+                colors.len()
+            )));
+        }
+        colors
+            .iter()
+            .map(|&c| match c {
+                -1 => Ok(usize::MAX),
+                c => checked_index(c),
+            })
+            .collect()
+    }
+
+    /// Local vertex ids, each checked to be owned.
+    fn owned_vertices(&self, vertices: &IndexArray<'_>) -> PyResult<Vec<usize>> {
+        let n_owned = self.graph.n_owned();
+        with_index_slice!(vertices, vertices => vertices
+            .iter()
+            .map(|&v| match checked_index(v)? {
+                v if v < n_owned => Ok(v),
+                v => Err(PyValueError::new_err(format!(
+                    "vertex {v} is not owned, expected fewer than {n_owned}"
+                ))),
+            })
+            .collect())
+    }
+
+    /// Copy an optional priority over the local vertices.
+    fn local_priority(
+        &self,
+        priority: Option<PyReadonlyArray1<'_, f64>>,
+    ) -> PyResult<Option<Vec<f64>>> {
+        priority_values(priority, self.graph.n_vertices())
+    }
+}
+
+/// Colors back as int64, `usize::MAX` becoming -1.
+fn colors_to_i64(py: Python<'_>, colors: &[usize]) -> Py<PyArray1<i64>> {
+    PyArray1::from_iter(
+        py,
+        colors
+            .iter()
+            .map(|&c| if c == usize::MAX { -1 } else { c as i64 }),
+    )
+    .unbind()
+}
+
+fn vertices_to_i64(py: Python<'_>, vertices: &[usize]) -> Py<PyArray1<i64>> {
+    PyArray1::from_iter(py, vertices.iter().map(|&v| v as i64)).unbind()
+}
+
+#[pymethods]
+impl HaloGraph {
+    /// Rows `row_start..` in CSR with global columns, plus the CSR rows of `ghosts`.
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        row_ptr, col_idx, row_start, n_local, n_global, ghosts, ghost_ptr, ghost_idx,
+        validate="strict",
+    ))]
+    fn new(
+        py: Python<'_>,
+        row_ptr: IndexArray<'_>,
+        col_idx: IndexArray<'_>,
+        row_start: usize,
+        n_local: usize,
+        n_global: usize,
+        ghosts: IndexArray<'_>,
+        ghost_ptr: IndexArray<'_>,
+        ghost_idx: IndexArray<'_>,
+        validate: &str,
+    ) -> PyResult<Self> {
+        let validation = validation(validate)?;
+        if row_start + n_local > n_global {
+            return Err(PyValueError::new_err(format!(
+                "rows {row_start}..{} exceed the {n_global} global rows",
+                row_start + n_local
+            )));
+        }
+        let ghosts: Vec<usize> = with_index_slice!(&ghosts, ghosts => ghosts
+            .iter()
+            .map(|&g| checked_index(g))
+            .collect::<PyResult<_>>())?;
+        let owned = pattern_rows(&row_ptr, &col_idx, n_local, n_global, "csr", validation)?;
+        let ghost_rows = pattern_rows(
+            &ghost_ptr,
+            &ghost_idx,
+            ghosts.len(),
+            n_global,
+            "csr",
+            validation,
+        )?;
+        // The halo holds every distance-2 neighborhood only for a symmetric
+        // pattern, so strict input checks the mirrors this rank can see.
+        if validation == Validation::Strict
+            && let Some((i, j)) = py.detach(|| {
+                distributed::HaloGraph::asymmetric_entry(row_start, &owned, &ghosts, &ghost_rows)
+            })
+        {
+            return Err(PyValueError::new_err(format!(
+                "pattern is not structurally symmetric: entry ({i}, {j}) has no ({j}, {i})"
+            )));
+        }
+        let graph = py
+            .detach(|| distributed::HaloGraph::new(row_start, &owned, &ghosts, &ghost_rows))
+            .map_err(PyValueError::new_err)?;
+        Ok(Self { graph })
+    }
+
+    #[getter]
+    fn n_owned(&self) -> usize {
+        self.graph.n_owned()
+    }
+
+    /// Global index of every halo vertex (int64), following the owned vertices locally.
+    fn halo(&self, py: Python<'_>) -> Py<PyArray1<i64>> {
+        vertices_to_i64(py, self.graph.halo())
+    }
+
+    /// Owned vertices with a halo vertex within distance 2 (int64, local ids).
+    fn boundary(&self, py: Python<'_>) -> Py<PyArray1<i64>> {
+        let boundary = py.detach(|| self.graph.boundary());
+        vertices_to_i64(py, &boundary)
+    }
+
+    /// `colors` with `vertices` colored greedily in decreasing precedence.
+    #[pyo3(signature = (colors, vertices, priority=None))]
+    fn color(
+        &self,
+        py: Python<'_>,
+        colors: PyReadonlyArray1<'_, i64>,
+        vertices: IndexArray<'_>,
+        priority: Option<PyReadonlyArray1<'_, f64>>,
+    ) -> PyResult<Py<PyArray1<i64>>> {
+        let mut colors = self.local_colors(&colors)?;
+        let vertices = self.owned_vertices(&vertices)?;
+        let priority = self.local_priority(priority)?;
+        py.detach(|| {
+            self.graph
+                .color(&mut colors, &vertices, priority.as_deref())
+        });
+        Ok(colors_to_i64(py, &colors))
+    }
+
+    /// `colors` with the `vertices` that yield to a halo vertex uncolored, and those vertices.
+    #[pyo3(signature = (colors, vertices, priority=None))]
+    fn yield_conflicts(
+        &self,
+        py: Python<'_>,
+        colors: PyReadonlyArray1<'_, i64>,
+        vertices: IndexArray<'_>,
+        priority: Option<PyReadonlyArray1<'_, f64>>,
+    ) -> PyResult<ColorsAndLosers> {
+        let mut colors = self.local_colors(&colors)?;
+        let vertices = self.owned_vertices(&vertices)?;
+        let priority = self.local_priority(priority)?;
+        let losers = py.detach(|| {
+            self.graph
+                .yield_conflicts(&mut colors, &vertices, priority.as_deref())
+        });
+        Ok((colors_to_i64(py, &colors), vertices_to_i64(py, &losers)))
+    }
+}
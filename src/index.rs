@@ -0,0 +1,97 @@
+//! Integer dtypes accepted for sparsity-pattern index arrays.
+
+use std::fmt::Display;
+
+use numpy::{Element, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Integer type an index array may hold.
+pub(crate) trait IndexValue: Element + Copy + Display {
+    /// Position encoded by the value, or `None` if it is negative or exceeds `usize`.
+    fn to_index(self) -> Option<usize>;
+}
+
+macro_rules! impl_index_value {
+    ($($t:ty),*) => {
+        $(impl IndexValue for $t {
+            fn to_index(self) -> Option<usize> {
+                usize::try_from(self).ok()
+            }
+        })*
+    };
+}
+
+impl_index_value!(i64, i32, u64, u32);
+
+/// Overflow-checked conversion of an index value, raising `ValueError` when out of range.
+pub(crate) fn checked_index<I: IndexValue>(value: I) -> PyResult<usize> {
+    value
+        .to_index()
+        .ok_or_else(|| PyValueError::new_err(format!("index {value} is not a valid position")))
+}
+
+/// How thoroughly pattern index arrays are checked, chosen with `validate`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Validation {
+    /// Check every index and the pointer structure, sorting and merging rows as needed.
+    ///
+    /// Structural symmetry is checked wherever coloring relies on it: star,
+    /// restricted-star and acyclic coloring, Hessian recovery (unless
+    /// `symmetrize` declares half storage) and distributed rows. Distance-1,
+    /// distance-k and distance-2 coloring define their graph through both the
+    /// pattern and its transpose, so any pattern colors correctly there.
+    Strict,
+    /// Check array lengths and the pointer ends only, in O(1).
+    Fast,
+    /// Trust the input to be canonical and in range; bad input may panic or miscolor.
+    Off,
+}
+
+impl Validation {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "strict" => Ok(Self::Strict),
+            "fast" => Ok(Self::Fast),
+            "off" => Ok(Self::Off),
+            _ => Err(format!(
+                "unknown validate '{name}', expected one of: strict, fast, off"
+            )),
+        }
+    }
+}
+
+/// Index array borrowed from NumPy in its native dtype, so that no conversion copy is needed.
+#[derive(FromPyObject)]
+pub(crate) enum IndexArray<'py> {
+    I64(PyReadonlyArray1<'py, i64>),
+    I32(PyReadonlyArray1<'py, i32>),
+    U64(PyReadonlyArray1<'py, u64>),
+    U32(PyReadonlyArray1<'py, u32>),
+}
+
+/// Bind `$slice` to the contents of an `IndexArray` and evaluate `$body` for its dtype.
+macro_rules! with_index_slice {
+    ($array:expr, $slice:ident => $body:expr) => {
+        match $array {
+            $crate::index::IndexArray::I64(array) => {
+                let $slice = array.as_slice()?;
+                $body
+            }
+            $crate::index::IndexArray::I32(array) => {
+                let $slice = array.as_slice()?;
+                $body
+            }
+            $crate::index::IndexArray::U64(array) => {
+                let $slice = array.as_slice()?;
+                $body
+            }
+            $crate::index::IndexArray::U32(array) => {
+                let $slice = array.as_slice()?;
+                $body
+            }
+        }
+    };
+}
+
+pub(crate) use with_index_slice;
@@ -1,138 +1,2810 @@
-use ahash::{AHashMap, AHashSet};
-use numpy::{PyArray1, PyReadonlyArray1};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use numpy::{Element, PyArray1, PyReadonlyArray1, PyReadonlyArray2, PyUntypedArrayMethods};
 use pyo3::prelude::*;
-use pyo3::types::PyModule;
+use pyo3::types::{PyDict, PyModule};
+
+mod callback;
+mod colors;
+mod graph;
+mod halo;
+mod index;
+mod seeds;
+mod stats;
+
+use tatva_coloring_core::{
+    acyclic, adjacency, bipartite, constraints, distance2, distributed, greedy, matrix_market,
+    mesh, metis, options, ordering, partition, progress, recovery, refine, repair, rng, star,
+    supervariables, timings, verify,
+};
+
+use adjacency::Adjacency;
+use callback::PyProgress;
+use colors::ColorDtype;
+use distance2::Distance2Graph;
+use graph::ColoringGraph;
+use greedy::VertexOrdering;
+use halo::HaloGraph;
+use index::{IndexArray, IndexValue, Validation, checked_index, with_index_slice};
+use options::{Algorithm, ColoringOptions, Distance2Error};
+use recovery::HessianRecovery;
+use refine::Refinement;
+use seeds::SeedOptions;
+use stats::ColoringStats;
+use timings::Timings;
+
+/// Colors plus the seed matrix in the requested layout, if requested.
+type ColorsAndSeeds = (Py<PyAny>, Option<Py<PyAny>>);
+/// `ColorsAndSeeds` plus coloring statistics and stage timings, if requested.
+type ColorsSeedsStatsAndTimings = (
+    Py<PyAny>,
+    Option<Py<PyAny>>,
+    Option<ColoringStats>,
+    Option<Py<PyDict>>,
+);
+/// Colors plus coloring statistics and stage timings, if requested.
+type ColorsStatsAndTimings = (Py<PyAny>, Option<ColoringStats>, Option<Py<PyDict>>);
+type RowAndColumnColors = (Py<PyArray1<i32>>, Py<PyArray1<i32>>);
+/// CSR `(indptr, indices)` of a graph as np.int64.
+type CsrArrays = (Py<PyArray1<i64>>, Py<PyArray1<i64>>);
+/// Compressed product `J @ S` of shape `(n_rows, n_colors)` in a supported float dtype.
+#[derive(FromPyObject)]
+enum CompressedProduct<'py> {
+    F64(PyReadonlyArray2<'py, f64>),
+    F32(PyReadonlyArray2<'py, f32>),
+}
+
+/// A permutation and its inverse as np.int64.
+type Permutation = (Py<PyArray1<i64>>, Py<PyArray1<i64>>);
+/// Color count, `ISColoringValue` colors and one `PetscInt` index set per color.
+type PetscColoring = (usize, Py<PyAny>, Vec<Py<PyAny>>);
 
 /// Color a sparse matrix's distance-2 graph and emit colors and seed vectors.
 #[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false, restarts=1,
+    max_colors=None, require_max_colors=true, priority=None, precolors=None,
+    allowed_colors=None, format="csr", symmetrize=false, block_size=1, dofs_per_node=1,
+    tie_ptr=None, tie_dofs=None, supervariables=false, n_cols=None, low_memory=false,
+    seed_format="list", seed_dtype="bool", rademacher_seed=None, out=None, return_seeds=true,
+    return_stats=false, return_timings=false, colors_dtype="int32", progress=None,
+    progress_interval=100000, verbose=false, validate="strict",
+))]
 fn distance2_color_and_seeds(
     py: Python<'_>,
-    row_ptr: PyReadonlyArray1<'_, i64>,
-    col_idx: PyReadonlyArray1<'_, i64>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
     n_dofs: usize,
-) -> PyResult<(Py<PyArray1<i32>>, Vec<Py<PyArray1<bool>>>)> {
-    let row_ptr = row_ptr.as_slice()?;
-    let col_idx = col_idx.as_slice()?;
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    restarts: usize,
+    max_colors: Option<usize>,
+    require_max_colors: bool,
+    priority: Option<PyReadonlyArray1<'_, f64>>,
+    precolors: Option<PyReadonlyArray1<'_, i64>>,
+    allowed_colors: Option<PyReadonlyArray1<'_, u64>>,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    dofs_per_node: usize,
+    tie_ptr: Option<IndexArray<'_>>,
+    tie_dofs: Option<IndexArray<'_>>,
+    supervariables: bool,
+    n_cols: Option<usize>,
+    low_memory: bool,
+    seed_format: &str,
+    seed_dtype: &str,
+    rademacher_seed: Option<u64>,
+    out: Option<Bound<'_, PyAny>>,
+    return_seeds: bool,
+    return_stats: bool,
+    return_timings: bool,
+    colors_dtype: &str,
+    progress: Option<Py<PyAny>>,
+    progress_interval: usize,
+    verbose: bool,
+    validate: &str,
+) -> PyResult<ColorsSeedsStatsAndTimings> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let seed_options = seed_options(seed_format, seed_dtype, rademacher_seed)?;
+    let progress = PyProgress::new(py, progress, progress_interval, verbose)?;
+    if out.is_some() && !return_seeds {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "out requires return_seeds",
+        ));
+    }
+    let dofs_per_vertex = dofs_per_vertex(block_size, dofs_per_node)?;
+    let mut options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+    set_restarts(&mut options, restarts)?;
+    let n_vertices = n_cols.unwrap_or(n_dofs);
+    let ties = tie_groups(tie_ptr, tie_dofs, n_vertices, dofs_per_vertex)?;
+    let dofs = match &ties {
+        Some(groups) => VertexDofs::Tied(groups),
+        None => VertexDofs::Blocks(dofs_per_vertex),
+    };
+    options.priority = priority_values(priority, n_vertices)?.map(|p| dofs.priority(&p));
+    set_max_colors(&mut options, max_colors, dofs)?;
+    set_precolors(&mut options, precolors, n_vertices, dofs)?;
+    set_allowed(&mut options, allowed_colors, n_vertices, dofs)?;
 
-    let colors = distance2_colors_impl(row_ptr, col_idx, n_dofs)?;
+    let mut timings = Timings::default();
+    let (colors, stats) = distance2_colors_impl(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_dofs,
+        n_cols,
+        format,
+        symmetrize,
+        validation(validate)?,
+        block_size,
+        dofs_per_node,
+        ties.as_deref(),
+        supervariables,
+        &options,
+        low_memory,
+        return_stats,
+        &mut timings,
+        &progress,
+    )?;
+    check_max_colors(&colors, max_colors, require_max_colors)?;
 
-    // Pack outputs for Python: colors as np.int32 and seeds as list of bool.
-    let colors_py = PyArray1::from_iter(py, colors.iter().map(|&c| c as i32)).unbind();
-    let seeds = seeds_from_colors(py, &colors)?;
+    // Pack outputs for Python: colors, seeds, statistics and timings.
+    let colors_py = colors::colors_to_py(py, &colors, colors_dtype)?;
+    let seeds = return_seeds
+        .then(|| {
+            timings.time("seeds", || {
+                seeds::seeds_into_py(py, &colors, seed_options, out.as_ref())
+            })
+        })
+        .transpose()?;
+    let timings = return_timings
+        .then(|| timings_dict(py, &timings))
+        .transpose()?;
 
-    Ok((colors_py, seeds))
+    Ok((colors_py, seeds, stats, timings))
 }
 
 /// Compute distance-2 colors for a CSR sparse matrix.
 #[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false, restarts=1,
+    max_colors=None, require_max_colors=true, priority=None, precolors=None,
+    allowed_colors=None, format="csr", symmetrize=false, block_size=1, dofs_per_node=1,
+    tie_ptr=None, tie_dofs=None, supervariables=false, n_cols=None, low_memory=false,
+    return_stats=false, return_timings=false, colors_dtype="int32", progress=None,
+    progress_interval=100000, verbose=false, validate="strict",
+))]
 fn distance2_colors(
     py: Python<'_>,
-    row_ptr: PyReadonlyArray1<'_, i64>,
-    col_idx: PyReadonlyArray1<'_, i64>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
     n_dofs: usize,
-) -> PyResult<Py<PyArray1<i32>>> {
-    let row_ptr = row_ptr.as_slice()?;
-    let col_idx = col_idx.as_slice()?;
-    let colors = distance2_colors_impl(row_ptr, col_idx, n_dofs)?;
-    Ok(PyArray1::from_iter(py, colors.iter().map(|&c| c as i32)).unbind())
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    restarts: usize,
+    max_colors: Option<usize>,
+    require_max_colors: bool,
+    priority: Option<PyReadonlyArray1<'_, f64>>,
+    precolors: Option<PyReadonlyArray1<'_, i64>>,
+    allowed_colors: Option<PyReadonlyArray1<'_, u64>>,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    dofs_per_node: usize,
+    tie_ptr: Option<IndexArray<'_>>,
+    tie_dofs: Option<IndexArray<'_>>,
+    supervariables: bool,
+    n_cols: Option<usize>,
+    low_memory: bool,
+    return_stats: bool,
+    return_timings: bool,
+    colors_dtype: &str,
+    progress: Option<Py<PyAny>>,
+    progress_interval: usize,
+    verbose: bool,
+    validate: &str,
+) -> PyResult<ColorsStatsAndTimings> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let progress = PyProgress::new(py, progress, progress_interval, verbose)?;
+    let dofs_per_vertex = dofs_per_vertex(block_size, dofs_per_node)?;
+    let mut options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+    set_restarts(&mut options, restarts)?;
+    let n_vertices = n_cols.unwrap_or(n_dofs);
+    let ties = tie_groups(tie_ptr, tie_dofs, n_vertices, dofs_per_vertex)?;
+    let dofs = match &ties {
+        Some(groups) => VertexDofs::Tied(groups),
+        None => VertexDofs::Blocks(dofs_per_vertex),
+    };
+    options.priority = priority_values(priority, n_vertices)?.map(|p| dofs.priority(&p));
+    set_max_colors(&mut options, max_colors, dofs)?;
+    set_precolors(&mut options, precolors, n_vertices, dofs)?;
+    set_allowed(&mut options, allowed_colors, n_vertices, dofs)?;
+    let mut timings = Timings::default();
+    let (colors, stats) = distance2_colors_impl(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_dofs,
+        n_cols,
+        format,
+        symmetrize,
+        validation(validate)?,
+        block_size,
+        dofs_per_node,
+        ties.as_deref(),
+        supervariables,
+        &options,
+        low_memory,
+        return_stats,
+        &mut timings,
+        &progress,
+    )?;
+    check_max_colors(&colors, max_colors, require_max_colors)?;
+    let timings = return_timings
+        .then(|| timings_dict(py, &timings))
+        .transpose()?;
+    Ok((
+        colors::colors_to_py(py, &colors, colors_dtype)?,
+        stats,
+        timings,
+    ))
 }
 
-/// Internal distance-2 coloring from CSR storage.
-fn distance2_colors_impl(row_ptr: &[i64], col_idx: &[i64], n_dofs: usize) -> PyResult<Vec<usize>> {
-    if row_ptr.len() != n_dofs + 1 {
+/// Bytes the explicit distance-2 conflict graph of a pattern would occupy.
+///
+/// Counted exactly by walking the 2-hop neighborhoods, without building the
+/// graph. Arguments as for `distance2_colors`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, format="csr", symmetrize=false, block_size=1, n_cols=None,
+    validate="strict",
+))]
+fn distance2_memory_estimate(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    n_cols: Option<usize>,
+    validate: &str,
+) -> PyResult<usize> {
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let n_block_cols = n_cols.map(|n| block_count(n, block_size)).transpose()?;
+    let rows = distance2_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        n_block_cols,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let graph = py.detach(|| distance2_graph(rows, n_block_cols));
+    let progress = PyProgress::signals_only();
+    progress
+        .run(py, || graph.degrees_with_progress(&progress).map(|_| ()))?
+        .map_err(|_| callback::distance2_error(Distance2Error::Cancelled))?;
+    Ok(graph.adjacency_bytes())
+}
+
+/// Distance-2 conflict graph of a pattern as CSR `(indptr, indices)` int64 arrays.
+///
+/// Rows are sorted and hold no self loops; the graph is over blocks for BSR
+/// input and over columns for rectangular patterns. Arguments as for
+/// `distance2_colors`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, format="csr", symmetrize=false, block_size=1, n_cols=None,
+    validate="strict",
+))]
+fn distance2_adjacency(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    n_cols: Option<usize>,
+    validate: &str,
+) -> PyResult<CsrArrays> {
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let n_block_cols = n_cols.map(|n| block_count(n, block_size)).transpose()?;
+    let rows = distance2_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        n_block_cols,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let graph = py.detach(|| distance2_graph(rows, n_block_cols));
+    let progress = PyProgress::signals_only();
+    progress
+        .run(py, || graph.adjacency_with_progress(&progress).map(|_| ()))?
+        .map_err(|_| callback::distance2_error(Distance2Error::Cancelled))?;
+    Ok(csr_arrays(py, graph.adjacency()))
+}
+
+/// Write the distance-1 or distance-2 graph of a pattern as a METIS graph file.
+///
+/// The distance-2 graph is the conflict graph of `distance2_adjacency`; the
+/// distance-1 graph is the symmetrized square pattern without its diagonal,
+/// as colored by `distance1_color`. Other arguments as for `distance2_colors`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    path, row_ptr, col_idx, n_dofs, distance=2, format="csr", symmetrize=false, block_size=1,
+    n_cols=None, validate="strict",
+))]
+fn save_metis_graph(
+    py: Python<'_>,
+    path: PathBuf,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    distance: usize,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    n_cols: Option<usize>,
+    validate: &str,
+) -> PyResult<()> {
+    use pyo3::exceptions::PyValueError;
+
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let n_block_cols = n_cols.map(|n| block_count(n, block_size)).transpose()?;
+    let validation = validation(validate)?;
+    match distance {
+        1 if n_block_cols.is_some() => {
+            return Err(PyValueError::new_err(
+                "distance 1 needs a square pattern, got n_cols",
+            ));
+        }
+        1 => {
+            let rows = pattern_rows(&row_ptr, &col_idx, n_blocks, n_blocks, format, validation)?;
+            py.detach(|| metis::write_graph(&path, &symmetric_adjacency(&rows)))?;
+        }
+        2 => {
+            let rows = distance2_pattern(
+                py,
+                &row_ptr,
+                &col_idx,
+                n_blocks,
+                n_block_cols,
+                format,
+                symmetrize,
+                validation,
+            )?;
+            let graph = py.detach(|| distance2_graph(rows, n_block_cols));
+            let progress = PyProgress::signals_only();
+            progress
+                .run(py, || graph.adjacency_with_progress(&progress).map(|_| ()))?
+                .map_err(|_| callback::distance2_error(Distance2Error::Cancelled))?;
+            py.detach(|| metis::write_graph(&path, graph.adjacency()))?;
+        }
+        d => {
+            return Err(PyValueError::new_err(format!(
+                "distance must be 1 or 2, got {d}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Distance-2 colors whose color classes each stay inside one subdomain of `partition`.
+///
+/// Subdomain `p` uses colors `offsets[p]..offsets[p + 1]`, returned as
+/// int64 alongside the colors; with `color_budget` these are the fixed
+/// ranges `p * color_budget..`. `partition` maps every DOF (every column of
+/// a rectangular pattern) to its subdomain. Other arguments as for
+/// `distance2_colors`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, partition, color_budget=None, ordering="natural", seed=0,
+    refine=None, algorithm="greedy", time_limit=10.0, tabu_iterations=10000,
+    deterministic=false, priority=None, format="csr", symmetrize=false, n_cols=None,
+    colors_dtype="int32", validate="strict",
+))]
+fn partitioned_distance2_colors(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    partition: IndexArray<'_>,
+    color_budget: Option<usize>,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    priority: Option<PyReadonlyArray1<'_, f64>>,
+    format: &str,
+    symmetrize: bool,
+    n_cols: Option<usize>,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<(Py<PyAny>, Py<PyArray1<i64>>)> {
+    use pyo3::exceptions::PyValueError;
+
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let mut options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+    let n_vertices = n_cols.unwrap_or(n_dofs);
+    options.priority = priority_values(priority, n_vertices)?;
+    let partition: Vec<usize> = with_index_slice!(&partition, partition => partition
+        .iter()
+        .map(|&p| checked_index(p))
+        .collect::<PyResult<_>>())?;
+    if partition.len() != n_vertices {
+        return Err(PyValueError::new_err(format!(
+            "partition length must be {n_vertices}, got {}",
+            partition.len()
+        )));
+    }
+    partition::check_partition(&partition).map_err(PyValueError::new_err)?;
+    let rows = distance2_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_dofs,
+        n_cols,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let graph = py.detach(|| distance2_graph(rows, n_cols));
+    let progress = PyProgress::signals_only();
+    progress
+        .run(py, || graph.adjacency_with_progress(&progress).map(|_| ()))?
+        .map_err(|_| callback::distance2_error(Distance2Error::Cancelled))?;
+    let (colors, offsets) = py
+        .detach(|| {
+            partition::partitioned_colors(graph.adjacency(), &partition, color_budget, &options)
+        })
+        .map_err(PyValueError::new_err)?;
+    let offsets = PyArray1::from_iter(py, offsets.iter().map(|&o| o as i64)).unbind();
+    Ok((colors::colors_to_py(py, &colors, colors_dtype)?, offsets))
+}
+
+/// Reverse Cuthill–McKee permutation of a square pattern (int64).
+///
+/// The pattern is symmetrized first; `perm[k]` is the old index of the DOF
+/// placed at position `k`, so `A[perm][:, perm]` is the reordered matrix.
+/// For BSR input the blocks are reordered and keep their DOFs together.
+#[pyfunction]
+#[pyo3(signature = (row_ptr, col_idx, n_dofs, format="csr", block_size=1, validate="strict"))]
+fn rcm_permutation(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    format: &str,
+    block_size: usize,
+    validate: &str,
+) -> PyResult<Py<PyArray1<i64>>> {
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        n_blocks,
+        format,
+        validation(validate)?,
+    )?;
+    let perm = py.detach(|| {
+        let order = ordering::reverse_cuthill_mckee(&symmetric_adjacency(&rows));
+        expand_blocks(&order, block_size)
+    });
+    Ok(PyArray1::from_iter(py, perm.iter().map(|&v| v as i64)).unbind())
+}
+
+/// `(indptr, indices)` of `adjacency` as int64 arrays.
+fn csr_arrays(py: Python<'_>, adjacency: &Adjacency) -> CsrArrays {
+    let as_i64 = |values: &[usize]| PyArray1::from_iter(py, values.iter().map(|&v| v as i64));
+    (
+        as_i64(adjacency.offsets()).unbind(),
+        as_i64(adjacency.neighbors()).unbind(),
+    )
+}
+
+/// Lower bound on the colors of any distance-2 coloring of a pattern.
+///
+/// The size of a clique found greedily around the `n_seeds` vertices of
+/// highest distance-2 degree, times `block_size`. Arguments as for
+/// `distance2_colors`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, format="csr", symmetrize=false, block_size=1, n_cols=None,
+    n_seeds=32, validate="strict",
+))]
+fn distance2_lower_bound(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    n_cols: Option<usize>,
+    n_seeds: usize,
+    validate: &str,
+) -> PyResult<usize> {
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let n_block_cols = n_cols.map(|n| block_count(n, block_size)).transpose()?;
+    let rows = distance2_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        n_block_cols,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let clique = py.detach(|| distance2_graph(rows, n_block_cols).greedy_clique(n_seeds));
+    Ok(clique.len() * block_size)
+}
+
+/// Repair a distance-2 coloring after nonzeros were added to or removed from the pattern.
+///
+/// `row_ptr` and `col_idx` hold the edited pattern, `added_*` and `removed_*`
+/// the row and column of every changed entry. Only vertices touched by the
+/// edits are recolored.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    colors, row_ptr, col_idx, n_dofs, added_rows, added_cols, removed_rows, removed_cols,
+    format="csr", symmetrize=false, n_cols=None, colors_dtype="int32", validate="strict",
+))]
+fn repair_distance2_colors(
+    py: Python<'_>,
+    colors: IndexArray<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    added_rows: IndexArray<'_>,
+    added_cols: IndexArray<'_>,
+    removed_rows: IndexArray<'_>,
+    removed_cols: IndexArray<'_>,
+    format: &str,
+    symmetrize: bool,
+    n_cols: Option<usize>,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<Py<PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let colors = color_ids(&colors)?;
+    let n_vertices = n_cols.unwrap_or(n_dofs);
+    if colors.len() != n_vertices {
+        return Err(PyValueError::new_err(format!(
+            "colors length must be {n_vertices}, got {}",
+            colors.len()
+        )));
+    }
+    let added = edit_endpoints(&added_rows, &added_cols, n_dofs, n_cols)?;
+    let removed = edit_endpoints(&removed_rows, &removed_cols, n_dofs, n_cols)?;
+
+    let rows = distance2_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_dofs,
+        n_cols,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let colors = py
+        .detach(|| repair::repair_colors(&distance2_graph(rows, n_cols), colors, &added, &removed));
+    colors::colors_to_py(py, &colors, colors_dtype)
+}
+
+/// Rows where two columns of one of the ragged `(group_ptr, group_cols)` groups meet.
+///
+/// Returns sorted `(row, first, other)` triples, `first` being the column of
+/// the group that reached the row before `other`; none means every group is
+/// structurally orthogonal. The groups need not come from this module.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    group_ptr, group_cols, row_ptr, col_idx, n_rows, n_cols, format="csr", validate="strict",
+))]
+fn orthogonality_violations(
+    py: Python<'_>,
+    group_ptr: IndexArray<'_>,
+    group_cols: IndexArray<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_rows: usize,
+    n_cols: usize,
+    format: &str,
+    validate: &str,
+) -> PyResult<Vec<(usize, usize, usize)>> {
+    let groups = with_index_slice!(&group_ptr, ptr => with_index_slice!(&group_cols, cols => {
+        csr_adjacency(
+            ptr,
+            cols,
+            ptr.len().saturating_sub(1),
+            ("group_ptr", "group_cols"),
+            Validation::Strict,
+        )?
+    }));
+    check_indices(&groups, n_cols, "group", "column")?;
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_rows,
+        n_cols,
+        format,
+        validation(validate)?,
+    )?;
+    Ok(py.detach(|| {
+        verify::orthogonality_violations(&bipartite::transpose(&rows, n_cols), &groups, n_rows)
+    }))
+}
+
+/// Pairs of DOFs within `distance` of each other that share a color.
+///
+/// Distance 2 follows `distance2_colors` (including `symmetrize` and
+/// rectangular patterns through `n_cols`); other distances follow
+/// `distance_k_color` on the symmetrized pattern. Pairs `(u, v)` have
+/// `u < v` and are sorted; none means the coloring is valid.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    colors, row_ptr, col_idx, n_dofs, distance=2, format="csr", symmetrize=false, n_cols=None,
+    validate="strict",
+))]
+fn coloring_conflicts(
+    py: Python<'_>,
+    colors: IndexArray<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    distance: usize,
+    format: &str,
+    symmetrize: bool,
+    n_cols: Option<usize>,
+    validate: &str,
+) -> PyResult<Vec<(usize, usize)>> {
+    use pyo3::exceptions::PyValueError;
+
+    let colors = color_ids(&colors)?;
+    let n_vertices = n_cols.unwrap_or(n_dofs);
+    if colors.len() != n_vertices {
+        return Err(PyValueError::new_err(format!(
+            "colors length must be {n_vertices}, got {}",
+            colors.len()
+        )));
+    }
+    match distance {
+        0 => Err(PyValueError::new_err("distance must be at least 1")),
+        2 => {
+            let rows = distance2_pattern(
+                py,
+                &row_ptr,
+                &col_idx,
+                n_dofs,
+                n_cols,
+                format,
+                symmetrize,
+                validation(validate)?,
+            )?;
+            Ok(py.detach(|| distance2_graph(rows, n_cols).conflicts(&colors, 1)))
+        }
+        _ if n_cols.is_some() => Err(PyValueError::new_err("n_cols requires distance 2")),
+        _ => {
+            let rows = pattern_rows(
+                &row_ptr,
+                &col_idx,
+                n_dofs,
+                n_dofs,
+                format,
+                validation(validate)?,
+            )?;
+            Ok(py.detach(|| {
+                let adjacency = symmetric_adjacency(&rows);
+                let adjacency = match distance {
+                    1 => adjacency,
+                    k => distance_k_adjacency(&adjacency, k),
+                };
+                verify::conflicts(&adjacency, &colors)
+            }))
+        }
+    }
+}
+
+/// Compute distance-1 colors for a CSR sparse matrix.
+///
+/// Only directly coupled DOFs must differ, so no 2-hop graph is built.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false,
+    priority=None, format="csr", block_size=1, colors_dtype="int32", validate="strict",
+))]
+fn distance1_color(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    priority: Option<PyReadonlyArray1<'_, f64>>,
+    format: &str,
+    block_size: usize,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<Py<PyAny>> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let mut options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+    options.priority = priority_values(priority, n_dofs)?.map(|p| block_maxima(&p, block_size));
+
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        n_blocks,
+        format,
+        validation(validate)?,
+    )?;
+    let colors = py.detach(|| {
+        // Symmetrize so that one-sided couplings still conflict.
+        let adjacency = symmetric_adjacency(&rows);
+        expand_blocks(&options.color(&adjacency), block_size)
+    });
+    colors::colors_to_py(py, &colors, colors_dtype)
+}
+
+/// Compute distance-k colors for a CSR sparse matrix.
+///
+/// DOFs joined by a path of at most `k` edges in the symmetrized pattern
+/// receive different colors.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, k, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false,
+    priority=None, format="csr", block_size=1, colors_dtype="int32", validate="strict",
+))]
+fn distance_k_color(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    k: usize,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    priority: Option<PyReadonlyArray1<'_, f64>>,
+    format: &str,
+    block_size: usize,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<Py<PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    if k == 0 {
+        return Err(PyValueError::new_err("k must be at least 1"));
+    }
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let mut options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+    options.priority = priority_values(priority, n_dofs)?.map(|p| block_maxima(&p, block_size));
+
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        n_blocks,
+        format,
+        validation(validate)?,
+    )?;
+    let colors = py.detach(|| {
+        let adjacency_k = distance_k_adjacency(&symmetric_adjacency(&rows), k);
+        expand_blocks(&options.color(&adjacency_k), block_size)
+    });
+    colors::colors_to_py(py, &colors, colors_dtype)
+}
+
+/// Star-color a symmetric sparse matrix and emit colors and seed vectors.
+///
+/// Star colorings are distance-1 colorings in which every path on four vertices
+/// uses at least three colors, which is enough for direct Hessian recovery.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, format="csr", symmetrize=false, block_size=1,
+    seed_format="list", seed_dtype="bool", rademacher_seed=None, return_seeds=true,
+    colors_dtype="int32", validate="strict",
+))]
+fn star_color_and_seeds(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    seed_format: &str,
+    seed_dtype: &str,
+    rademacher_seed: Option<u64>,
+    return_seeds: bool,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<ColorsAndSeeds> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let seed_options = seed_options(seed_format, seed_dtype, rademacher_seed)?;
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let adjacency = symmetric_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let colors = py.detach(|| expand_blocks(&star::star_color(&adjacency), block_size));
+
+    let colors_py = colors::colors_to_py(py, &colors, colors_dtype)?;
+    let seeds = return_seeds
+        .then(|| seeds::seeds_to_py(py, &colors, seed_options))
+        .transpose()?;
+
+    Ok((colors_py, seeds))
+}
+
+/// Restricted-star-color a symmetric sparse matrix and emit colors and seed vectors.
+///
+/// Every Hessian entry h_ij with color(i) > color(j) is read directly from
+/// row i of the product with seed color(j), without star bookkeeping.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, format="csr", symmetrize=false, block_size=1,
+    seed_format="list", seed_dtype="bool", rademacher_seed=None, return_seeds=true,
+    colors_dtype="int32", validate="strict",
+))]
+fn restricted_star_color_and_seeds(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    seed_format: &str,
+    seed_dtype: &str,
+    rademacher_seed: Option<u64>,
+    return_seeds: bool,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<ColorsAndSeeds> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let seed_options = seed_options(seed_format, seed_dtype, rademacher_seed)?;
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let adjacency = symmetric_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let colors = py.detach(|| expand_blocks(&star::restricted_star_color(&adjacency), block_size));
+
+    let colors_py = colors::colors_to_py(py, &colors, colors_dtype)?;
+    let seeds = return_seeds
+        .then(|| seeds::seeds_to_py(py, &colors, seed_options))
+        .transpose()?;
+
+    Ok((colors_py, seeds))
+}
+
+/// Acyclic-color a symmetric sparse matrix and emit colors and seed vectors.
+///
+/// Acyclic colorings need even fewer colors than star colorings; the Hessian
+/// is then recovered by substitution instead of directly.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_dofs, format="csr", symmetrize=false, block_size=1,
+    seed_format="list", seed_dtype="bool", rademacher_seed=None, return_seeds=true,
+    colors_dtype="int32", validate="strict",
+))]
+fn acyclic_color_and_seeds(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    format: &str,
+    symmetrize: bool,
+    block_size: usize,
+    seed_format: &str,
+    seed_dtype: &str,
+    rademacher_seed: Option<u64>,
+    return_seeds: bool,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<ColorsAndSeeds> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let seed_options = seed_options(seed_format, seed_dtype, rademacher_seed)?;
+    let n_blocks = block_count(n_dofs, block_size)?;
+    let adjacency = symmetric_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_blocks,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let colors = py.detach(|| expand_blocks(&acyclic::acyclic_color(&adjacency), block_size));
+
+    let colors_py = colors::colors_to_py(py, &colors, colors_dtype)?;
+    let seeds = return_seeds
+        .then(|| seeds::seeds_to_py(py, &colors, seed_options))
+        .transpose()?;
+
+    Ok((colors_py, seeds))
+}
+
+/// Color the columns of a rectangular CSR pattern and emit colors and seed vectors.
+///
+/// Columns sharing a nonzero row receive different colors (partial distance-2
+/// coloring of the bipartite row–column graph), so `J @ seed` compresses `J`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_rows, n_cols, format="csr", seed_format="list",
+    seed_dtype="bool", rademacher_seed=None, return_seeds=true,
+    colors_dtype="int32", validate="strict",
+))]
+fn partial_distance2_color_and_seeds(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_rows: usize,
+    n_cols: usize,
+    format: &str,
+    seed_format: &str,
+    seed_dtype: &str,
+    rademacher_seed: Option<u64>,
+    return_seeds: bool,
+    colors_dtype: &str,
+    validate: &str,
+) -> PyResult<ColorsAndSeeds> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let seed_options = seed_options(seed_format, seed_dtype, rademacher_seed)?;
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_rows,
+        n_cols,
+        format,
+        validation(validate)?,
+    )?;
+    let colors = py.detach(|| bipartite::partial_distance2_color(&rows, n_cols));
+
+    let colors_py = colors::colors_to_py(py, &colors, colors_dtype)?;
+    let seeds = return_seeds
+        .then(|| seeds::seeds_to_py(py, &colors, seed_options))
+        .transpose()?;
+
+    Ok((colors_py, seeds))
+}
+
+/// Bicolor a rectangular CSR pattern for combined forward and reverse compression.
+///
+/// Returns row and column colors as np.int32, -1 marking entries without a color.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_rows, n_cols, dense_row_threshold=None, format="csr", validate="strict",
+))]
+fn bicolor(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_rows: usize,
+    n_cols: usize,
+    dense_row_threshold: Option<usize>,
+    format: &str,
+    validate: &str,
+) -> PyResult<RowAndColumnColors> {
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_rows,
+        n_cols,
+        format,
+        validation(validate)?,
+    )?;
+    let (row_colors, col_colors) =
+        py.detach(|| bipartite::bicolor(&rows, n_cols, dense_row_threshold));
+
+    let to_py = |colors: &[usize]| -> PyResult<_> {
+        let colors = colors
+            .iter()
+            .map(|&c| match c {
+                usize::MAX => Ok(-1),
+                c => i32::try_from(c).map_err(|_| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "color {c} does not fit in int32"
+                    ))
+                }),
+            })
+            .collect::<PyResult<Vec<i32>>>()?;
+        Ok(PyArray1::from_vec(py, colors).unbind())
+    };
+    Ok((to_py(&row_colors)?, to_py(&col_colors)?))
+}
+
+/// Curtis–Powell–Reid grouping of the columns of a rectangular CSR pattern.
+///
+/// Builds the column intersection graph and colors it with the selected
+/// strategy; each returned group lists structurally orthogonal columns.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_rows, n_cols, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false,
+    priority=None, format="csr", validate="strict",
+))]
+fn cpr_column_groups(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_rows: usize,
+    n_cols: usize,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    priority: Option<PyReadonlyArray1<'_, f64>>,
+    format: &str,
+    validate: &str,
+) -> PyResult<Vec<Py<PyArray1<i64>>>> {
+    let mut options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+    options.priority = priority_values(priority, n_cols)?;
+
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_rows,
+        n_cols,
+        format,
+        validation(validate)?,
+    )?;
+    let colors =
+        py.detach(|| options.color(&bipartite::column_intersection_adjacency(&rows, n_cols)));
+    Ok(seeds::groups_from_colors(py, &colors))
+}
+
+/// Read a Matrix Market coordinate file and color its pattern.
+///
+/// Square matrices get a distance-2 coloring; rectangular ones a column
+/// coloring of the column intersection graph.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    path, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false,
+    colors_dtype="int32",
+))]
+fn color_matrix_market(
+    py: Python<'_>,
+    path: PathBuf,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    colors_dtype: &str,
+) -> PyResult<Py<PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+
+    let colors = py.detach(|| -> PyResult<_> {
+        let pattern = matrix_market::read_pattern(&path)?;
+        let n_cols = (pattern.n_rows != pattern.n_cols).then_some(pattern.n_cols);
+        options
+            .color_distance2(
+                &distance2_graph(pattern.rows, n_cols),
+                false,
+                &mut Timings::default(),
+                &progress::Silent,
+            )
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    })?;
+    colors::colors_to_py(py, &colors, colors_dtype)
+}
+
+/// Symbolic `AᵀA` (or `AAᵀ`) pattern of a general pattern `A` as `(indptr, indices)` int64 arrays.
+///
+/// `product` is `"AtA"` (columns sharing a row, `n_cols` square) or `"AAt"`
+/// (rows sharing a column, `n_rows` square). Rows are sorted and include the
+/// diagonal of every nonempty column (row).
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    row_ptr, col_idx, n_rows, n_cols, product="AtA", format="csr", validate="strict",
+))]
+fn normal_pattern(
+    py: Python<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_rows: usize,
+    n_cols: usize,
+    product: &str,
+    format: &str,
+    validate: &str,
+) -> PyResult<CsrArrays> {
+    let transposed = match product {
+        "AtA" => false,
+        "AAt" => true,
+        other => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown product '{other}', expected one of: AtA, AAt"
+            )));
+        }
+    };
+    let rows = pattern_rows(
+        &row_ptr,
+        &col_idx,
+        n_rows,
+        n_cols,
+        format,
+        validation(validate)?,
+    )?;
+    let pattern = py.detach(|| {
+        if transposed {
+            bipartite::normal_pattern(&bipartite::transpose(&rows, n_cols), n_rows)
+        } else {
+            bipartite::normal_pattern(&rows, n_cols)
+        }
+    });
+    Ok(csr_arrays(py, &pattern))
+}
+
+/// Assembled CSR sparsity of a mesh as `(indptr, indices)` int64 arrays.
+///
+/// Elements are ragged CSR lists (`elem_ptr`, `elem_nodes`) of DOFs; row `i`
+/// holds every DOF sharing an element with `i`, sorted and including `i`.
+/// `n_dofs` defaults to the largest DOF index + 1.
+#[pyfunction]
+#[pyo3(signature = (elem_ptr, elem_nodes, n_dofs=None))]
+fn pattern_from_elements(
+    py: Python<'_>,
+    elem_ptr: IndexArray<'_>,
+    elem_nodes: IndexArray<'_>,
+    n_dofs: Option<usize>,
+) -> PyResult<CsrArrays> {
+    let (elements, n_dofs) = element_lists(&elem_ptr, &elem_nodes, n_dofs)?;
+    let pattern = py.detach(|| mesh::element_pattern(&elements, n_dofs));
+    Ok(csr_arrays(py, &pattern))
+}
+
+/// Distance-2 color the DOFs of a mesh given by element-to-node connectivity.
+///
+/// Elements are ragged CSR lists (`elem_ptr`, `elem_nodes`); the global
+/// matrix is never formed. `n_nodes` defaults to the largest node index + 1.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    elem_ptr, elem_nodes, n_nodes=None, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false,
+    colors_dtype="int32",
+))]
+fn element_distance2_colors(
+    py: Python<'_>,
+    elem_ptr: IndexArray<'_>,
+    elem_nodes: IndexArray<'_>,
+    n_nodes: Option<usize>,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    colors_dtype: &str,
+) -> PyResult<Py<PyAny>> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+
+    let (elements, n_nodes) = element_lists(&elem_ptr, &elem_nodes, n_nodes)?;
+    let colors =
+        py.detach(|| options.color(&mesh::element_distance2_adjacency(&elements, n_nodes)));
+    colors::colors_to_py(py, &colors, colors_dtype)
+}
+
+/// Color mesh cells so that no two cells sharing a vertex get the same color.
+///
+/// Each color class can be assembled in parallel without atomics. Cells are
+/// ragged CSR lists (`cell_ptr`, `cell_vertices`).
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    cell_ptr, cell_vertices, n_vertices=None, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false,
+    colors_dtype="int32",
+))]
+fn cell_colors(
+    py: Python<'_>,
+    cell_ptr: IndexArray<'_>,
+    cell_vertices: IndexArray<'_>,
+    n_vertices: Option<usize>,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    colors_dtype: &str,
+) -> PyResult<Py<PyAny>> {
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    let options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+
+    let (cells, n_vertices) = element_lists(&cell_ptr, &cell_vertices, n_vertices)?;
+    let colors = py.detach(|| options.color(&mesh::cell_adjacency(&cells, n_vertices)));
+    colors::colors_to_py(py, &colors, colors_dtype)
+}
+
+/// Color a graph given as an edge list, for interaction pairs that never form a matrix.
+///
+/// `edges` holds the flattened `(n_edges, 2)` vertex pairs. Vertices within
+/// `distance` edges of each other receive different colors.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    edges, n_vertices=None, distance=1, ordering="natural", seed=0, refine=None,
+    algorithm="greedy", time_limit=10.0, tabu_iterations=10000, deterministic=false,
+    colors_dtype="int32",
+))]
+fn color_edge_list(
+    py: Python<'_>,
+    edges: IndexArray<'_>,
+    n_vertices: Option<usize>,
+    distance: usize,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    algorithm: &str,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+    colors_dtype: &str,
+) -> PyResult<Py<PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let colors_dtype = colors_dtype_value(colors_dtype)?;
+    if distance == 0 {
+        return Err(PyValueError::new_err("distance must be at least 1"));
+    }
+    let options = coloring_options(
+        algorithm,
+        ordering,
+        seed,
+        refine,
+        time_limit,
+        tabu_iterations,
+        deterministic,
+    )?;
+
+    let adjacency = with_index_slice!(&edges, edges => edge_adjacency(edges, n_vertices)?);
+    let colors = py.detach(|| {
+        let graph = if distance == 1 {
+            adjacency
+        } else {
+            distance_k_adjacency(&adjacency, distance)
+        };
+        options.color(&graph)
+    });
+    colors::colors_to_py(py, &colors, colors_dtype)
+}
+
+/// Permutation sorting DOFs by color, ties broken by index, and its inverse.
+///
+/// `perm[k]` is the DOF placed at position `k` and `inverse[perm[k]] == k`,
+/// so every color class occupies one contiguous block of the new numbering.
+#[pyfunction]
+fn color_permutation(py: Python<'_>, colors: IndexArray<'_>) -> PyResult<Permutation> {
+    let colors = color_ids(&colors)?;
+    let perm: Vec<usize> = greedy::color_classes(&colors).concat();
+    let mut inverse = vec![0i64; perm.len()];
+    for (k, &v) in perm.iter().enumerate() {
+        inverse[v] = k as i64;
+    }
+    Ok((
+        PyArray1::from_iter(py, perm.iter().map(|&v| v as i64)).unbind(),
+        PyArray1::from_vec(py, inverse).unbind(),
+    ))
+}
+
+/// Coloring in the layout of PETSc's `ISColoringCreate`: color count, colors and index sets.
+///
+/// `colors` come back as `ISColoringValue` (`color_dtype`, PETSc's default
+/// being `unsigned short`), and the DOFs of each of the `n_colors` colors as
+/// one sorted `PetscInt` array (`index_dtype`), shifted by `offset` into the
+/// global numbering of a parallel matrix. Colors without DOFs on this rank
+/// keep an empty index set, so every rank reports the same `n_colors`.
+#[pyfunction]
+#[pyo3(signature = (colors, n_colors=None, offset=0, color_dtype="uint16", index_dtype="int32"))]
+fn petsc_coloring(
+    py: Python<'_>,
+    colors: IndexArray<'_>,
+    n_colors: Option<usize>,
+    offset: usize,
+    color_dtype: &str,
+    index_dtype: &str,
+) -> PyResult<PetscColoring> {
+    use pyo3::exceptions::PyValueError;
+
+    let color_dtype = colors_dtype_value(color_dtype)?;
+    let wide = match index_dtype {
+        "int32" => false,
+        "int64" => true,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unknown index_dtype '{index_dtype}', expected one of: int32, int64"
+            )));
+        }
+    };
+    let colors = color_ids(&colors)?;
+    let used = greedy::color_count(&colors);
+    let n_colors = n_colors.unwrap_or(used);
+    if used > n_colors {
+        return Err(PyValueError::new_err(format!(
+            "color {} out of range for {n_colors} colors",
+            used - 1
+        )));
+    }
+    let mut classes = greedy::color_classes(&colors);
+    classes.resize(n_colors, Vec::new());
+    let index_sets = classes
+        .iter()
+        .map(|class| -> PyResult<Py<PyAny>> {
+            let indices = class.iter().map(|&v| v + offset);
+            if wide {
+                return Ok(PyArray1::from_iter(py, indices.map(|i| i as i64))
+                    .into_any()
+                    .unbind());
+            }
+            let set = indices
+                .map(|i| {
+                    i32::try_from(i).map_err(|_| {
+                        PyValueError::new_err(format!("index {i} does not fit in int32"))
+                    })
+                })
+                .collect::<PyResult<Vec<i32>>>()?;
+            Ok(PyArray1::from_vec(py, set).into_any().unbind())
+        })
+        .collect::<PyResult<_>>()?;
+    Ok((
+        n_colors,
+        colors::colors_to_py(py, &colors, color_dtype)?,
+        index_sets,
+    ))
+}
+
+/// Position of every stored pattern entry in the compressed product `J @ S`.
+///
+/// Entries are taken in storage order, so the returned flat indices into the
+/// row-major `(n_rows, n_colors)` product gather the CSR/CSC/COO `data` array.
+#[pyfunction]
+#[pyo3(signature = (colors, row_ptr, col_idx, format="csr"))]
+fn recovery_map(
+    py: Python<'_>,
+    colors: IndexArray<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    format: &str,
+) -> PyResult<Py<PyArray1<i64>>> {
+    let colors = color_ids(&colors)?;
+    let entries = stored_entries(&row_ptr, &col_idx, format)?;
+    let positions = recovery::direct_positions(&entries, &colors)
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(PyArray1::from_iter(py, positions.into_iter().map(|p| p as i64)).unbind())
+}
+
+/// Scatter a column-compressed Jacobian product back into the sparse `data` array.
+///
+/// Returns the value of every stored entry in storage order, in the dtype of
+/// `compressed`.
+#[pyfunction]
+#[pyo3(signature = (compressed, colors, row_ptr, col_idx, format="csr"))]
+fn decompress_jacobian(
+    py: Python<'_>,
+    compressed: CompressedProduct<'_>,
+    colors: IndexArray<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    format: &str,
+) -> PyResult<Py<PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let colors = color_ids(&colors)?;
+    let entries = stored_entries(&row_ptr, &col_idx, format)?;
+    let positions = recovery::direct_positions(&entries, &colors).map_err(PyValueError::new_err)?;
+    let n_colors = greedy::color_count(&colors);
+    match compressed {
+        CompressedProduct::F64(array) => gathered(py, &array, n_colors, &positions),
+        CompressedProduct::F32(array) => gathered(py, &array, n_colors, &positions),
+    }
+}
+
+/// Recover a symmetric Hessian from its compressed product `H @ S`.
+///
+/// `colors` must be a star coloring for `method="direct"` or an acyclic
+/// coloring for `method="substitution"`. Returns the value of every stored
+/// entry in storage order, in the dtype of `compressed`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (
+    compressed, colors, row_ptr, col_idx, n_dofs, method="direct", format="csr", symmetrize=false,
+    validate="strict",
+))]
+fn recover_hessian(
+    py: Python<'_>,
+    compressed: CompressedProduct<'_>,
+    colors: IndexArray<'_>,
+    row_ptr: IndexArray<'_>,
+    col_idx: IndexArray<'_>,
+    n_dofs: usize,
+    method: &str,
+    format: &str,
+    symmetrize: bool,
+    validate: &str,
+) -> PyResult<Py<PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let method = HessianRecovery::parse(method).map_err(PyValueError::new_err)?;
+    let colors = color_ids(&colors)?;
+    if colors.len() != n_dofs {
+        return Err(PyValueError::new_err(format!(
+            "colors length must be {n_dofs}, got {}",
+            colors.len()
+        )));
+    }
+    // With `symmetrize`, entries of either triangle are recovered, so half
+    // storage works as well.
+    let adjacency = symmetric_pattern(
+        py,
+        &row_ptr,
+        &col_idx,
+        n_dofs,
+        format,
+        symmetrize,
+        validation(validate)?,
+    )?;
+    let entries = stored_entries(&row_ptr, &col_idx, format)?;
+    match compressed {
+        CompressedProduct::F64(array) => {
+            hessian_data(py, &array, &adjacency, &colors, method, &entries)
+        }
+        CompressedProduct::F32(array) => {
+            hessian_data(py, &array, &adjacency, &colors, method, &entries)
+        }
+    }
+}
+
+/// Internal distance-2 coloring from CSR, CSC or COO storage.
+///
+/// Square patterns color the distance-2 graph of the DOFs. With `n_cols`
+/// the pattern is rectangular and its columns are colored through the column
+/// intersection graph (partial distance-2 coloring of the bipartite graph).
+/// BSR blocks, nodes of `dofs_per_node` DOFs and the DOF groups of `ties` are
+/// colored as one vertex each (see `blocked_graph`). The conflict graph is
+/// only materialized when the strategy needs it, and never with
+/// `low_memory`. Returns the DOF colors, plus statistics of the colored
+/// conflict graph if `return_stats` is set. The time of each stage is added
+/// to `timings`; the coloring phases, and with verbose progress the
+/// strategy, graph size and color count, are reported to `progress`.
+#[allow(clippy::too_many_arguments)]
+fn distance2_colors_impl(
+    py: Python<'_>,
+    row_ptr: &IndexArray<'_>,
+    col_idx: &IndexArray<'_>,
+    n_dofs: usize,
+    n_cols: Option<usize>,
+    format: &str,
+    symmetrize: bool,
+    validation: Validation,
+    block_size: usize,
+    dofs_per_node: usize,
+    ties: Option<&[usize]>,
+    supervariables: bool,
+    options: &ColoringOptions,
+    low_memory: bool,
+    return_stats: bool,
+    timings: &mut Timings,
+    progress: &PyProgress,
+) -> PyResult<(Vec<usize>, Option<ColoringStats>)> {
+    let constrained =
+        options.precolors.is_some() || options.allowed.is_some() || options.max_colors.is_some();
+    if supervariables && constrained {
         return Err(pyo3::exceptions::PyValueError::new_err(
-            "row_ptr length must be n_dofs + 1",
+            "supervariables cannot be combined with precolors, allowed_colors or max_colors",
+        ));
+    }
+    let (graph, dofs_per_vertex) = timings.time("adjacency", || {
+        blocked_graph(
+            py,
+            row_ptr,
+            col_idx,
+            n_dofs,
+            n_cols,
+            format,
+            symmetrize,
+            validation,
+            block_size,
+            dofs_per_node,
+            ties,
+        )
+    })?;
+    let dofs = match ties {
+        Some(groups) => VertexDofs::Tied(groups),
+        None => VertexDofs::Blocks(dofs_per_vertex),
+    };
+    if !supervariables {
+        return color_distance2_graph(
+            py,
+            &graph,
+            None,
+            dofs,
+            options,
+            low_memory,
+            return_stats,
+            timings,
+            progress,
+        );
+    }
+
+    let (groups, condensed) = timings.time("adjacency", || {
+        py.detach(|| {
+            let groups = graph.supervariables();
+            let condensed = graph.condensed(&groups);
+            (groups, condensed)
+        })
+    });
+    progress.log(
+        py,
+        &format!(
+            "merged {} vertices into {} supervariables",
+            graph.n_vertices(),
+            condensed.n_vertices()
+        ),
+    )?;
+    let options = ColoringOptions {
+        priority: (options.priority.as_ref()).map(|p| VertexDofs::Tied(&groups).priority(p)),
+        precolors: None,
+        allowed: None,
+        ..*options
+    };
+    color_distance2_graph(
+        py,
+        &condensed,
+        Some(&groups),
+        dofs,
+        &options,
+        low_memory,
+        return_stats,
+        timings,
+        progress,
+    )
+}
+
+/// DOFs each colored vertex stands for: `block_size` for BSR input, else `dofs_per_node`.
+fn dofs_per_vertex(block_size: usize, dofs_per_node: usize) -> PyResult<usize> {
+    use pyo3::exceptions::PyValueError;
+    if dofs_per_node == 0 {
+        return Err(PyValueError::new_err("dofs_per_node must be positive"));
+    }
+    if block_size > 1 && dofs_per_node > 1 {
+        return Err(PyValueError::new_err(
+            "block_size and dofs_per_node cannot both exceed 1",
         ));
     }
+    Ok(block_size * dofs_per_node)
+}
 
-    // Build adjacency from CSR.
-    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n_dofs];
-    for i in 0..n_dofs {
-        let start = row_ptr[i] as usize;
-        let end = row_ptr[i + 1] as usize;
-        let slice = &col_idx[start..end];
-        adjacency[i].extend(slice.iter().map(|&v| v as usize));
+/// Distance-2 graph of a pattern over its BSR blocks, nodes or tie groups, and the DOFs per vertex.
+///
+/// BSR input is stored by blocks already. Scalar input with `dofs_per_node`
+/// DOFs per node, DOF `i * d + r` being component `r` of node `i`, is merged
+/// into the nodal pattern after parsing: nodes are adjacent when any of
+/// their DOFs are. Its graph is `d * d` times smaller, and since the DOFs of
+/// a node are coupled anyway, the expanded node colors are a coloring of
+/// the DOFs. DOFs tied by constraints are merged the same way, `ties`
+/// giving the group of every DOF. Only the columns are merged for a
+/// rectangular pattern.
+#[allow(clippy::too_many_arguments)]
+fn blocked_graph(
+    py: Python<'_>,
+    row_ptr: &IndexArray<'_>,
+    col_idx: &IndexArray<'_>,
+    n_dofs: usize,
+    n_cols: Option<usize>,
+    format: &str,
+    symmetrize: bool,
+    validation: Validation,
+    block_size: usize,
+    dofs_per_node: usize,
+    ties: Option<&[usize]>,
+) -> PyResult<(Distance2Graph, usize)> {
+    let dofs_per_vertex = dofs_per_vertex(block_size, dofs_per_node)?;
+    let n_rows = block_count(n_dofs, block_size)?;
+    let n_vertex_cols = n_cols.map(|n| block_count(n, block_size)).transpose()?;
+    let vertices = n_vertex_cols.unwrap_or(n_rows);
+    if !vertices.is_multiple_of(dofs_per_node) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "dofs_per_node must divide the {vertices} colored DOFs"
+        )));
     }
+    let rows = distance2_pattern(
+        py,
+        row_ptr,
+        col_idx,
+        n_rows,
+        n_vertex_cols,
+        format,
+        symmetrize,
+        validation,
+    )?;
+    let graph = py.detach(|| {
+        let nodes: Vec<usize>;
+        let groups = match ties {
+            Some(groups) => groups,
+            None if dofs_per_node > 1 => {
+                nodes = (0..vertices).map(|v| v / dofs_per_node).collect();
+                &nodes
+            }
+            None => return distance2_graph(rows, n_vertex_cols),
+        };
+        let n_groups = groups.iter().max().map_or(0, |&g| g + 1);
+        let merged = constraints::merge_groups(&rows, groups, n_vertex_cols.is_none());
+        distance2_graph(merged, n_vertex_cols.map(|_| n_groups))
+    });
+    Ok((graph, dofs_per_vertex))
+}
 
-    // Build distance-2 adjacency (neighbors and neighbors-of-neighbors).
-    let adjacency2 = distance2_adjacency(&adjacency);
+/// Tie group of each of the `n_vertices` colored DOFs, from the ragged `(tie_ptr, tie_dofs)` lists.
+fn tie_groups(
+    tie_ptr: Option<IndexArray<'_>>,
+    tie_dofs: Option<IndexArray<'_>>,
+    n_vertices: usize,
+    dofs_per_vertex: usize,
+) -> PyResult<Option<Vec<usize>>> {
+    use pyo3::exceptions::PyValueError;
+    let (tie_ptr, tie_dofs) = match (tie_ptr, tie_dofs) {
+        (None, None) => return Ok(None),
+        (Some(ptr), Some(dofs)) => (ptr, dofs),
+        _ => {
+            return Err(PyValueError::new_err(
+                "tie_ptr and tie_dofs must be given together",
+            ));
+        }
+    };
+    if dofs_per_vertex > 1 {
+        return Err(PyValueError::new_err(
+            "ties cannot be combined with block_size or dofs_per_node",
+        ));
+    }
+    let ties = with_index_slice!(&tie_ptr, ptr => with_index_slice!(&tie_dofs, dofs => {
+        csr_adjacency(
+            ptr,
+            dofs,
+            ptr.len().saturating_sub(1),
+            ("tie_ptr", "tie_dofs"),
+            Validation::Strict,
+        )?
+    }));
+    check_indices(&ties, n_vertices, "tie", "DOF")?;
+    Ok(Some(constraints::tie_groups(n_vertices, &ties)))
+}
 
-    // Greedy coloring on distance-2 adjacency.
-    Ok(greedy_color(&adjacency2))
+/// DOFs behind each vertex of a colored graph.
+#[derive(Clone, Copy, Debug)]
+enum VertexDofs<'a> {
+    /// DOFs `i * b..(i + 1) * b` for vertex `i`: BSR blocks or nodes of `b` DOFs.
+    Blocks(usize),
+    /// `groups[dof]` is the vertex of every DOF, as from `tie_groups`.
+    Tied(&'a [usize]),
 }
 
-/// Compute distance-2 adjacency from 1-hop adjacency.
-fn distance2_adjacency(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
-    let n = adjacency.len();
-    let mut adj2: Vec<AHashSet<usize>> = vec![AHashSet::new(); n];
+impl VertexDofs<'_> {
+    /// DOF colors from vertex colors.
+    fn expand(self, colors: &[usize]) -> Vec<usize> {
+        match self {
+            Self::Blocks(block_size) => expand_blocks(colors, block_size),
+            Self::Tied(groups) => groups.iter().map(|&g| colors[g]).collect(),
+        }
+    }
 
-    for (i, neighs) in adjacency.iter().enumerate() {
-        for &j in neighs {
-            if i != j {
-                adj2[i].insert(j);
-                adj2[j].insert(i);
+    /// Per-vertex priorities from per-DOF ones: the maximum over the vertex's DOFs.
+    fn priority(self, priority: &[f64]) -> Vec<f64> {
+        match self {
+            Self::Blocks(block_size) => block_maxima(priority, block_size),
+            Self::Tied(groups) => {
+                let n_groups = groups.iter().max().map_or(0, |&g| g + 1);
+                let mut maxima = vec![f64::NEG_INFINITY; n_groups];
+                for (&g, &p) in groups.iter().zip(priority) {
+                    maxima[g] = maxima[g].max(p);
+                }
+                maxima
+            }
+        }
+    }
+
+    /// Vertex colors from DOF colors, `usize::MAX` marking free DOFs: the inverse of `expand`.
+    ///
+    /// Fails unless the fixed DOFs of every vertex agree on one vertex color.
+    fn vertex_colors(self, colors: &[usize]) -> Result<Vec<usize>, String> {
+        match self {
+            Self::Blocks(block_size) => colors
+                .chunks(block_size)
+                .enumerate()
+                .map(|(i, block)| {
+                    let fixed = block.iter().find(|&&c| c != usize::MAX);
+                    let color = fixed.map_or(usize::MAX, |&c| c / block_size);
+                    let fits =
+                        |(r, &c): (usize, &usize)| c == usize::MAX || c == color * block_size + r;
+                    if block.iter().enumerate().all(fits) {
+                        Ok(color)
+                    } else {
+                        Err(format!(
+                            "precolors of DOFs {}..{} do not expand from one block color, \
+                             DOF r of a block colored c needing color c * {block_size} + r",
+                            i * block_size,
+                            (i + 1) * block_size
+                        ))
+                    }
+                })
+                .collect(),
+            Self::Tied(groups) => {
+                let n_groups = groups.iter().max().map_or(0, |&g| g + 1);
+                let mut vertex_colors = vec![usize::MAX; n_groups];
+                for (dof, (&g, &c)) in groups.iter().zip(colors).enumerate() {
+                    match vertex_colors[g] {
+                        _ if c == usize::MAX => {}
+                        usize::MAX => vertex_colors[g] = c,
+                        tied if tied == c => {}
+                        tied => {
+                            return Err(format!(
+                                "DOF {dof} is precolored {c}, but a DOF tied to it {tied}"
+                            ));
+                        }
+                    }
+                }
+                Ok(vertex_colors)
             }
-            for &k in &adjacency[j] {
-                if k != i {
-                    adj2[i].insert(k);
-                    adj2[k].insert(i);
+        }
+    }
+
+    /// Vertex masks of the colors allowed for every DOF: those expanding to allowed DOF colors.
+    fn allowed(self, masks: &[u64]) -> Vec<u64> {
+        match self {
+            Self::Blocks(block_size) => masks
+                .chunks(block_size)
+                .map(|block| {
+                    let allows = |c: usize| {
+                        block.iter().enumerate().all(|(r, &mask)| {
+                            let dof_color = c * block_size + r;
+                            dof_color < u64::BITS as usize && mask >> dof_color & 1 == 1
+                        })
+                    };
+                    (0..u64::BITS as usize)
+                        .filter(|&c| allows(c))
+                        .fold(0, |vertex_mask, c| vertex_mask | 1 << c)
+                })
+                .collect(),
+            Self::Tied(groups) => {
+                let n_groups = groups.iter().max().map_or(0, |&g| g + 1);
+                let mut vertex_masks = vec![u64::MAX; n_groups];
+                for (&g, &mask) in groups.iter().zip(masks) {
+                    vertex_masks[g] &= mask;
                 }
+                vertex_masks
             }
         }
     }
 
-    adj2.into_iter()
-        .map(|set| {
-            let mut v: Vec<usize> = set.into_iter().collect();
-            v.sort_unstable();
-            v
+    /// First DOF of vertex `v`.
+    fn first_dof(self, v: usize) -> usize {
+        match self {
+            Self::Blocks(block_size) => v * block_size,
+            Self::Tied(groups) => groups
+                .iter()
+                .position(|&g| g == v)
+                .expect("every tie group has a DOF"),
+        }
+    }
+}
+
+/// Color a distance-2 graph of blocks or tie groups and expand the colors to DOFs.
+///
+/// A graph condensed to `supervariable_groups` has its colors expanded to
+/// the vertices first. Returns statistics of the colored graph as well if
+/// `return_stats` is set.
+#[allow(clippy::too_many_arguments)]
+fn color_distance2_graph(
+    py: Python<'_>,
+    graph: &Distance2Graph,
+    supervariable_groups: Option<&[usize]>,
+    dofs: VertexDofs<'_>,
+    options: &ColoringOptions,
+    low_memory: bool,
+    return_stats: bool,
+    timings: &mut Timings,
+    progress: &PyProgress,
+) -> PyResult<(Vec<usize>, Option<ColoringStats>)> {
+    let vertices = match dofs {
+        VertexDofs::Blocks(block_size) => format!("block size {block_size}"),
+        VertexDofs::Tied(groups) => format!("{} tied DOFs", groups.len()),
+    };
+    progress.log(
+        py,
+        &format!(
+            "coloring {} vertices of {vertices}: {:?}, {:?} ordering, refine {:?}",
+            graph.n_vertices(),
+            options.algorithm,
+            options.ordering,
+            options.refine,
+        ),
+    )?;
+    if let Some(precolors) = &options.precolors {
+        // Free vertices get distinct colors above every fixed one, so only
+        // fixed vertices can clash.
+        let tagged: Vec<usize> = precolors
+            .iter()
+            .enumerate()
+            .map(|(v, &c)| if c == usize::MAX { usize::MAX - v } else { c })
+            .collect();
+        if let Some(&(u, v)) = py.detach(|| graph.conflicts(&tagged, 1)).first() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "precolored DOFs {} and {} share a color within distance 2",
+                dofs.first_dof(u),
+                dofs.first_dof(v)
+            )));
+        }
+    }
+    let (colors, stats) = progress
+        .run(py, || {
+            let colors = options.color_distance2(graph, low_memory, timings, progress)?;
+            let colors = match supervariable_groups {
+                Some(groups) => supervariables::expand(&colors, groups),
+                None => colors,
+            };
+            let colors = dofs.expand(&colors);
+            let stats = return_stats.then(|| {
+                timings.time("stats", || {
+                    let degrees = dof_degrees(graph, supervariable_groups, dofs, colors.len());
+                    ColoringStats::new(&colors, &degrees)
+                })
+            });
+            Ok((colors, stats))
+        })?
+        .map_err(callback::distance2_error)?;
+    if let Some(degrees) = graph.counted_degrees() {
+        let max_degree = degrees.iter().copied().max().unwrap_or(0);
+        let entries: usize = degrees.iter().sum();
+        progress.log(
+            py,
+            &format!("distance-2 graph: {entries} entries, max degree {max_degree}"),
+        )?;
+    }
+    let n_colors = greedy::color_count(&colors);
+    progress.log(
+        py,
+        &format!("colored {} DOFs with {n_colors} colors", colors.len()),
+    )?;
+    Ok((colors, stats))
+}
+
+/// Distance-2 degree of each of `n_dofs` DOFs behind the condensed `graph`.
+///
+/// A DOF conflicts with every DOF of the neighbors of its vertex, with the
+/// other vertices of its supervariable and with the other DOFs of its block;
+/// DOFs tied into one vertex share its color and do not conflict.
+fn dof_degrees(
+    graph: &Distance2Graph,
+    supervariable_groups: Option<&[usize]>,
+    dofs: VertexDofs<'_>,
+    n_dofs: usize,
+) -> Vec<usize> {
+    let n_vertices = supervariable_groups.map_or(graph.n_vertices(), <[_]>::len);
+    let (dof_vertex, block_mates): (Vec<usize>, usize) = match dofs {
+        VertexDofs::Blocks(block_size) => ((0..n_dofs).map(|u| u / block_size).collect(), 1),
+        VertexDofs::Tied(groups) => (groups.to_vec(), 0),
+    };
+    let mut vertex_dofs = vec![0; n_vertices];
+    for &v in &dof_vertex {
+        vertex_dofs[v] += 1;
+    }
+    let condensed = |v: usize| supervariable_groups.map_or(v, |groups| groups[v]);
+    let mut condensed_dofs = vec![0; graph.n_vertices()];
+    for (v, &count) in vertex_dofs.iter().enumerate() {
+        condensed_dofs[condensed(v)] += count;
+    }
+    let outside = graph.weighted_degrees(&condensed_dofs);
+    dof_vertex
+        .iter()
+        .map(|&v| {
+            let c = condensed(v);
+            let own_block = block_mates * (vertex_dofs[v] - 1);
+            outside[c] + condensed_dofs[c] - vertex_dofs[v] + own_block
         })
         .collect()
 }
 
-/// Simple greedy coloring: smallest available color per vertex.
-fn greedy_color(adjacency: &[Vec<usize>]) -> Vec<usize> {
-    let n = adjacency.len();
-    let mut colors = vec![usize::MAX; n];
-    let mut used: AHashMap<usize, usize> = AHashMap::new();
-
-    for i in 0..n {
-        used.clear();
-        for &nb in &adjacency[i] {
-            let Some(&c) = colors.get(nb) else { continue };
-            if c != usize::MAX {
-                *used.entry(c).or_insert(0) += 1;
+/// Block pattern rows of a distance-2 coloring, checked and mirrored with `symmetrize`.
+#[allow(clippy::too_many_arguments)]
+fn distance2_pattern(
+    py: Python<'_>,
+    row_ptr: &IndexArray<'_>,
+    col_idx: &IndexArray<'_>,
+    n_blocks: usize,
+    n_block_cols: Option<usize>,
+    format: &str,
+    symmetrize: bool,
+    validation: Validation,
+) -> PyResult<Adjacency> {
+    use pyo3::exceptions::PyValueError;
+
+    let n_cols = n_block_cols.unwrap_or(n_blocks);
+    let rows = pattern_rows(row_ptr, col_idx, n_blocks, n_cols, format, validation)?;
+
+    if !symmetrize {
+        return Ok(rows);
+    }
+    if n_block_cols.is_some() {
+        return Err(PyValueError::new_err(
+            "symmetrize requires a square pattern",
+        ));
+    }
+    // Half storage: mirror the stored triangle so both directions count.
+    Ok(py.detach(|| symmetric_adjacency(&rows)))
+}
+
+/// Mirrored block pattern rows of a coloring or recovery that assumes a symmetric pattern.
+///
+/// Strict validation rejects an entry without its mirror unless `symmetrize`
+/// declares half storage, so a partly assembled pattern is not colored as if
+/// it were complete.
+#[allow(clippy::too_many_arguments)]
+fn symmetric_pattern(
+    py: Python<'_>,
+    row_ptr: &IndexArray<'_>,
+    col_idx: &IndexArray<'_>,
+    n_blocks: usize,
+    format: &str,
+    symmetrize: bool,
+    validation: Validation,
+) -> PyResult<Adjacency> {
+    use pyo3::exceptions::PyValueError;
+
+    let rows = pattern_rows(row_ptr, col_idx, n_blocks, n_blocks, format, validation)?;
+    if validation == Validation::Strict
+        && !symmetrize
+        && let Some((i, j)) = py.detach(|| verify::asymmetric_entry(&rows))
+    {
+        return Err(PyValueError::new_err(format!(
+            "pattern is not structurally symmetric: entry ({i}, {j}) has no ({j}, {i}); \
+             pass symmetrize=True for half storage"
+        )));
+    }
+    Ok(py.detach(|| symmetric_adjacency(&rows)))
+}
+
+/// Conflict graph of `distance2_colors_impl`: DOFs of a square pattern, or columns.
+fn distance2_graph(rows: Adjacency, n_block_cols: Option<usize>) -> Distance2Graph {
+    match n_block_cols {
+        Some(n_cols) => Distance2Graph::columns(rows, n_cols),
+        None => Distance2Graph::new(rows),
+    }
+}
+
+/// `{stage: seconds}` dict of `timings` in stage order.
+fn timings_dict(py: Python<'_>, timings: &Timings) -> PyResult<Py<PyDict>> {
+    use pyo3::types::IntoPyDict;
+
+    let stages = timings.stages().iter();
+    Ok(stages
+        .map(|&(stage, elapsed)| (stage, elapsed.as_secs_f64()))
+        .into_py_dict(py)?
+        .unbind())
+}
+
+/// Parse the strategy keyword arguments into `ColoringOptions`.
+fn coloring_options(
+    algorithm: &str,
+    ordering: &str,
+    seed: u64,
+    refine: Option<&str>,
+    time_limit: f64,
+    tabu_iterations: usize,
+    deterministic: bool,
+) -> PyResult<ColoringOptions> {
+    use pyo3::exceptions::PyValueError;
+    Ok(ColoringOptions {
+        algorithm: Algorithm::parse(algorithm).map_err(PyValueError::new_err)?,
+        ordering: VertexOrdering::parse(ordering, seed).map_err(PyValueError::new_err)?,
+        seed,
+        refine: refine
+            .map(|name| Refinement::parse(name, tabu_iterations, seed))
+            .transpose()
+            .map_err(PyValueError::new_err)?,
+        time_limit: Duration::try_from_secs_f64(time_limit).map_err(|_| {
+            PyValueError::new_err("time_limit must be a non-negative number of seconds")
+        })?,
+        deterministic,
+        priority: None,
+        restarts: 1,
+        max_colors: None,
+        precolors: None,
+        allowed: None,
+    })
+}
+
+/// Set the greedy `restarts` of `options`, checking that the algorithm supports them.
+fn set_restarts(options: &mut ColoringOptions, restarts: usize) -> PyResult<()> {
+    use pyo3::exceptions::PyValueError;
+    if restarts == 0 {
+        return Err(PyValueError::new_err("restarts must be positive"));
+    }
+    if restarts > 1 && options.algorithm != Algorithm::Greedy {
+        return Err(PyValueError::new_err(
+            "restarts only apply to algorithm='greedy'",
+        ));
+    }
+    options.restarts = restarts;
+    Ok(())
+}
+
+/// Set the `max_colors` DOF colors `options` escalates towards, over vertices spanning `dofs`.
+///
+/// Blocks hold the vertex budget to `max_colors / block_size`, rounding
+/// down, and a budget below one block is rejected up front.
+fn set_max_colors(
+    options: &mut ColoringOptions,
+    max_colors: Option<usize>,
+    dofs: VertexDofs<'_>,
+) -> PyResult<()> {
+    use pyo3::exceptions::PyValueError;
+    if max_colors == Some(0) {
+        return Err(PyValueError::new_err("max_colors must be positive"));
+    }
+    // Every block takes `block_size` consecutive colors, so a smaller budget
+    // cannot be met by any strategy.
+    if let (Some(k), VertexDofs::Blocks(block_size)) = (max_colors, dofs)
+        && k < block_size
+    {
+        return Err(PyValueError::new_err(format!(
+            "max_colors={k} is below the {block_size} DOFs of a block (block_size times \
+             dofs_per_node), which all need their own color"
+        )));
+    }
+    options.max_colors = max_colors.map(|k| match dofs {
+        VertexDofs::Blocks(block_size) => k / block_size,
+        VertexDofs::Tied(_) => k,
+    });
+    Ok(())
+}
+
+/// Set the `precolors` of `options` from per-DOF colors of length `n_dofs`, -1 marking free DOFs.
+fn set_precolors(
+    options: &mut ColoringOptions,
+    precolors: Option<PyReadonlyArray1<'_, i64>>,
+    n_dofs: usize,
+    dofs: VertexDofs<'_>,
+) -> PyResult<()> {
+    use pyo3::exceptions::PyValueError;
+    let Some(precolors) = precolors else {
+        return Ok(());
+    };
+    let precolors = precolors.as_slice()?;
+    if precolors.len() != n_dofs {
+        return Err(PyValueError::new_err(format!(
+            "precolors length must be {n_dofs}, got {}",
+            precolors.len()
+        )));
+    }
+    let precolors = precolors
+        .iter()
+        .map(|&c| match c {
+            -1 => Ok(usize::MAX),
+            c => checked_index(c),
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let precolors = dofs
+        .vertex_colors(&precolors)
+        .map_err(PyValueError::new_err)?;
+    options.precolors = Some(precolors);
+    Ok(())
+}
+
+/// Set the `allowed` colors of `options` from per-DOF bitmasks of length `n_dofs`.
+fn set_allowed(
+    options: &mut ColoringOptions,
+    allowed_colors: Option<PyReadonlyArray1<'_, u64>>,
+    n_dofs: usize,
+    dofs: VertexDofs<'_>,
+) -> PyResult<()> {
+    let Some(allowed_colors) = allowed_colors else {
+        return Ok(());
+    };
+    let masks = allowed_colors.as_slice()?;
+    if masks.len() != n_dofs {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "allowed_colors length must be {n_dofs}, got {}",
+            masks.len()
+        )));
+    }
+    options.allowed = Some(dofs.allowed(masks));
+    Ok(())
+}
+
+/// Fail if `colors` exceeds `max_colors` and the caller requires it.
+fn check_max_colors(colors: &[usize], max_colors: Option<usize>, require: bool) -> PyResult<()> {
+    let n_colors = greedy::color_count(colors);
+    match max_colors {
+        Some(max_colors) if require && n_colors > max_colors => {
+            Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "no strategy reached max_colors={max_colors}, \
+                 the best coloring has {n_colors} colors"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parse the `colors_dtype` argument of the coloring entry points.
+fn colors_dtype_value(name: &str) -> PyResult<ColorDtype> {
+    ColorDtype::parse(name).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Parse the `validate` argument of the pattern entry points.
+fn validation(name: &str) -> PyResult<Validation> {
+    Validation::parse(name).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Parse the seed arguments of the `*_and_seeds` entry points.
+fn seed_options(format: &str, dtype: &str, rademacher_seed: Option<u64>) -> PyResult<SeedOptions> {
+    SeedOptions::parse(format, dtype, rademacher_seed)
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// `(row, column)` of every stored entry in storage order, duplicates included.
+fn stored_entries(
+    ptr: &IndexArray<'_>,
+    idx: &IndexArray<'_>,
+    format: &str,
+) -> PyResult<Vec<(usize, usize)>> {
+    with_index_slice!(ptr, ptr => with_index_slice!(idx, idx => {
+        typed_stored_entries(ptr, idx, format)
+    }))
+}
+
+fn typed_stored_entries<P: IndexValue, I: IndexValue>(
+    ptr: &[P],
+    idx: &[I],
+    format: &str,
+) -> PyResult<Vec<(usize, usize)>> {
+    use pyo3::exceptions::PyValueError;
+    match format {
+        "csr" | "csc" => {
+            // CSR rows and CSC columns both keep their storage order here.
+            let lists = csr_adjacency(
+                ptr,
+                idx,
+                ptr.len().saturating_sub(1),
+                ("row_ptr", "col_idx"),
+                Validation::Strict,
+            )?;
+            let entries = lists
+                .iter()
+                .enumerate()
+                .flat_map(|(a, bs)| bs.iter().map(move |&b| (a, b)));
+            Ok(if format == "csr" {
+                entries.collect()
+            } else {
+                entries.map(|(j, i)| (i, j)).collect()
+            })
+        }
+        "coo" => {
+            if ptr.len() != idx.len() {
+                return Err(PyValueError::new_err(
+                    "COO row and column index arrays must have equal length",
+                ));
+            }
+            ptr.iter()
+                .zip(idx)
+                .map(|(&i, &j)| Ok((checked_index(i)?, checked_index(j)?)))
+                .collect()
+        }
+        _ => Err(PyValueError::new_err(format!(
+            "unknown format '{format}', expected one of: csr, csc, coo"
+        ))),
+    }
+}
+
+/// Entries of a compressed product at flat `positions`, after checking its width.
+fn gathered<T: Element + Copy>(
+    py: Python<'_>,
+    compressed: &PyReadonlyArray2<'_, T>,
+    n_colors: usize,
+    positions: &[usize],
+) -> PyResult<Py<PyAny>> {
+    use pyo3::exceptions::PyValueError;
+    let width = compressed.shape()[1];
+    if width != n_colors {
+        return Err(PyValueError::new_err(format!(
+            "compressed product must have {n_colors} columns, one per color, got {width}"
+        )));
+    }
+    let data =
+        recovery::gather(compressed.as_slice()?, positions).map_err(PyValueError::new_err)?;
+    Ok(PyArray1::from_vec(py, data).into_any().unbind())
+}
+
+/// Recovered Hessian values of `entries` from a compressed product.
+fn hessian_data<T>(
+    py: Python<'_>,
+    compressed: &PyReadonlyArray2<'_, T>,
+    adjacency: &Adjacency,
+    colors: &[usize],
+    method: HessianRecovery,
+    entries: &[(usize, usize)],
+) -> PyResult<Py<PyAny>>
+where
+    T: Element + Copy + Default + Send + Sync + std::ops::Sub<Output = T>,
+{
+    use pyo3::exceptions::PyValueError;
+    // The flat product alone cannot tell a transposed `(n_colors, n)` array apart.
+    let (n, n_colors) = (adjacency.len(), greedy::color_count(colors));
+    if compressed.shape() != [n, n_colors] {
+        let shape = compressed.shape();
+        return Err(PyValueError::new_err(format!(
+            "compressed product must have shape ({n}, {n_colors}), one column per color, \
+             got ({}, {})",
+            shape[0], shape[1]
+        )));
+    }
+    let compressed = compressed.as_slice()?;
+    let values = py
+        .detach(|| recovery::recover_hessian(adjacency, colors, compressed, method))
+        .map_err(PyValueError::new_err)?;
+    let data = entries
+        .iter()
+        .map(|&(i, j)| {
+            values
+                .get(adjacency, i, j)
+                .ok_or_else(|| PyValueError::new_err(format!("entry ({i}, {j}) out of range")))
+        })
+        .collect::<PyResult<Vec<T>>>()?;
+    Ok(PyArray1::from_vec(py, data).into_any().unbind())
+}
+
+/// Vertices whose conflicts an edit of entries `(rows[e], cols[e])` can change.
+///
+/// Both row and column of a square pattern, only the column of a rectangular one.
+fn edit_endpoints(
+    rows: &IndexArray<'_>,
+    cols: &IndexArray<'_>,
+    n_rows: usize,
+    n_cols: Option<usize>,
+) -> PyResult<Vec<usize>> {
+    use pyo3::exceptions::PyValueError;
+
+    let indices = |array: &IndexArray<'_>| -> PyResult<Vec<usize>> {
+        with_index_slice!(array, values => values.iter().map(|&v| checked_index(v)).collect())
+    };
+    let rows = indices(rows)?;
+    let cols = indices(cols)?;
+    if rows.len() != cols.len() {
+        return Err(PyValueError::new_err(
+            "edited row and column index arrays must have equal length",
+        ));
+    }
+    let n = n_cols.unwrap_or(n_rows);
+    if let Some((&i, &j)) = rows
+        .iter()
+        .zip(&cols)
+        .find(|&(&i, &j)| i >= n_rows || j >= n)
+    {
+        return Err(PyValueError::new_err(format!(
+            "edited entry ({i}, {j}) out of range for a {n_rows} x {n} pattern"
+        )));
+    }
+    Ok(match n_cols {
+        Some(_) => cols,
+        None => rows.into_iter().chain(cols).collect(),
+    })
+}
+
+/// Copy a colors array into color ids, rejecting negative entries.
+fn color_ids(colors: &IndexArray<'_>) -> PyResult<Vec<usize>> {
+    with_index_slice!(colors, colors => colors.iter().map(|&c| checked_index(c)).collect())
+}
+
+/// Copy an optional per-vertex priority array, checking its length.
+fn priority_values(
+    priority: Option<PyReadonlyArray1<'_, f64>>,
+    n_vertices: usize,
+) -> PyResult<Option<Vec<f64>>> {
+    use pyo3::exceptions::PyValueError;
+    let Some(priority) = priority else {
+        return Ok(None);
+    };
+    let priority = priority.as_slice()?;
+    if priority.len() != n_vertices {
+        return Err(PyValueError::new_err(format!(
+            "priority length must be {n_vertices}, got {}",
+            priority.len()
+        )));
+    }
+    Ok(Some(priority.to_vec()))
+}
+
+/// Build 1-hop adjacency lists from CSR storage.
+///
+/// The pointer must hold `n_dofs + 1` entries, start at 0, never decrease and
+/// end at the number of indices, and no entry may be negative; `names` are
+/// the pointer and index arguments the error messages refer to. Below
+/// `Validation::Strict` only the pointer ends are checked (`Fast`), or only
+/// its length (`Off`), and negative indices become out-of-range positions.
+fn csr_adjacency<P: IndexValue, I: IndexValue>(
+    row_ptr: &[P],
+    col_idx: &[I],
+    n_dofs: usize,
+    names: (&str, &str),
+    validation: Validation,
+) -> PyResult<Adjacency> {
+    use pyo3::exceptions::PyValueError;
+
+    let (ptr_name, idx_name) = names;
+    if row_ptr.len() != n_dofs + 1 {
+        return Err(PyValueError::new_err(format!(
+            "{ptr_name} length must be {}, got {}",
+            n_dofs + 1,
+            row_ptr.len()
+        )));
+    }
+    let strict = validation == Validation::Strict;
+    let invalid = |name: &str, k: usize, value: &dyn std::fmt::Display| {
+        PyValueError::new_err(format!("{name}[{k}] = {value} is not a valid index"))
+    };
+    let offsets = row_ptr
+        .iter()
+        .enumerate()
+        .map(|(k, &p)| match p.to_index() {
+            Some(offset) => Ok(offset),
+            None if strict => Err(invalid(ptr_name, k, &p)),
+            None => Ok(usize::MAX),
+        })
+        .collect::<PyResult<Vec<usize>>>()?;
+    if validation != Validation::Off {
+        if offsets[0] != 0 {
+            return Err(PyValueError::new_err(format!(
+                "{ptr_name} must start at 0, got {}",
+                offsets[0]
+            )));
+        }
+        if offsets[n_dofs] != col_idx.len() {
+            return Err(PyValueError::new_err(format!(
+                "{ptr_name} ends at {}, but {idx_name} has {} entries",
+                offsets[n_dofs],
+                col_idx.len()
+            )));
+        }
+    }
+    if strict && let Some(i) = (0..n_dofs).find(|&i| offsets[i] > offsets[i + 1]) {
+        return Err(PyValueError::new_err(format!(
+            "{ptr_name} must be non-decreasing, got {ptr_name}[{i}] = {} > {ptr_name}[{}] = {}",
+            offsets[i],
+            i + 1,
+            offsets[i + 1]
+        )));
+    }
+
+    let mut adjacency = Adjacency::new();
+    for w in offsets.windows(2) {
+        let row = (w[0]..w[1]).map(|k| {
+            let v = col_idx[k];
+            match v.to_index() {
+                Some(j) => Ok(j),
+                None if strict => Err(invalid(idx_name, k, &v)),
+                None => Ok(usize::MAX),
+            }
+        });
+        adjacency.try_push_row(row)?;
+    }
+    Ok(adjacency)
+}
+
+/// Build sorted, duplicate-free 1-hop adjacency lists from COO triplet indices.
+fn coo_adjacency<R: IndexValue, C: IndexValue>(
+    rows: &[R],
+    cols: &[C],
+    n_rows: usize,
+    n_cols: usize,
+) -> PyResult<Adjacency> {
+    use pyo3::exceptions::PyValueError;
+    if rows.len() != cols.len() {
+        return Err(PyValueError::new_err(
+            "COO row and column index arrays must have equal length",
+        ));
+    }
+
+    let pairs: Vec<(usize, usize)> = rows
+        .iter()
+        .zip(cols)
+        .enumerate()
+        .map(|(k, (&i, &j))| {
+            let invalid = |value: &dyn std::fmt::Display| {
+                PyValueError::new_err(format!("entry {k} has invalid index {value}"))
+            };
+            let i = i.to_index().ok_or_else(|| invalid(&i))?;
+            let j = j.to_index().ok_or_else(|| invalid(&j))?;
+            if i >= n_rows {
+                return Err(PyValueError::new_err(format!(
+                    "entry {k} has row index {i}, out of range for {n_rows} rows"
+                )));
+            }
+            if j >= n_cols {
+                return Err(PyValueError::new_err(format!(
+                    "entry {k} has column index {j}, out of range for {n_cols} columns"
+                )));
+            }
+            Ok((i, j))
+        })
+        .collect::<PyResult<_>>()?;
+    let mut adjacency = Adjacency::from_pairs(n_rows, pairs);
+    adjacency.sort_dedup_rows();
+    Ok(adjacency)
+}
+
+/// Row-wise adjacency of an `n_rows x n_cols` pattern given in `format` ("csr", "csc" or "coo").
+///
+/// CSC arrays are read as the CSR storage of the transpose and flipped back,
+/// so callers never need to transpose index arrays themselves. For COO, `ptr`
+/// and `idx` hold the row and column of every entry. With
+/// `Validation::Strict` every index is checked against the shape, raising
+/// `ValueError` with the offending row and value, and the rows come back
+/// sorted with duplicate entries merged; COO input is always checked and
+/// sorted. Weaker validation trusts CSR and CSC input to be canonical.
+fn pattern_rows(
+    ptr: &IndexArray<'_>,
+    idx: &IndexArray<'_>,
+    n_rows: usize,
+    n_cols: usize,
+    format: &str,
+    validation: Validation,
+) -> PyResult<Adjacency> {
+    with_index_slice!(ptr, ptr => with_index_slice!(idx, idx => {
+        typed_pattern_rows(ptr, idx, n_rows, n_cols, format, validation)
+    }))
+}
+
+/// `pattern_rows` for one combination of index dtypes.
+fn typed_pattern_rows<P: IndexValue, I: IndexValue>(
+    ptr: &[P],
+    idx: &[I],
+    n_rows: usize,
+    n_cols: usize,
+    format: &str,
+    validation: Validation,
+) -> PyResult<Adjacency> {
+    let strict = validation == Validation::Strict;
+    let names = ("row_ptr", "col_idx");
+    let mut rows = match format {
+        "csr" => {
+            let rows = csr_adjacency(ptr, idx, n_rows, names, validation)?;
+            if strict {
+                check_indices(&rows, n_cols, "row", "column")?;
+            }
+            rows
+        }
+        "csc" => {
+            let cols = csr_adjacency(ptr, idx, n_cols, names, validation)?;
+            if strict {
+                check_indices(&cols, n_rows, "column", "row")?;
             }
+            bipartite::transpose(&cols, n_rows)
         }
-        let mut c = 0;
-        while used.contains_key(&c) {
-            c += 1;
+        "coo" => return coo_adjacency(ptr, idx, n_rows, n_cols),
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown format '{format}', expected one of: csr, csc, coo"
+            )));
         }
-        colors[i] = c;
+    };
+    // Unsummed COO conversions leave duplicate entries, which would repeat
+    // work in every 2-hop walk, and many assemblies leave rows unsorted; one
+    // parallel pass finds whether either needs fixing, so callers can skip
+    // scipy's `sort_indices` and `sum_duplicates`.
+    if strict && !rows.is_canonical() {
+        rows.sort_dedup_rows();
     }
+    Ok(rows)
+}
+
+/// Raise `ValueError` naming the first `list` with an index not below `bound`.
+///
+/// `list` and `index` name what the lists and their entries stand for, e.g.
+/// "row" and "column" for CSR storage.
+fn check_indices(lists: &Adjacency, bound: usize, list: &str, index: &str) -> PyResult<()> {
+    for (i, entries) in lists.iter().enumerate() {
+        if let Some(&j) = entries.iter().find(|&&j| j >= bound) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{list} {i} has {index} index {j}, out of range for {bound} {index}s"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Number of `block_size x block_size` blocks per dimension of an `n_dofs` pattern.
+fn block_count(n_dofs: usize, block_size: usize) -> PyResult<usize> {
+    if block_size == 0 || !n_dofs.is_multiple_of(block_size) {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "block_size must be positive and divide n_dofs",
+        ));
+    }
+    Ok(n_dofs / block_size)
+}
+
+/// Expand block colors to scalar DOFs: DOF `i * b + r` gets color `colors[i] * b + r`.
+///
+/// The DOFs of one block are mutually coupled, so each keeps its own color
+/// within the block's group of `b` colors.
+fn expand_blocks(colors: &[usize], block_size: usize) -> Vec<usize> {
     colors
+        .iter()
+        .flat_map(|&c| (0..block_size).map(move |r| c * block_size + r))
+        .collect()
+}
+
+/// Reduce per-DOF priorities to per-block priorities (the block maximum).
+fn block_maxima(priority: &[f64], block_size: usize) -> Vec<f64> {
+    priority
+        .chunks(block_size)
+        .map(|block| block.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+        .collect()
 }
 
-/// Generate one-hot seeds per color (bool).
-fn seeds_from_colors(py: Python<'_>, colors: &[usize]) -> PyResult<Vec<Py<PyArray1<bool>>>> {
-    if colors.is_empty() {
-        return Ok(Vec::new());
+/// Element-to-node lists from ragged CSR connectivity, with the node count.
+fn element_lists(
+    elem_ptr: &IndexArray<'_>,
+    elem_nodes: &IndexArray<'_>,
+    n_nodes: Option<usize>,
+) -> PyResult<(Adjacency, usize)> {
+    let elements = with_index_slice!(elem_ptr, ptr => with_index_slice!(elem_nodes, nodes => {
+        csr_adjacency(
+            ptr,
+            nodes,
+            ptr.len().saturating_sub(1),
+            ("elem_ptr", "elem_nodes"),
+            Validation::Strict,
+        )?
+    }));
+    let max_node = elements.iter().flatten().max().map_or(0, |&v| v + 1);
+    let n_nodes = n_nodes.unwrap_or(max_node);
+    if max_node > n_nodes {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "connectivity index {} out of range for {n_nodes} nodes",
+            max_node - 1
+        )));
     }
-    let max_color = *colors.iter().max().unwrap_or(&0);
-    let n_colors = max_color + 1;
-    let n = colors.len();
+    Ok((elements, n_nodes))
+}
 
-    let mut seeds = Vec::with_capacity(n_colors);
-    for color in 0..n_colors {
-        let mut seed = vec![false; n];
-        for (idx, &c) in colors.iter().enumerate() {
-            if c == color {
-                seed[idx] = true;
+/// Sorted, symmetric adjacency without self loops from flattened `(u, v)` edge pairs.
+///
+/// `n_vertices` defaults to the largest vertex index + 1.
+fn edge_adjacency<I: IndexValue>(edges: &[I], n_vertices: Option<usize>) -> PyResult<Adjacency> {
+    use pyo3::exceptions::PyValueError;
+    if !edges.len().is_multiple_of(2) {
+        return Err(PyValueError::new_err("edges must have shape (n_edges, 2)"));
+    }
+
+    let pairs: Vec<(usize, usize)> = edges
+        .chunks_exact(2)
+        .map(|pair| Ok((checked_index(pair[0])?, checked_index(pair[1])?)))
+        .collect::<PyResult<_>>()?;
+    let max_vertex = pairs.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+    let n_vertices = n_vertices.unwrap_or(max_vertex);
+    if max_vertex > n_vertices {
+        return Err(PyValueError::new_err(format!(
+            "edge vertex {} out of range for {n_vertices} vertices",
+            max_vertex - 1
+        )));
+    }
+
+    let mut adjacency = Adjacency::from_pairs(
+        n_vertices,
+        pairs
+            .iter()
+            .filter(|&&(u, v)| u != v)
+            .flat_map(|&(u, v)| [(u, v), (v, u)]),
+    );
+    adjacency.sort_dedup_rows();
+    Ok(adjacency)
+}
+
+/// Mirror 1-hop adjacency into sorted, symmetric lists without self loops.
+fn symmetric_adjacency(adjacency: &Adjacency) -> Adjacency {
+    let entries = adjacency.iter().enumerate().flat_map(|(i, neighs)| {
+        neighs
+            .iter()
+            .filter(move |&&j| j != i)
+            .flat_map(move |&j| [(i, j), (j, i)])
+    });
+    let mut sym = Adjacency::from_pairs(adjacency.len(), entries);
+    sym.sort_dedup_rows();
+    sym
+}
+
+/// Build the distance-k graph by breadth-first search to depth `k` from every vertex.
+///
+/// Expects symmetric adjacency; returns sorted neighbor lists without self loops.
+fn distance_k_adjacency(adjacency: &Adjacency, k: usize) -> Adjacency {
+    let n = adjacency.len();
+    // seen[v] == source marks v as already reached from source.
+    let mut seen = vec![usize::MAX; n];
+    let mut frontier: Vec<usize> = Vec::new();
+    let mut next: Vec<usize> = Vec::new();
+    let mut neighs: Vec<usize> = Vec::new();
+    let mut adjacency_k = Adjacency::new();
+
+    for source in 0..n {
+        seen[source] = source;
+        neighs.clear();
+        frontier.clear();
+        frontier.push(source);
+        for _ in 0..k {
+            next.clear();
+            for &v in &frontier {
+                for &w in &adjacency[v] {
+                    if seen[w] != source {
+                        seen[w] = source;
+                        next.push(w);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
             }
+            neighs.extend_from_slice(&next);
+            std::mem::swap(&mut frontier, &mut next);
         }
-        seeds.push(PyArray1::from_vec(py, seed).unbind());
+        neighs.sort_unstable();
+        adjacency_k.push_row(neighs.iter().copied());
     }
-    Ok(seeds)
+    adjacency_k
 }
 
 /// Python module definition.
@@ -140,5 +2812,36 @@ fn seeds_from_colors(py: Python<'_>, colors: &[usize]) -> PyResult<Vec<Py<PyArra
 fn _tatva_coloring(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(distance2_color_and_seeds, m)?)?;
     m.add_function(wrap_pyfunction!(distance2_colors, m)?)?;
+    m.add_function(wrap_pyfunction!(distance2_memory_estimate, m)?)?;
+    m.add_function(wrap_pyfunction!(distance2_adjacency, m)?)?;
+    m.add_function(wrap_pyfunction!(save_metis_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(rcm_permutation, m)?)?;
+    m.add_function(wrap_pyfunction!(distance2_lower_bound, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_distance2_colors, m)?)?;
+    m.add_function(wrap_pyfunction!(distance1_color, m)?)?;
+    m.add_function(wrap_pyfunction!(distance_k_color, m)?)?;
+    m.add_function(wrap_pyfunction!(star_color_and_seeds, m)?)?;
+    m.add_function(wrap_pyfunction!(restricted_star_color_and_seeds, m)?)?;
+    m.add_function(wrap_pyfunction!(acyclic_color_and_seeds, m)?)?;
+    m.add_function(wrap_pyfunction!(partial_distance2_color_and_seeds, m)?)?;
+    m.add_function(wrap_pyfunction!(partitioned_distance2_colors, m)?)?;
+    m.add_function(wrap_pyfunction!(cpr_column_groups, m)?)?;
+    m.add_function(wrap_pyfunction!(bicolor, m)?)?;
+    m.add_function(wrap_pyfunction!(color_matrix_market, m)?)?;
+    m.add_function(wrap_pyfunction!(pattern_from_elements, m)?)?;
+    m.add_function(wrap_pyfunction!(normal_pattern, m)?)?;
+    m.add_function(wrap_pyfunction!(element_distance2_colors, m)?)?;
+    m.add_function(wrap_pyfunction!(cell_colors, m)?)?;
+    m.add_function(wrap_pyfunction!(color_edge_list, m)?)?;
+    m.add_function(wrap_pyfunction!(color_permutation, m)?)?;
+    m.add_function(wrap_pyfunction!(petsc_coloring, m)?)?;
+    m.add_function(wrap_pyfunction!(coloring_conflicts, m)?)?;
+    m.add_function(wrap_pyfunction!(orthogonality_violations, m)?)?;
+    m.add_function(wrap_pyfunction!(recovery_map, m)?)?;
+    m.add_function(wrap_pyfunction!(decompress_jacobian, m)?)?;
+    m.add_function(wrap_pyfunction!(recover_hessian, m)?)?;
+    m.add_class::<ColoringGraph>()?;
+    m.add_class::<HaloGraph>()?;
+    m.add_class::<ColoringStats>()?;
     Ok(())
 }
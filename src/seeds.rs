@@ -0,0 +1,315 @@
+//! Seed matrix layouts handed back to Python.
+
+use numpy::ndarray::Array2;
+use numpy::{Complex64, Element, IntoPyArray, PyArray1, PyReadwriteArray1, PyReadwriteArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::{greedy, rng};
+
+/// Layout of the seed matrix `S` with `S[i, c] = 1` iff `colors[i] == c`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SeedFormat {
+    /// One 1D seed vector per color.
+    List,
+    /// One contiguous `(n, n_colors)` array.
+    Dense,
+    /// `(data, indices, indptr)` of the `(n, n_colors)` CSR matrix, one entry per row.
+    Csr,
+    /// Index array of the nonzero rows of each column, i.e. of each color class.
+    Groups,
+}
+
+impl SeedFormat {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "list" => Ok(Self::List),
+            "dense" => Ok(Self::Dense),
+            "csr" => Ok(Self::Csr),
+            "groups" => Ok(Self::Groups),
+            _ => Err(format!(
+                "unknown seed_format '{name}', expected one of: list, dense, csr, groups"
+            )),
+        }
+    }
+}
+
+/// Element type of the seed matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SeedDtype {
+    Bool,
+    Float32,
+    Float64,
+    Complex128,
+}
+
+impl SeedDtype {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "bool" => Ok(Self::Bool),
+            "float32" => Ok(Self::Float32),
+            "float64" => Ok(Self::Float64),
+            "complex128" => Ok(Self::Complex128),
+            _ => Err(format!(
+                "unknown seed_dtype '{name}', expected one of: bool, float32, float64, complex128"
+            )),
+        }
+    }
+}
+
+/// How the seed matrix is returned: layout, value type and nonzero values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SeedOptions {
+    pub(crate) format: SeedFormat,
+    pub(crate) dtype: SeedDtype,
+    /// Draw each nonzero as +1 or -1 (Rademacher) from this seed instead of 1.
+    pub(crate) rademacher_seed: Option<u64>,
+}
+
+impl SeedOptions {
+    pub(crate) fn parse(
+        format: &str,
+        dtype: &str,
+        rademacher_seed: Option<u64>,
+    ) -> Result<Self, String> {
+        let options = Self {
+            format: SeedFormat::parse(format)?,
+            dtype: SeedDtype::parse(dtype)?,
+            rademacher_seed,
+        };
+        if rademacher_seed.is_some() {
+            if options.format == SeedFormat::Groups {
+                return Err("rademacher_seed does not apply to seed_format 'groups'".into());
+            }
+            if options.dtype == SeedDtype::Bool {
+                return Err("rademacher_seed requires a float or complex seed_dtype".into());
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Value of the single nonzero in row `i` of the seed matrix, as a float.
+fn sign(options: SeedOptions, i: usize) -> f64 {
+    match options.rademacher_seed {
+        Some(seed) if rng::hash64(seed, i as u64) & 1 == 1 => -1.0,
+        _ => 1.0,
+    }
+}
+
+/// Build the seed matrix for `colors` as described by `options`.
+///
+/// Class index groups carry no values and are always int64.
+pub(crate) fn seeds_to_py(
+    py: Python<'_>,
+    colors: &[usize],
+    options: SeedOptions,
+) -> PyResult<Py<PyAny>> {
+    let sign = |i| sign(options, i);
+    let format = options.format;
+    match options.dtype {
+        SeedDtype::Bool => typed_seeds(py, colors, format, |_| true),
+        SeedDtype::Float32 => typed_seeds(py, colors, format, |i| sign(i) as f32),
+        SeedDtype::Float64 => typed_seeds(py, colors, format, sign),
+        SeedDtype::Complex128 => typed_seeds(py, colors, format, |i| Complex64::new(sign(i), 0.0)),
+    }
+}
+
+/// Seed matrix with `value(i)` at row `i`, column `colors[i]`.
+///
+/// The seeds are written in parallel without the GIL: one task per color for
+/// the list layout and per entry range for the dense and CSR layouts, so the
+/// zero fill is spread over all threads too.
+fn typed_seeds<T: Element + Copy + Default + Sync>(
+    py: Python<'_>,
+    colors: &[usize],
+    format: SeedFormat,
+    value: impl Fn(usize) -> T + Sync,
+) -> PyResult<Py<PyAny>> {
+    let n = colors.len();
+    let n_colors = greedy::color_count(colors);
+    match format {
+        SeedFormat::List => {
+            let seeds: Vec<Vec<T>> = py.detach(|| {
+                greedy::color_classes(colors)
+                    .par_iter()
+                    .map(|class| {
+                        let mut seed = vec![T::default(); n];
+                        for &i in class {
+                            seed[i] = value(i);
+                        }
+                        seed
+                    })
+                    .collect()
+            });
+            let seeds: Vec<Py<PyArray1<T>>> = seeds
+                .into_iter()
+                .map(|seed| PyArray1::from_vec(py, seed).unbind())
+                .collect();
+            Ok(seeds.into_pyobject(py)?.unbind())
+        }
+        SeedFormat::Dense => {
+            let data: Vec<T> = py.detach(|| {
+                (0..n * n_colors)
+                    .into_par_iter()
+                    .map(|k| {
+                        let i = k / n_colors;
+                        if colors[i] == k % n_colors {
+                            value(i)
+                        } else {
+                            T::default()
+                        }
+                    })
+                    .collect()
+            });
+            let seeds =
+                Array2::from_shape_vec((n, n_colors), data).expect("one entry per row and color");
+            Ok(seeds.into_pyarray(py).into_any().unbind())
+        }
+        SeedFormat::Csr => {
+            let (data, indices): (Vec<T>, Vec<i64>) = py.detach(|| {
+                let data = (0..n).into_par_iter().map(&value).collect();
+                let indices = colors.par_iter().map(|&c| c as i64).collect();
+                (data, indices)
+            });
+            let data = PyArray1::from_vec(py, data);
+            let indices = PyArray1::from_vec(py, indices);
+            let indptr = PyArray1::from_iter(py, (0..=n).map(|i| i as i64));
+            Ok((data, indices, indptr)
+                .into_pyobject(py)?
+                .into_any()
+                .unbind())
+        }
+        SeedFormat::Groups => Ok(groups_from_colors(py, colors).into_pyobject(py)?.unbind()),
+    }
+}
+
+/// `seeds_to_py`, or filling and returning the caller's `out` if given.
+pub(crate) fn seeds_into_py(
+    py: Python<'_>,
+    colors: &[usize],
+    options: SeedOptions,
+    out: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    match out {
+        Some(out) => {
+            fill_seeds(colors, options, out)?;
+            Ok(out.clone().unbind())
+        }
+        None => seeds_to_py(py, colors, options),
+    }
+}
+
+/// Write the seed matrix for `colors` into `out` instead of allocating it.
+///
+/// `out` is a writable `(n, n_colors)` array for the dense layout or a sequence
+/// of `n_colors` writable length-`n` arrays for the list layout, of the seed
+/// dtype; strided views are fine. Every entry is overwritten.
+fn fill_seeds(colors: &[usize], options: SeedOptions, out: &Bound<'_, PyAny>) -> PyResult<()> {
+    let sign = |i| sign(options, i);
+    let format = options.format;
+    match options.dtype {
+        SeedDtype::Bool => fill_typed_seeds(colors, format, out, |_| true),
+        SeedDtype::Float32 => fill_typed_seeds(colors, format, out, |i| sign(i) as f32),
+        SeedDtype::Float64 => fill_typed_seeds(colors, format, out, sign),
+        SeedDtype::Complex128 => {
+            fill_typed_seeds(colors, format, out, |i| Complex64::new(sign(i), 0.0))
+        }
+    }
+}
+
+/// `fill_seeds` for seeds of type `T`, in parallel as in `typed_seeds`.
+///
+/// Only a C-contiguous dense `out` is filled row-parallel; other strides fall
+/// back to one thread.
+fn fill_typed_seeds<T: Element + Copy + Default + Sync>(
+    colors: &[usize],
+    format: SeedFormat,
+    out: &Bound<'_, PyAny>,
+    value: impl Fn(usize) -> T + Sync,
+) -> PyResult<()> {
+    let py = out.py();
+    let n = colors.len();
+    let n_colors = greedy::color_count(colors);
+    match format {
+        SeedFormat::List => {
+            let mut seeds = out
+                .try_iter()?
+                .map(|seed| {
+                    let seed = seed?.extract::<PyReadwriteArray1<'_, T>>().map_err(|_| {
+                        PyValueError::new_err("out must hold writable 1D arrays of seed_dtype")
+                    })?;
+                    let len = seed.as_array().len();
+                    if len != n {
+                        return Err(PyValueError::new_err(format!(
+                            "out arrays must have length {n}, got {len}"
+                        )));
+                    }
+                    Ok(seed)
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            if seeds.len() != n_colors {
+                return Err(PyValueError::new_err(format!(
+                    "out must hold {n_colors} arrays, one per color, got {}",
+                    seeds.len()
+                )));
+            }
+            let mut seeds: Vec<_> = seeds.iter_mut().map(|seed| seed.as_array_mut()).collect();
+            py.detach(|| {
+                let classes = greedy::color_classes(colors);
+                seeds
+                    .par_iter_mut()
+                    .zip(&classes)
+                    .for_each(|(seed, class)| {
+                        seed.fill(T::default());
+                        for &i in class {
+                            seed[i] = value(i);
+                        }
+                    });
+            });
+        }
+        SeedFormat::Dense => {
+            let mut seeds = out.extract::<PyReadwriteArray2<'_, T>>().map_err(|_| {
+                PyValueError::new_err("out must be a writable 2D array of seed_dtype")
+            })?;
+            let mut seeds = seeds.as_array_mut();
+            if seeds.dim() != (n, n_colors) {
+                return Err(PyValueError::new_err(format!(
+                    "out must have shape ({n}, {n_colors}), got {:?}",
+                    seeds.dim()
+                )));
+            }
+            py.detach(|| match seeds.as_slice_mut() {
+                Some(entries) => entries
+                    .par_chunks_mut(n_colors.max(1))
+                    .zip(colors)
+                    .enumerate()
+                    .for_each(|(i, (row, &c))| {
+                        row.fill(T::default());
+                        row[c] = value(i);
+                    }),
+                None => {
+                    seeds.fill(T::default());
+                    for (i, &c) in colors.iter().enumerate() {
+                        seeds[(i, c)] = value(i);
+                    }
+                }
+            });
+        }
+        SeedFormat::Csr | SeedFormat::Groups => {
+            return Err(PyValueError::new_err(
+                "out requires seed_format 'list' or 'dense'",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Index array of each color class (int64).
+pub(crate) fn groups_from_colors(py: Python<'_>, colors: &[usize]) -> Vec<Py<PyArray1<i64>>> {
+    greedy::color_classes(colors)
+        .into_iter()
+        .map(|class| PyArray1::from_iter(py, class.into_iter().map(|v| v as i64)).unbind())
+        .collect()
+}
@@ -0,0 +1,62 @@
+//! Summary statistics of a coloring and the conflict graph it was computed on.
+
+use pyo3::prelude::*;
+
+use crate::greedy;
+
+/// Color class sizes of a coloring plus the size of its conflict graph.
+#[pyclass(frozen, get_all, module = "tatva_coloring")]
+#[derive(Clone, Debug)]
+pub(crate) struct ColoringStats {
+    /// Number of colors.
+    n_colors: usize,
+    /// Size of the smallest color class.
+    min_class_size: usize,
+    /// Size of the largest color class.
+    max_class_size: usize,
+    /// Mean color class size.
+    mean_class_size: f64,
+    /// Largest degree of a DOF in the conflict graph of the DOFs.
+    max_degree: usize,
+    /// Stored entries of the conflict graph of the DOFs, i.e. twice its edge count.
+    graph_nnz: usize,
+}
+
+impl ColoringStats {
+    /// Statistics of the DOF `colors` on a conflict graph with the given DOF degrees.
+    ///
+    /// `degrees` refer to the DOFs even if a condensed graph was colored, so
+    /// that both describe the same graph.
+    pub(crate) fn new(colors: &[usize], degrees: &[usize]) -> Self {
+        let sizes: Vec<usize> = greedy::color_classes(colors).iter().map(Vec::len).collect();
+        let n_colors = sizes.len();
+        Self {
+            n_colors,
+            min_class_size: sizes.iter().copied().min().unwrap_or(0),
+            max_class_size: sizes.iter().copied().max().unwrap_or(0),
+            mean_class_size: if n_colors == 0 {
+                0.0
+            } else {
+                colors.len() as f64 / n_colors as f64
+            },
+            max_degree: degrees.iter().copied().max().unwrap_or(0),
+            graph_nnz: degrees.iter().sum(),
+        }
+    }
+}
+
+#[pymethods]
+impl ColoringStats {
+    fn __repr__(&self) -> String {
+        format!(
+            "ColoringStats(n_colors={}, min_class_size={}, max_class_size={}, \
+             mean_class_size={:.2}, max_degree={}, graph_nnz={})",
+            self.n_colors,
+            self.min_class_size,
+            self.max_class_size,
+            self.mean_class_size,
+            self.max_degree,
+            self.graph_nnz,
+        )
+    }
+}